@@ -3,7 +3,7 @@ use crate::state::CONFIG;
 use crate::{execute, query, ContractError};
 use account_tracker::{Config, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
 };
 use cw_utils::nonpayable;
 
@@ -61,6 +61,10 @@ pub fn execute(
             nonpayable(&info).unwrap();
             execute::account::free_funding_account(deps, data)
         }
+        ExecuteMsg::CloseFundingAccount(data) => {
+            nonpayable(&info).unwrap();
+            execute::account::close_funding_account(deps, data)
+        }
         ExecuteMsg::UpdateConfig(data) => update_config(deps, env, info, data),
     }
 }
@@ -68,25 +72,30 @@ pub fn execute(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::QueryConfig(_) => to_binary(&query::account::query_config(deps)?),
-        QueryMsg::QueryAccounts(data) => to_binary(&query::account::query_accounts(deps, data)?),
+        QueryMsg::QueryConfig(_) => to_json_binary(&query::account::query_config(deps)?),
+        QueryMsg::QueryAccounts(data) => {
+            to_json_binary(&query::account::query_accounts(deps, data)?)
+        }
         QueryMsg::QueryFundingAccounts(data) => {
-            to_binary(&query::account::query_funding_accounts(deps, data)?)
+            to_json_binary(&query::account::query_funding_accounts(deps, data)?)
         }
         QueryMsg::QueryFundingAccount(data) => {
-            to_binary(&query::account::query_funding_account(deps, data)?)
+            to_json_binary(&query::account::query_funding_account(deps, data)?)
         }
-        QueryMsg::QueryFirstFreeFundingAccount(data) => to_binary(
+        QueryMsg::QueryFirstFreeFundingAccount(data) => to_json_binary(
             &query::account::query_first_free_funding_account(deps, data)?,
         ),
         QueryMsg::QueryJobAccounts(data) => {
-            to_binary(&query::account::query_job_accounts(deps, data)?)
+            to_json_binary(&query::account::query_job_accounts(deps, data)?)
         }
         QueryMsg::QueryJobAccount(data) => {
-            to_binary(&query::account::query_job_account(deps, data)?)
+            to_json_binary(&query::account::query_job_account(deps, data)?)
         }
         QueryMsg::QueryFirstFreeJobAccount(data) => {
-            to_binary(&query::account::query_first_free_job_account(deps, data)?)
+            to_json_binary(&query::account::query_first_free_job_account(deps, data)?)
+        }
+        QueryMsg::QueryJobAccountBalances(data) => {
+            to_json_binary(&query::account::query_job_account_balances(deps, data)?)
         }
     }
 }
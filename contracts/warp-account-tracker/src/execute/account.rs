@@ -3,8 +3,8 @@ use crate::state::{
 };
 use crate::ContractError;
 use account_tracker::{
-    Account, AccountType, FreeFundingAccountMsg, FreeJobAccountMsg, TakeFundingAccountMsg,
-    TakeJobAccountMsg,
+    Account, AccountType, CloseFundingAccountMsg, FreeFundingAccountMsg, FreeJobAccountMsg,
+    TakeFundingAccountMsg, TakeJobAccountMsg,
 };
 use cosmwasm_std::{DepsMut, Response, Uint64};
 
@@ -203,3 +203,31 @@ pub fn free_funding_account(
         .add_attribute("account_addr", data.account_addr)
         .add_attribute("job_id", data.job_id.to_string()))
 }
+
+pub fn close_funding_account(
+    deps: DepsMut,
+    data: CloseFundingAccountMsg,
+) -> Result<Response, ContractError> {
+    let account_owner_addr_ref = &deps.api.addr_validate(&data.account_owner_addr)?;
+    let account_addr_ref = &deps.api.addr_validate(&data.account_addr)?;
+
+    let account = ACCOUNTS
+        .may_load(deps.storage, (account_owner_addr_ref, account_addr_ref))?
+        .ok_or(ContractError::AccountNotFound {})?;
+
+    if account.account_type != AccountType::Funding {
+        return Err(ContractError::InvalidAccountType {});
+    }
+
+    // Funding account can only be closed once no job uses it
+    if TAKEN_FUNDING_ACCOUNTS.has(deps.storage, (account_owner_addr_ref, account_addr_ref)) {
+        return Err(ContractError::AccountAlreadyTakenError {});
+    }
+
+    FREE_FUNDING_ACCOUNTS.remove(deps.storage, (account_owner_addr_ref, account_addr_ref));
+    ACCOUNTS.remove(deps.storage, (account_owner_addr_ref, account_addr_ref));
+
+    Ok(Response::new()
+        .add_attribute("action", "close_funding_account")
+        .add_attribute("account_addr", data.account_addr))
+}
@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod tests {
     use account_tracker::{
-        AccountStatus, Config, ConfigResponse, ExecuteMsg, FreeJobAccountMsg, InstantiateMsg,
-        JobAccount, JobAccountResponse, JobAccountsResponse, QueryConfigMsg,
-        QueryFirstFreeJobAccountMsg, QueryJobAccountsMsg, QueryMsg, TakeJobAccountMsg,
+        AccountStatus, CloseFundingAccountMsg, Config, ConfigResponse, ExecuteMsg,
+        FreeFundingAccountMsg, FreeJobAccountMsg, FundingAccountResponse, InstantiateMsg,
+        JobAccount, JobAccountBalances, JobAccountBalancesResponse, JobAccountResponse,
+        JobAccountsResponse, QueryConfigMsg, QueryFirstFreeJobAccountMsg, QueryFundingAccountMsg,
+        QueryJobAccountBalancesMsg, QueryJobAccountsMsg, QueryMsg, TakeFundingAccountMsg,
+        TakeJobAccountMsg,
     };
     use anyhow::Result as AnyResult;
     use cosmwasm_std::{Addr, Coin, Empty, Uint128, Uint64};
@@ -385,4 +388,203 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn warp_account_tracker_contract_multi_test_close_funding_account() {
+        let mut app = mock_app();
+        let warp_account_tracker_contract_code_id = app.store_code(contract_warp_account_tracker());
+        let warp_account_tracker_contract_addr =
+            init_warp_account_tracker(&mut app, warp_account_tracker_contract_code_id);
+
+        let close_msg = ExecuteMsg::CloseFundingAccount(CloseFundingAccountMsg {
+            account_owner_addr: USER_1.to_string(),
+            account_addr: DUMMY_WARP_ACCOUNT_1_ADDR.to_string(),
+        });
+
+        // Unknown account cannot be closed
+        assert_err(
+            app.execute_contract(
+                Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+                warp_account_tracker_contract_addr.clone(),
+                &close_msg,
+                &[],
+            ),
+            ContractError::AccountNotFound {},
+        );
+
+        // Job account cannot be closed
+        let _ = app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &ExecuteMsg::TakeJobAccount(TakeJobAccountMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_addr: DUMMY_WARP_ACCOUNT_2_ADDR.to_string(),
+                job_id: DUMMY_JOB_1_ID,
+            }),
+            &[],
+        );
+        assert_err(
+            app.execute_contract(
+                Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+                warp_account_tracker_contract_addr.clone(),
+                &ExecuteMsg::CloseFundingAccount(CloseFundingAccountMsg {
+                    account_owner_addr: USER_1.to_string(),
+                    account_addr: DUMMY_WARP_ACCOUNT_2_ADDR.to_string(),
+                }),
+                &[],
+            ),
+            ContractError::InvalidAccountType {},
+        );
+
+        // Funding account taken by a job cannot be closed
+        let _ = app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &ExecuteMsg::TakeFundingAccount(TakeFundingAccountMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_addr: DUMMY_WARP_ACCOUNT_1_ADDR.to_string(),
+                job_id: DUMMY_JOB_1_ID,
+            }),
+            &[],
+        );
+        assert_err(
+            app.execute_contract(
+                Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+                warp_account_tracker_contract_addr.clone(),
+                &close_msg,
+                &[],
+            ),
+            ContractError::AccountAlreadyTakenError {},
+        );
+
+        // Free funding account can be closed
+        let _ = app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &ExecuteMsg::FreeFundingAccount(FreeFundingAccountMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_addr: DUMMY_WARP_ACCOUNT_1_ADDR.to_string(),
+                job_id: DUMMY_JOB_1_ID,
+            }),
+            &[],
+        );
+        app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &close_msg,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            app.wrap().query_wasm_smart(
+                warp_account_tracker_contract_addr,
+                &QueryMsg::QueryFundingAccount(QueryFundingAccountMsg {
+                    account_owner_addr: USER_1.to_string(),
+                    account_addr: DUMMY_WARP_ACCOUNT_1_ADDR.to_string(),
+                })
+            ),
+            Ok(FundingAccountResponse {
+                funding_account: None
+            })
+        );
+    }
+
+    #[test]
+    fn warp_account_tracker_contract_multi_test_job_account_balances() {
+        let mut app = mock_app();
+        let warp_account_tracker_contract_code_id = app.store_code(contract_warp_account_tracker());
+        let warp_account_tracker_contract_addr =
+            init_warp_account_tracker(&mut app, warp_account_tracker_contract_code_id);
+
+        // First account is freed, second one is taken, third one is a funding account
+        let _ = app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &ExecuteMsg::FreeJobAccount(FreeJobAccountMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_addr: DUMMY_WARP_ACCOUNT_1_ADDR.to_string(),
+                last_job_id: DUMMY_JOB_1_ID,
+            }),
+            &[],
+        );
+        let _ = app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &ExecuteMsg::TakeJobAccount(TakeJobAccountMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_addr: DUMMY_WARP_ACCOUNT_2_ADDR.to_string(),
+                job_id: DUMMY_JOB_2_ID,
+            }),
+            &[],
+        );
+        let _ = app.execute_contract(
+            Addr::unchecked(DUMMY_WARP_CONTROLLER_ADDR),
+            warp_account_tracker_contract_addr.clone(),
+            &ExecuteMsg::FreeFundingAccount(FreeFundingAccountMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_addr: DUMMY_WARP_ACCOUNT_3_ADDR.to_string(),
+                job_id: DUMMY_JOB_1_ID,
+            }),
+            &[],
+        );
+
+        let funds = vec![Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1_000),
+        }];
+        app.send_tokens(
+            Addr::unchecked(USER_1),
+            Addr::unchecked(DUMMY_WARP_ACCOUNT_1_ADDR),
+            &funds,
+        )
+        .unwrap();
+
+        let query_msg = |account_status: Option<AccountStatus>| {
+            QueryMsg::QueryJobAccountBalances(QueryJobAccountBalancesMsg {
+                account_owner_addr: USER_1.to_string(),
+                account_status,
+                cw20_addrs: vec![],
+                start_after: None,
+                limit: None,
+            })
+        };
+
+        assert_eq!(
+            app.wrap()
+                .query_wasm_smart(warp_account_tracker_contract_addr.clone(), &query_msg(None)),
+            Ok(JobAccountBalancesResponse {
+                job_accounts: vec![
+                    JobAccountBalances {
+                        account_addr: Addr::unchecked(DUMMY_WARP_ACCOUNT_1_ADDR),
+                        account_status: AccountStatus::Free,
+                        job_id: DUMMY_JOB_1_ID,
+                        native_balances: funds,
+                        cw20_balances: vec![],
+                    },
+                    JobAccountBalances {
+                        account_addr: Addr::unchecked(DUMMY_WARP_ACCOUNT_2_ADDR),
+                        account_status: AccountStatus::Taken,
+                        job_id: DUMMY_JOB_2_ID,
+                        native_balances: vec![],
+                        cw20_balances: vec![],
+                    },
+                ],
+                total_count: 2
+            })
+        );
+
+        let resp: JobAccountBalancesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                warp_account_tracker_contract_addr,
+                &query_msg(Some(AccountStatus::Taken)),
+            )
+            .unwrap();
+        assert_eq!(resp.total_count, 1);
+        assert_eq!(
+            resp.job_accounts[0].account_addr,
+            Addr::unchecked(DUMMY_WARP_ACCOUNT_2_ADDR)
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Deps, Order, StdResult};
+use cosmwasm_std::{Addr, Deps, Order, StdResult};
 use cw_storage_plus::{Bound, PrefixBound};
 
 use crate::state::{
@@ -7,10 +7,11 @@ use crate::state::{
 };
 
 use account_tracker::{
-    Account, AccountStatus, AccountsResponse, ConfigResponse, FundingAccount,
-    FundingAccountResponse, FundingAccountsResponse, JobAccount, JobAccountResponse,
-    JobAccountsResponse, QueryAccountsMsg, QueryFirstFreeFundingAccountMsg,
-    QueryFirstFreeJobAccountMsg, QueryFundingAccountMsg, QueryFundingAccountsMsg,
+    Account, AccountStatus, AccountType, AccountsResponse, ConfigResponse, Cw20Balance,
+    FundingAccount, FundingAccountResponse, FundingAccountsResponse, JobAccount,
+    JobAccountBalances, JobAccountBalancesResponse, JobAccountResponse, JobAccountsResponse,
+    QueryAccountsMsg, QueryFirstFreeFundingAccountMsg, QueryFirstFreeJobAccountMsg,
+    QueryFundingAccountMsg, QueryFundingAccountsMsg, QueryJobAccountBalancesMsg,
     QueryJobAccountMsg, QueryJobAccountsMsg,
 };
 
@@ -286,3 +287,94 @@ pub fn query_first_free_job_account(
         job_account: resp.job_accounts.first().cloned(),
     })
 }
+
+pub fn query_job_account_balances(
+    deps: Deps,
+    data: QueryJobAccountBalancesMsg,
+) -> StdResult<JobAccountBalancesResponse> {
+    let account_owner_ref = &deps.api.addr_validate(data.account_owner_addr.as_str())?;
+    let start_after = data
+        .start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let cw20_addrs = data
+        .cw20_addrs
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let iter = match start_after {
+        Some(start_after_addr) => ACCOUNTS.range(
+            deps.storage,
+            Some(Bound::exclusive((account_owner_ref, &start_after_addr))),
+            None,
+            Order::Ascending,
+        ),
+        None => ACCOUNTS.prefix_range(
+            deps.storage,
+            Some(PrefixBound::inclusive(account_owner_ref)),
+            Some(PrefixBound::inclusive(account_owner_ref)),
+            Order::Ascending,
+        ),
+    };
+
+    let mut job_accounts = vec![];
+    for item in iter {
+        if job_accounts.len() >= data.limit.unwrap_or(QUERY_LIMIT) as usize {
+            break;
+        }
+
+        let (_, account) = item?;
+        // accounts are ordered by owner first
+        if account.owner_addr != *account_owner_ref {
+            break;
+        }
+
+        if account.account_type != AccountType::Job {
+            continue;
+        }
+
+        let key = (account_owner_ref, &account.account_addr);
+        let (account_status, job_id) = match TAKEN_JOB_ACCOUNTS.may_load(deps.storage, key)? {
+            Some(job_id) => (AccountStatus::Taken, job_id),
+            None => match FREE_JOB_ACCOUNTS.may_load(deps.storage, key)? {
+                Some(job_id) => (AccountStatus::Free, job_id),
+                None => continue,
+            },
+        };
+
+        if data.account_status.is_some() && data.account_status != Some(account_status.clone()) {
+            continue;
+        }
+
+        let mut cw20_balances = vec![];
+        for cw20_addr in &cw20_addrs {
+            let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                cw20_addr,
+                &cw20::Cw20QueryMsg::Balance {
+                    address: account.account_addr.to_string(),
+                },
+            )?;
+
+            if !balance.balance.is_zero() {
+                cw20_balances.push(Cw20Balance {
+                    contract_addr: cw20_addr.clone(),
+                    balance: balance.balance,
+                });
+            }
+        }
+
+        job_accounts.push(JobAccountBalances {
+            native_balances: deps.querier.query_all_balances(&account.account_addr)?,
+            account_addr: account.account_addr,
+            account_status,
+            job_id,
+            cw20_balances,
+        });
+    }
+
+    Ok(JobAccountBalancesResponse {
+        total_count: job_accounts.len() as u32,
+        job_accounts,
+    })
+}
@@ -1,9 +1,11 @@
-use crate::state::CONFIG;
-use crate::{query, ContractError};
+use crate::execute::grant::validate_granted_msgs;
+use crate::state::{CONFIG, GRANTS, POLICY};
+use crate::{execute, query, ContractError};
 use account::{Config, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use controller::account::{execute_warp_msgs, warp_msgs_to_cosmos_msgs};
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdResult,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -21,7 +23,7 @@ pub fn instantiate(
 
     CONFIG.save(deps.storage, &config)?;
 
-    let msgs = warp_msgs_to_cosmos_msgs(deps.as_ref(), env, msg.msgs, &config.owner).unwrap();
+    let msgs = warp_msgs_to_cosmos_msgs(deps.as_ref(), env, msg.msgs, &config.owner, None)?;
 
     Ok(Response::new()
         .add_messages(msgs.clone())
@@ -45,20 +47,50 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    if info.sender != config.owner && info.sender != config.creator_addr {
-        return Err(ContractError::Unauthorized {});
-    }
     match msg {
         ExecuteMsg::WarpMsgs(data) => {
-            execute_warp_msgs(deps, env, data, &config.owner).map_err(ContractError::Std)
+            if info.sender != config.owner && info.sender != config.creator_addr {
+                // grantees execute msgs within their grant scope
+                let grant = GRANTS
+                    .may_load(deps.storage, &info.sender)?
+                    .ok_or(ContractError::Unauthorized {})?;
+                validate_granted_msgs(&grant, &env, &data.msgs)?;
+            }
+
+            // owner msgs and controller msgs outside of job executions (fee and reward payments,
+            // top ups, withdrawals) bypass the policy
+            let bypass_policy = info.sender == config.owner
+                || (info.sender == config.creator_addr && data.job_id.is_none());
+            let policy = match bypass_policy {
+                true => None,
+                false => POLICY.may_load(deps.storage)?,
+            };
+
+            execute_warp_msgs(deps, env, data, &config.owner, policy.as_ref())
+                .map_err(ContractError::Std)
         }
+        ExecuteMsg::Grant(data) => execute::grant::grant(deps, env, info, data, config),
+        ExecuteMsg::Revoke(data) => execute::grant::revoke(deps, info, data, config),
+        ExecuteMsg::UpdatePolicy(data) => execute::policy::update_policy(deps, info, data, config),
+        ExecuteMsg::Receive(data) => execute::receive::receive_cw20(info, data, config),
+        ExecuteMsg::ReceiveNft(data) => execute::receive::receive_cw721(info, data, config),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::QueryConfig(_) => to_binary(&query::account::query_config(deps)?),
+        QueryMsg::QueryConfig(_) => to_json_binary(&query::account::query_config(deps)?),
+        QueryMsg::QueryGrants(data) => to_json_binary(&query::account::query_grants(deps, data)?),
+        QueryMsg::QueryPolicy(_) => to_json_binary(&query::account::query_policy(deps)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        execute::receive::RECEIVE_HOOK_REPLY_ID => execute::receive::receive_hook_reply(msg),
+        _ => Err(ContractError::InvalidArguments {}),
     }
 }
 
@@ -38,6 +38,12 @@ pub enum ContractError {
     #[error("Error resolving JSON path")]
     ResolveError {},
 
+    #[error("Grant expired")]
+    GrantExpired {},
+
+    #[error("Msg not allowed by grant")]
+    GrantScopeViolation {},
+
     #[error("Sub account already taken")]
     SubAccountAlreadyTakenError {},
 
@@ -0,0 +1,104 @@
+use crate::state::GRANTS;
+use crate::ContractError;
+use account::{Config, Grant, GrantMsg, GrantScope, RevokeMsg, WarpMsgKind};
+use controller::account::WarpMsg;
+use cosmwasm_std::{CosmosMsg, DepsMut, Env, MessageInfo, Response, WasmMsg};
+
+pub fn grant(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    data: GrantMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let grantee = deps.api.addr_validate(&data.grantee)?;
+
+    if let Some(expiration) = data.expiration {
+        if expiration <= env.block.time {
+            return Err(ContractError::GrantExpired {});
+        }
+    }
+
+    if let Some(contracts) = &data.scope.contracts {
+        for contract in contracts {
+            deps.api.addr_validate(contract)?;
+        }
+    }
+
+    GRANTS.save(
+        deps.storage,
+        &grantee,
+        &Grant {
+            grantee: grantee.clone(),
+            scope: data.scope.clone(),
+            expiration: data.expiration,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant")
+        .add_attribute("grantee", grantee)
+        .add_attribute("scope", serde_json_wasm::to_string(&data.scope)?))
+}
+
+pub fn revoke(
+    deps: DepsMut,
+    info: MessageInfo,
+    data: RevokeMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let grantee = deps.api.addr_validate(&data.grantee)?;
+    GRANTS.remove(deps.storage, &grantee);
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("grantee", grantee))
+}
+
+// every msg executed by a grantee must be covered by its grant
+pub fn validate_granted_msgs(
+    grant: &Grant,
+    env: &Env,
+    msgs: &[WarpMsg],
+) -> Result<(), ContractError> {
+    if let Some(expiration) = grant.expiration {
+        if expiration <= env.block.time {
+            return Err(ContractError::GrantExpired {});
+        }
+    }
+
+    if msgs.iter().all(|msg| msg_in_scope(&grant.scope, msg)) {
+        Ok(())
+    } else {
+        Err(ContractError::GrantScopeViolation {})
+    }
+}
+
+fn msg_in_scope(scope: &GrantScope, msg: &WarpMsg) -> bool {
+    let kind = match msg {
+        WarpMsg::Generic(_) => WarpMsgKind::Generic,
+        WarpMsg::IbcTransfer(_) => WarpMsgKind::IbcTransfer,
+        WarpMsg::WithdrawAssets(_) => WarpMsgKind::WithdrawAssets,
+    };
+
+    if !scope.msg_kinds.contains(&kind) {
+        return false;
+    }
+
+    match (msg, &scope.contracts) {
+        (
+            WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })),
+            Some(contracts),
+        ) => contracts.contains(contract_addr),
+        (WarpMsg::Generic(_), Some(_)) => false,
+        _ => true,
+    }
+}
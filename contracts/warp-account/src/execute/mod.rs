@@ -0,0 +1,3 @@
+pub(crate) mod grant;
+pub(crate) mod policy;
+pub(crate) mod receive;
@@ -0,0 +1,34 @@
+use crate::state::POLICY;
+use crate::ContractError;
+use account::{Config, UpdatePolicyMsg};
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+
+pub fn update_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    data: UpdatePolicyMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match &data.policy {
+        Some(policy) => {
+            for addr in policy
+                .allowed_contracts
+                .iter()
+                .chain(policy.allowed_recipients.iter())
+                .flatten()
+            {
+                deps.api.addr_validate(addr)?;
+            }
+            POLICY.save(deps.storage, policy)?;
+        }
+        None => POLICY.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_policy")
+        .add_attribute("policy", serde_json_wasm::to_string(&data.policy)?))
+}
@@ -0,0 +1,81 @@
+use crate::ContractError;
+use account::{Config, ReceiveHookMsg};
+use controller::job::ExecuteJobMsg;
+use cosmwasm_std::{
+    from_json, to_json_binary, Attribute, Binary, MessageInfo, Reply, Response, SubMsg, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use cw721::Cw721ReceiveMsg;
+
+pub const RECEIVE_HOOK_REPLY_ID: u64 = 1;
+
+pub fn receive_cw20(
+    info: MessageInfo,
+    data: Cw20ReceiveMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let attrs = vec![
+        Attribute::new("action", "receive_cw20"),
+        Attribute::new("contract_addr", info.sender),
+        Attribute::new("sender", data.sender),
+        Attribute::new("amount", data.amount),
+    ];
+
+    receive_hook(data.msg, attrs, config)
+}
+
+pub fn receive_cw721(
+    info: MessageInfo,
+    data: Cw721ReceiveMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let attrs = vec![
+        Attribute::new("action", "receive_cw721"),
+        Attribute::new("contract_addr", info.sender),
+        Attribute::new("sender", data.sender),
+        Attribute::new("token_id", data.token_id),
+    ];
+
+    receive_hook(data.msg, attrs, config)
+}
+
+fn receive_hook(
+    msg: Binary,
+    attrs: Vec<Attribute>,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let hook = match msg.is_empty() {
+        true => ReceiveHookMsg::Deposit {},
+        false => from_json(&msg)?,
+    };
+
+    let res = Response::new().add_attributes(attrs);
+
+    match hook {
+        ReceiveHookMsg::Deposit {} => Ok(res.add_attribute("hook", "deposit")),
+        ReceiveHookMsg::ExecuteJob { job_id } => Ok(res
+            .add_submessage(SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: config.creator_addr.to_string(),
+                    msg: to_json_binary(&controller::ExecuteMsg::ExecuteJob(ExecuteJobMsg {
+                        id: job_id,
+                        external_inputs: None,
+                    }))?,
+                    funds: vec![],
+                },
+                RECEIVE_HOOK_REPLY_ID,
+            ))
+            .add_attribute("hook", "execute_job")
+            .add_attribute("job_id", job_id)),
+    }
+}
+
+// failed hooks don't revert the deposit
+pub fn receive_hook_reply(msg: Reply) -> Result<Response, ContractError> {
+    let error = msg.result.unwrap_err();
+
+    Ok(Response::new()
+        .add_attribute("action", "receive_hook_reply")
+        .add_attribute("hook_status", "failed")
+        .add_attribute("error", error))
+}
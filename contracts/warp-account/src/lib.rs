@@ -1,5 +1,6 @@
 pub mod contract;
 mod error;
+mod execute;
 mod query;
 pub mod state;
 
@@ -1,8 +1,36 @@
-use crate::state::CONFIG;
-use account::ConfigResponse;
-use cosmwasm_std::{Deps, StdResult};
+use crate::state::{CONFIG, GRANTS, POLICY};
+use account::{ConfigResponse, Grant, GrantsResponse, PolicyResponse, QueryGrantsMsg};
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+const QUERY_LIMIT: u32 = 50;
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
     Ok(ConfigResponse { config })
 }
+
+pub fn query_grants(deps: Deps, data: QueryGrantsMsg) -> StdResult<GrantsResponse> {
+    let start_after = data
+        .start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let grants = GRANTS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(data.limit.unwrap_or(QUERY_LIMIT) as usize)
+        .map(|item| item.map(|(_, grant)| grant))
+        .collect::<StdResult<Vec<Grant>>>()?;
+
+    Ok(GrantsResponse { grants })
+}
+
+pub fn query_policy(deps: Deps) -> StdResult<PolicyResponse> {
+    let policy = POLICY.may_load(deps.storage)?;
+    Ok(PolicyResponse { policy })
+}
@@ -1,5 +1,13 @@
-use cw_storage_plus::Item;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
 
-use account::Config;
+use account::{Config, Grant};
+use controller::account::AccountPolicy;
 
 pub const CONFIG: Item<Config> = Item::new("config");
+
+// Key is the grantee address, value is the grant of delegated permissions
+pub const GRANTS: Map<&Addr, Grant> = Map::new("grants");
+
+// Firewall applied to msgs not executed by the owner
+pub const POLICY: Item<AccountPolicy> = Item::new("policy");
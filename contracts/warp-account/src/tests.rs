@@ -1,12 +1,22 @@
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, reply};
 use crate::ContractError;
-use account::{ExecuteMsg, InstantiateMsg};
-use controller::account::{WarpMsg, WarpMsgs};
+use account::{
+    ExecuteMsg, GrantMsg, GrantScope, InstantiateMsg, ReceiveHookMsg, RevokeMsg, UpdatePolicyMsg,
+    WarpMsgKind,
+};
+use controller::account::{
+    AccountPolicy, AssetInfo, IbcTransferMsg, SpendLimit, TransferMsg, WarpMsg, WarpMsgs,
+    WithdrawAssetsMsg,
+};
+use controller::job::ExecuteJobMsg;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 use cosmwasm_std::{
-    to_binary, BankMsg, Coin, CosmosMsg, DistributionMsg, GovMsg, IbcMsg, IbcTimeout,
-    IbcTimeoutBlock, Response, StakingMsg, Uint128, Uint64, VoteOption, WasmMsg,
+    to_json_binary, Attribute, BankMsg, Binary, Coin, CosmosMsg, DistributionMsg, GovMsg, IbcMsg,
+    IbcTimeout, IbcTimeoutBlock, Reply, Response, StakingMsg, StdError, SubMsg, SubMsgResult,
+    Uint128, Uint64, VoteOption, WasmMsg,
 };
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
 
 #[test]
 fn test_execute_controller() {
@@ -31,7 +41,7 @@ fn test_execute_controller() {
         msgs: vec![
             WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: "contract".to_string(),
-                msg: to_binary("test").unwrap(),
+                msg: to_json_binary("test").unwrap(),
                 funds: vec![Coin {
                     denom: "coin".to_string(),
                     amount: Uint128::new(100),
@@ -89,7 +99,7 @@ fn test_execute_controller() {
             .add_messages(vec![
                 CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: "contract".to_string(),
-                    msg: to_binary("test").unwrap(),
+                    msg: to_json_binary("test").unwrap(),
                     funds: vec![Coin {
                         denom: "coin".to_string(),
                         amount: Uint128::new(100)
@@ -159,7 +169,7 @@ fn test_execute_owner() {
         msgs: vec![
             WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: "contract".to_string(),
-                msg: to_binary("test").unwrap(),
+                msg: to_json_binary("test").unwrap(),
                 funds: vec![Coin {
                     denom: "coin".to_string(),
                     amount: Uint128::new(100),
@@ -219,7 +229,7 @@ fn test_execute_owner() {
             .add_messages(vec![
                 CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: "contract".to_string(),
-                    msg: to_binary("test").unwrap(),
+                    msg: to_json_binary("test").unwrap(),
                     funds: vec![Coin {
                         denom: "coin".to_string(),
                         amount: Uint128::new(100)
@@ -289,7 +299,7 @@ fn test_execute_unauth() {
         msgs: vec![
             WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: "contract".to_string(),
-                msg: to_binary("test").unwrap(),
+                msg: to_json_binary("test").unwrap(),
                 funds: vec![Coin {
                     denom: "coin".to_string(),
                     amount: Uint128::new(100),
@@ -344,3 +354,566 @@ fn test_execute_unauth() {
 
     assert_eq!(execute_res, ContractError::Unauthorized {})
 }
+
+#[test]
+fn test_execute_grantee() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let _instantiate_res = instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        InstantiateMsg {
+            owner: "vlad".to_string(),
+            job_id: Uint64::zero(),
+            native_funds: vec![],
+            cw_funds: vec![],
+            msgs: vec![],
+        },
+    );
+
+    let withdraw_msg = ExecuteMsg::WarpMsgs(WarpMsgs {
+        msgs: vec![WarpMsg::WithdrawAssets(WithdrawAssetsMsg {
+            asset_infos: vec![],
+        })],
+        job_id: None,
+    });
+    let execute_contract_msg = |contract_addr: &str| {
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary("test").unwrap(),
+                funds: vec![],
+            }))],
+            job_id: None,
+        })
+    };
+    let grant_msg = |expiration| {
+        ExecuteMsg::Grant(GrantMsg {
+            grantee: "operator".to_string(),
+            scope: GrantScope {
+                msg_kinds: vec![WarpMsgKind::Generic, WarpMsgKind::WithdrawAssets],
+                contracts: Some(vec!["contract".to_string()]),
+            },
+            expiration,
+        })
+    };
+
+    // only owner grants
+    let grant_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        grant_msg(None),
+    )
+    .unwrap_err();
+    assert_eq!(grant_res, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad", &[]),
+        grant_msg(Some(env.block.time.plus_seconds(100))),
+    )
+    .unwrap();
+
+    // msgs within scope
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator", &[]),
+        execute_contract_msg("contract"),
+    )
+    .unwrap();
+
+    // msgs out of scope
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator", &[]),
+        execute_contract_msg("other_contract"),
+    )
+    .unwrap_err();
+    assert_eq!(execute_res, ContractError::GrantScopeViolation {});
+
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator", &[]),
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "operator".to_string(),
+                amount: vec![Coin {
+                    denom: "coin".to_string(),
+                    amount: Uint128::new(100),
+                }],
+            }))],
+            job_id: None,
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(execute_res, ContractError::GrantScopeViolation {});
+
+    // expired grant
+    let mut expired_env = env.clone();
+    expired_env.block.time = env.block.time.plus_seconds(100);
+    let execute_res = execute(
+        deps.as_mut(),
+        expired_env,
+        mock_info("operator", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(execute_res, ContractError::GrantExpired {});
+
+    // revoked grant
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad", &[]),
+        ExecuteMsg::Revoke(RevokeMsg {
+            grantee: "operator".to_string(),
+        }),
+    )
+    .unwrap();
+    let execute_res =
+        execute(deps.as_mut(), env, mock_info("operator", &[]), withdraw_msg).unwrap_err();
+    assert_eq!(execute_res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_execute_policy() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let _instantiate_res = instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        InstantiateMsg {
+            owner: "vlad".to_string(),
+            job_id: Uint64::zero(),
+            native_funds: vec![],
+            cw_funds: vec![],
+            msgs: vec![],
+        },
+    );
+
+    let send_msg = |to_address: &str, amount: u128| {
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send {
+                to_address: to_address.to_string(),
+                amount: vec![Coin {
+                    denom: "coin".to_string(),
+                    amount: Uint128::new(amount),
+                }],
+            }))],
+            job_id: Some(Uint64::one()),
+        })
+    };
+    let execute_contract_msg = |contract_addr: &str| {
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary("test").unwrap(),
+                funds: vec![],
+            }))],
+            job_id: Some(Uint64::one()),
+        })
+    };
+    let update_policy_msg = ExecuteMsg::UpdatePolicy(UpdatePolicyMsg {
+        policy: Some(AccountPolicy {
+            allowed_contracts: Some(vec!["contract".to_string()]),
+            allowed_recipients: Some(vec!["vlad2".to_string()]),
+            max_spend: vec![SpendLimit {
+                asset: AssetInfo::Native("coin".to_string()),
+                amount: Uint128::new(100),
+            }],
+        }),
+    });
+
+    // only owner updates the policy
+    let update_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        update_policy_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(update_res, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad", &[]),
+        update_policy_msg,
+    )
+    .unwrap();
+
+    // msgs within policy
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        send_msg("vlad2", 100),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        execute_contract_msg("contract"),
+    )
+    .unwrap();
+
+    // sends to the owner are always allowed
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        send_msg("vlad", 1000),
+    )
+    .unwrap();
+
+    // msgs violating the policy
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        send_msg("attacker", 1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        execute_res,
+        ContractError::Std(StdError::generic_err(
+            "Account policy violation: recipient attacker not allowed"
+        ))
+    );
+
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        send_msg("vlad2", 101),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        execute_res,
+        ContractError::Std(StdError::GenericErr { .. })
+    ));
+
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        execute_contract_msg("other_contract"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        execute_res,
+        ContractError::Std(StdError::generic_err(
+            "Account policy violation: contract other_contract not allowed"
+        ))
+    );
+
+    // every msg moving assets or rights out of the account has its recipient checked
+    let cw_execute = |msg: Binary| {
+        WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "contract".to_string(),
+            msg,
+            funds: vec![],
+        }))
+    };
+    let recipient_bypasses = vec![
+        WarpMsg::Generic(CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: "channel-0".to_string(),
+            to_address: "attacker".to_string(),
+            amount: Coin::new(1, "coin"),
+            timeout: IbcTimeout::with_block(IbcTimeoutBlock {
+                revision: 1,
+                height: 100,
+            }),
+        })),
+        WarpMsg::IbcTransfer(IbcTransferMsg {
+            transfer_msg: TransferMsg {
+                source_port: "transfer".to_string(),
+                source_channel: "channel-0".to_string(),
+                token: Some(controller::account::Coin {
+                    denom: "coin".to_string(),
+                    amount: "1".to_string(),
+                }),
+                sender: "".to_string(),
+                receiver: "attacker".to_string(),
+                timeout_block: None,
+                timeout_timestamp: None,
+                memo: "".to_string(),
+            },
+            timeout_block_delta: None,
+            timeout_timestamp_seconds_delta: Some(100),
+        }),
+        cw_execute(
+            to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "attacker".to_string(),
+                amount: Uint128::new(1),
+            })
+            .unwrap(),
+        ),
+        cw_execute(
+            to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "attacker".to_string(),
+                amount: Uint128::new(1),
+                msg: Binary::default(),
+            })
+            .unwrap(),
+        ),
+        cw_execute(
+            to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: "attacker".to_string(),
+                amount: Uint128::new(1),
+                expires: None,
+            })
+            .unwrap(),
+        ),
+        cw_execute(
+            to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                recipient: "attacker".to_string(),
+                token_id: "1".to_string(),
+            })
+            .unwrap(),
+        ),
+        cw_execute(
+            to_json_binary(&Cw721ExecuteMsg::ApproveAll {
+                operator: "attacker".to_string(),
+                expires: None,
+            })
+            .unwrap(),
+        ),
+        WarpMsg::Generic(CosmosMsg::Distribution(
+            DistributionMsg::SetWithdrawAddress {
+                address: "attacker".to_string(),
+            },
+        )),
+        WarpMsg::Generic(CosmosMsg::Staking(StakingMsg::Delegate {
+            validator: "attacker".to_string(),
+            amount: Coin::new(1, "coin"),
+        })),
+        WarpMsg::Generic(CosmosMsg::Staking(StakingMsg::Redelegate {
+            src_validator: "vlad2".to_string(),
+            dst_validator: "attacker".to_string(),
+            amount: Coin::new(1, "coin"),
+        })),
+    ];
+    for msg in recipient_bypasses {
+        let execute_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("vlad_controller", &[]),
+            ExecuteMsg::WarpMsgs(WarpMsgs {
+                msgs: vec![msg],
+                job_id: Some(Uint64::one()),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            execute_res,
+            ContractError::Std(StdError::generic_err(
+                "Account policy violation: recipient attacker not allowed"
+            ))
+        );
+    }
+
+    // delegations are spent
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: "vlad2".to_string(),
+                amount: Coin::new(101, "coin"),
+            }))],
+            job_id: Some(Uint64::one()),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        execute_res,
+        ContractError::Std(StdError::generic_err(
+            "Account policy violation: spending 101 of Native(\"coin\") exceeds limit of 100"
+        ))
+    );
+
+    // msgs the policy doesn't inspect are rejected
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Gov(GovMsg::Vote {
+                proposal_id: 1,
+                vote: VoteOption::Yes,
+            }))],
+            job_id: Some(Uint64::one()),
+        }),
+    )
+    .unwrap_err();
+    assert!(execute_res
+        .to_string()
+        .contains("Account policy violation: msg {\"gov\":{\"vote\""));
+
+    // controller fee and reward payments outside of job executions bypass the policy
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        ExecuteMsg::WarpMsgs(WarpMsgs {
+            msgs: vec![WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee_collector".to_string(),
+                amount: vec![Coin::new(1000, "coin")],
+            }))],
+            job_id: None,
+        }),
+    )
+    .unwrap();
+
+    // owner bypasses the policy
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad", &[]),
+        send_msg("attacker", 1000),
+    )
+    .unwrap();
+
+    // removed policy
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad", &[]),
+        ExecuteMsg::UpdatePolicy(UpdatePolicyMsg { policy: None }),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("vlad_controller", &[]),
+        send_msg("attacker", 1000),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_execute_receive() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let _instantiate_res = instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vlad_controller", &[]),
+        InstantiateMsg {
+            owner: "vlad".to_string(),
+            job_id: Uint64::zero(),
+            native_funds: vec![],
+            cw_funds: vec![],
+            msgs: vec![],
+        },
+    );
+
+    // plain cw20 deposit
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("token", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "vlad2".to_string(),
+            amount: Uint128::new(100),
+            msg: Binary::default(),
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(
+        execute_res,
+        Response::new()
+            .add_attribute("action", "receive_cw20")
+            .add_attribute("contract_addr", "token")
+            .add_attribute("sender", "vlad2")
+            .add_attribute("amount", "100")
+            .add_attribute("hook", "deposit")
+    );
+
+    // cw721 deposit triggering a job execution
+    let execute_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("nft", &[]),
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: "vlad2".to_string(),
+            token_id: "1".to_string(),
+            msg: to_json_binary(&ReceiveHookMsg::ExecuteJob {
+                job_id: Uint64::new(7),
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(
+        execute_res,
+        Response::new()
+            .add_attribute("action", "receive_cw721")
+            .add_attribute("contract_addr", "nft")
+            .add_attribute("sender", "vlad2")
+            .add_attribute("token_id", "1")
+            .add_submessage(SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "vlad_controller".to_string(),
+                    msg: to_json_binary(&controller::ExecuteMsg::ExecuteJob(ExecuteJobMsg {
+                        id: Uint64::new(7),
+                        external_inputs: None,
+                    }))
+                    .unwrap(),
+                    funds: vec![],
+                },
+                1,
+            ))
+            .add_attribute("hook", "execute_job")
+            .add_attribute("job_id", "7")
+    );
+
+    // failed hook keeps the deposit
+    let reply_res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Err("Job is not active".to_string()),
+        },
+    )
+    .unwrap();
+    assert!(reply_res
+        .attributes
+        .contains(&Attribute::new("hook_status", "failed")));
+
+    // invalid payload
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("token", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "vlad2".to_string(),
+            amount: Uint128::new(100),
+            msg: to_json_binary("test").unwrap(),
+        }),
+    )
+    .unwrap_err();
+}
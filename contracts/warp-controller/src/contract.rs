@@ -1,5 +1,5 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response,
+    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response,
     StdResult, SubMsg, Uint64,
 };
 use cw_utils::nonpayable;
@@ -120,6 +120,14 @@ pub fn execute(
             nonpayable(&info).unwrap();
             execute::job::evict_job(deps, env, info, data, config)
         }
+        ExecuteMsg::PauseJob(data) => {
+            nonpayable(&info).unwrap();
+            execute::job::pause_job(deps, env, info, data)
+        }
+        ExecuteMsg::ResumeJob(data) => {
+            nonpayable(&info).unwrap();
+            execute::job::resume_job(deps, env, info, data, config)
+        }
         ExecuteMsg::UpdateConfig(data) => {
             nonpayable(&info).unwrap();
             execute::controller::update_config(deps, env, info, data, config)
@@ -141,18 +149,38 @@ pub fn execute(
         ExecuteMsg::CreateFundingAccount(data) => {
             execute::account::create_funding_account(deps, env, info, data)
         }
+        ExecuteMsg::WithdrawFromFundingAccount(data) => {
+            nonpayable(&info).unwrap();
+            execute::account::withdraw_from_funding_account(deps, info, data, config)
+        }
+        ExecuteMsg::CloseFundingAccount(data) => {
+            nonpayable(&info).unwrap();
+            execute::account::close_funding_account(deps, info, data, config)
+        }
+        ExecuteMsg::SweepAccounts(data) => {
+            nonpayable(&info).unwrap();
+            execute::account::sweep_accounts(deps, info, data, config)
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::QueryJob(data) => to_binary(&query::job::query_job(deps, env, data)?),
-        QueryMsg::QueryJobs(data) => to_binary(&query::job::query_jobs(deps, env, data)?),
+        QueryMsg::QueryJob(data) => to_json_binary(&query::job::query_job(deps, env, data)?),
+        QueryMsg::QueryJobs(data) => to_json_binary(&query::job::query_jobs(deps, env, data)?),
         QueryMsg::QueryConfig(data) => {
-            to_binary(&query::controller::query_config(deps, env, data)?)
+            to_json_binary(&query::controller::query_config(deps, env, data)?)
+        }
+        QueryMsg::QueryState(data) => {
+            to_json_binary(&query::controller::query_state(deps, env, data)?)
+        }
+        QueryMsg::QueryFundingAccountSummary(data) => to_json_binary(
+            &query::account::query_funding_account_summary(deps, env, data)?,
+        ),
+        QueryMsg::QueryAccountAddress(data) => {
+            to_json_binary(&query::account::query_account_address(deps, env, data)?)
         }
-        QueryMsg::QueryState(data) => to_binary(&query::controller::query_state(deps, env, data)?),
     }
 }
 
@@ -162,8 +190,6 @@ pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response,
 }
 
 // first 10 slots (0-9) are reserved for custom reply calls
-pub const REPLY_ID_CREATE_JOB_ACCOUNT_AND_JOB: u64 = 0;
-pub const REPLY_ID_CREATE_FUNDING_ACCOUNT: u64 = 2;
 pub const REPLY_ID_INSTANTIATE_SUB_CONTRACTS: u64 = 3;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -171,12 +197,6 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
     let config = CONFIG.load(deps.storage)?;
 
     match msg.id {
-        REPLY_ID_CREATE_JOB_ACCOUNT_AND_JOB => {
-            reply::account::create_account_and_job(deps, env, msg, config)
-        }
-        REPLY_ID_CREATE_FUNDING_ACCOUNT => {
-            reply::account::create_funding_account(deps, env, msg, config)
-        }
         REPLY_ID_INSTANTIATE_SUB_CONTRACTS => {
             reply::job::instantiate_sub_contracts(deps, env, msg, config)
         }
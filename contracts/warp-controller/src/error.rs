@@ -48,6 +48,30 @@ pub enum ContractError {
     #[error("Account does not exist")]
     AccountDoesNotExist {},
 
+    #[error("Funding budget requires a funding account")]
+    FundingBudgetWithoutFundingAccount {},
+
+    #[error("Funding budget exhausted")]
+    FundingBudgetExhausted {},
+
+    #[error("Top up source requires a funding account")]
+    TopUpSourceWithoutFundingAccount {},
+
+    #[error("Top up source must be another funding account of the job owner")]
+    InvalidTopUpSource {},
+
+    #[error("Job account cannot be provided along with fresh job account")]
+    JobAccountWithFreshJobAccount {},
+
+    #[error("Job account is taken by another job")]
+    JobAccountNotFree {},
+
+    #[error("Job not paused")]
+    JobNotPaused {},
+
+    #[error("Funding account is used by pending jobs")]
+    FundingAccountInUse {},
+
     #[error("Account already exists")]
     AccountAlreadyExists {},
 
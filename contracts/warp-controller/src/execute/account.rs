@@ -1,9 +1,21 @@
-use controller::CreateFundingAccountMsg;
-use cosmwasm_std::{DepsMut, Env, MessageInfo, ReplyOn, Response, SubMsg, Uint64};
+use account_tracker::{AccountStatus, FundingAccount, FundingAccountResponse, JobAccountResponse};
+use controller::account::AssetInfo;
+use controller::{
+    CloseFundingAccountMsg, Config, CreateFundingAccountMsg, SweepAccountsMsg,
+    WithdrawFromFundingAccountMsg,
+};
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, Uint64};
 
 use crate::{
-    contract::REPLY_ID_CREATE_FUNDING_ACCOUNT, state::CONFIG,
-    util::msg::build_instantiate_warp_account_msg, ContractError,
+    state::CONFIG,
+    util::{
+        account::{account_address, account_salt, take_account_index},
+        msg::{
+            build_account_withdraw_assets_msg, build_close_funding_account_msg,
+            build_free_funding_account_msg, build_instantiate_warp_account_msg,
+        },
+    },
+    ContractError,
 };
 
 pub fn create_funding_account(
@@ -14,22 +26,150 @@ pub fn create_funding_account(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    let submsgs = vec![SubMsg {
-        id: REPLY_ID_CREATE_FUNDING_ACCOUNT,
-        msg: build_instantiate_warp_account_msg(
+    let account_index = take_account_index(deps.storage, &info.sender)?;
+    let funding_account_addr =
+        account_address(deps.as_ref(), &env, &config, &info.sender, account_index)?;
+
+    let msgs = vec![
+        build_instantiate_warp_account_msg(
             Uint64::from(0u64), // placeholder
             env.contract.address.to_string(),
             config.warp_account_code_id.u64(),
             info.sender.to_string(),
-            info.funds,
+            account_salt(deps.as_ref(), &info.sender, account_index)?,
+            info.funds.clone(),
             None,
             None,
         ),
-        gas_limit: None,
-        reply_on: ReplyOn::Always,
-    }];
+        build_free_funding_account_msg(
+            config.account_tracker_address.to_string(),
+            info.sender.to_string(),
+            funding_account_addr.to_string(),
+            Uint64::from(0u64), // placeholder
+        ),
+    ];
 
     Ok(Response::new()
+        .add_messages(msgs)
         .add_attribute("action", "create_funding_account")
-        .add_submessages(submsgs))
+        .add_attribute("owner", info.sender)
+        .add_attribute("funding_account_address", funding_account_addr)
+        .add_attribute("account_index", account_index)
+        .add_attribute("native_funds", serde_json_wasm::to_string(&info.funds)?))
+}
+
+pub fn withdraw_from_funding_account(
+    deps: DepsMut,
+    info: MessageInfo,
+    data: WithdrawFromFundingAccountMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let funding_account =
+        query_owned_funding_account(deps.as_ref(), &config, &info.sender, &data.account)?;
+
+    // Funding account withdraws assets to its owner
+    Ok(Response::new()
+        .add_message(build_account_withdraw_assets_msg(
+            funding_account.account_addr.to_string(),
+            data.assets.clone(),
+        ))
+        .add_attribute("action", "withdraw_from_funding_account")
+        .add_attribute("funding_account_address", funding_account.account_addr)
+        .add_attribute("assets", serde_json_wasm::to_string(&data.assets)?))
+}
+
+pub fn close_funding_account(
+    deps: DepsMut,
+    info: MessageInfo,
+    data: CloseFundingAccountMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let funding_account =
+        query_owned_funding_account(deps.as_ref(), &config, &info.sender, &data.account)?;
+
+    if funding_account.account_status == AccountStatus::Taken {
+        return Err(ContractError::FundingAccountInUse {});
+    }
+
+    let native_assets: Vec<AssetInfo> = deps
+        .querier
+        .query_all_balances(&funding_account.account_addr)?
+        .into_iter()
+        .map(|coin| AssetInfo::Native(coin.denom))
+        .collect();
+
+    let msgs = vec![
+        // Funding account withdraws its native balances to its owner
+        build_account_withdraw_assets_msg(funding_account.account_addr.to_string(), native_assets),
+        // Untrack account
+        build_close_funding_account_msg(
+            config.account_tracker_address.to_string(),
+            info.sender.to_string(),
+            funding_account.account_addr.to_string(),
+        ),
+    ];
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "close_funding_account")
+        .add_attribute("funding_account_address", funding_account.account_addr))
+}
+
+pub fn sweep_accounts(
+    deps: DepsMut,
+    info: MessageInfo,
+    data: SweepAccountsMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let mut msgs = vec![];
+
+    for account in &data.accounts {
+        // only free job accounts of the sender can be swept
+        let job_account_resp: JobAccountResponse = deps.querier.query_wasm_smart(
+            config.account_tracker_address.to_string(),
+            &account_tracker::QueryMsg::QueryJobAccount(account_tracker::QueryJobAccountMsg {
+                account_owner_addr: info.sender.to_string(),
+                account_addr: deps.api.addr_validate(account)?.to_string(),
+            }),
+        )?;
+
+        let job_account = job_account_resp
+            .job_account
+            .ok_or(ContractError::AccountDoesNotExist {})?;
+
+        if job_account.account_status != AccountStatus::Free {
+            return Err(ContractError::JobAccountNotFree {});
+        }
+
+        // Job account withdraws assets to its owner
+        msgs.push(build_account_withdraw_assets_msg(
+            job_account.account_addr.to_string(),
+            data.assets.clone(),
+        ));
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "sweep_accounts")
+        .add_attribute("accounts", serde_json_wasm::to_string(&data.accounts)?)
+        .add_attribute("assets", serde_json_wasm::to_string(&data.assets)?))
+}
+
+fn query_owned_funding_account(
+    deps: Deps,
+    config: &Config,
+    owner: &Addr,
+    account: &str,
+) -> Result<FundingAccount, ContractError> {
+    let funding_account_resp: FundingAccountResponse = deps.querier.query_wasm_smart(
+        config.account_tracker_address.to_string(),
+        &account_tracker::QueryMsg::QueryFundingAccount(account_tracker::QueryFundingAccountMsg {
+            account_owner_addr: owner.to_string(),
+            account_addr: deps.api.addr_validate(account)?.to_string(),
+        }),
+    )?;
+
+    funding_account_resp
+        .funding_account
+        .ok_or(ContractError::AccountDoesNotExist {})
 }
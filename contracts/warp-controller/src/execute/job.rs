@@ -1,4 +1,3 @@
-use crate::contract::REPLY_ID_CREATE_JOB_ACCOUNT_AND_JOB;
 use crate::state::{JobQueue, STATE};
 use crate::util::msg::{
     build_account_execute_generic_msgs, build_account_execute_warp_msgs,
@@ -7,25 +6,27 @@ use crate::util::msg::{
 use crate::ContractError;
 use controller::account::WarpMsgs;
 use controller::job::{
-    CreateJobMsg, DeleteJobMsg, EvictJobMsg, ExecuteJobMsg, Execution, Job, JobStatus, UpdateJobMsg,
+    CreateJobMsg, DeleteJobMsg, EvictJobMsg, ExecuteJobMsg, Job, JobStatus, PauseJobMsg,
+    PauseReason, ResumeJobMsg, UpdateJobMsg,
 };
 use cosmwasm_std::{
-    to_binary, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, ReplyOn, Response, StdResult,
+    to_json_binary, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, ReplyOn, Response,
     SubMsg, Uint128, Uint64, WasmMsg,
 };
 
 use crate::util::{
-    fee::deduct_from_native_funds,
+    account::{account_address, account_salt, take_account_index},
+    fee::{compute_top_up, deduct_from_native_funds},
     msg::{
         build_account_withdraw_assets_msg, build_free_job_account_msg,
-        build_instantiate_warp_account_msg, build_take_job_account_msg, build_transfer_cw20_msg,
-        build_transfer_cw721_msg, build_transfer_native_funds_msg,
+        build_instantiate_warp_account_msg, build_job_env, build_take_job_account_msg,
+        build_top_up_msg, build_transfer_cw20_msg, build_transfer_cw721_msg,
+        build_transfer_native_funds_msg,
     },
 };
 
-use account_tracker::{FundingAccount, FundingAccountResponse, JobAccountResponse};
+use account_tracker::{AccountStatus, FundingAccount, FundingAccountResponse, JobAccountResponse};
 use controller::{account::CwFund, Config};
-use resolver::QueryHydrateMsgsMsg;
 
 use super::fee::{compute_burn_fee, compute_creation_fee, compute_maintenance_fee};
 
@@ -78,6 +79,42 @@ pub fn create_job(
         return Err(ContractError::FundingAccountMissingForRecurringJob {});
     }
 
+    if data.funding_account.is_none() && data.funding_budget.is_some() {
+        return Err(ContractError::FundingBudgetWithoutFundingAccount {});
+    }
+
+    if let Some(top_up_source) = &data.top_up_source {
+        if data.funding_account.is_none() {
+            return Err(ContractError::TopUpSourceWithoutFundingAccount {});
+        }
+
+        if data.funding_account.as_ref() == Some(&top_up_source.account) {
+            return Err(ContractError::InvalidTopUpSource {});
+        }
+
+        // top up source must be another funding account of the job owner
+        let top_up_source_resp: FundingAccountResponse = deps.querier.query_wasm_smart(
+            account_tracker_address_ref,
+            &account_tracker::QueryMsg::QueryFundingAccount(
+                account_tracker::QueryFundingAccountMsg {
+                    account_addr: top_up_source.account.to_string(),
+                    account_owner_addr: info.sender.to_string(),
+                },
+            ),
+        )?;
+
+        if top_up_source_resp.funding_account.is_none() {
+            return Err(ContractError::InvalidTopUpSource {});
+        }
+    }
+
+    // first iteration is paid from the funding account as well
+    if let Some(funding_budget) = data.funding_budget {
+        if funding_budget < total_fees + data.reward {
+            return Err(ContractError::FundingBudgetExhausted {});
+        }
+    }
+
     if data.funding_account.is_none() {
         if data.operational_amount < total_fees + data.reward {
             return Err(ContractError::InsufficientOperationalFunds {});
@@ -109,7 +146,6 @@ pub fn create_job(
         operational_amount,
     );
 
-    let mut submsgs = vec![];
     let mut msgs = vec![];
     let mut attrs = vec![];
 
@@ -138,37 +174,107 @@ pub fn create_job(
             created_at_time: Uint64::from(env.block.time.seconds()),
             // placeholder, will be updated later on
             funding_account: None,
+            execution_count: Uint64::zero(),
+            funding_budget: data.funding_budget,
+            funding_spent: Uint128::zero(),
+            top_up_source: data.top_up_source,
+            grace_mode: data.grace_mode.unwrap_or(false),
+            pause_reason: None,
         },
     )?;
 
-    let job_account_resp: JobAccountResponse = deps.querier.query_wasm_smart(
-        account_tracker_address_ref,
-        &account_tracker::QueryMsg::QueryFirstFreeJobAccount(
-            account_tracker::QueryFirstFreeJobAccountMsg {
-                account_owner_addr: job_owner.to_string(),
-            },
-        ),
-    )?;
+    let job_account = match (data.job_account, data.fresh_job_account.unwrap_or(false)) {
+        (Some(_), true) => return Err(ContractError::JobAccountWithFreshJobAccount {}),
+        (Some(job_account_addr), false) => {
+            // provided job account must be a free job account of the job owner
+            let job_account_resp: JobAccountResponse = deps.querier.query_wasm_smart(
+                account_tracker_address_ref,
+                &account_tracker::QueryMsg::QueryJobAccount(account_tracker::QueryJobAccountMsg {
+                    account_owner_addr: job_owner.to_string(),
+                    account_addr: job_account_addr.to_string(),
+                }),
+            )?;
+
+            match job_account_resp.job_account {
+                None => return Err(ContractError::AccountDoesNotExist {}),
+                Some(job_account) if job_account.account_status != AccountStatus::Free => {
+                    return Err(ContractError::JobAccountNotFree {})
+                }
+                job_account => job_account,
+            }
+        }
+        (None, true) => None,
+        (None, false) => {
+            let job_account_resp: JobAccountResponse = deps.querier.query_wasm_smart(
+                account_tracker_address_ref,
+                &account_tracker::QueryMsg::QueryFirstFreeJobAccount(
+                    account_tracker::QueryFirstFreeJobAccountMsg {
+                        account_owner_addr: job_owner.to_string(),
+                    },
+                ),
+            )?;
 
-    match job_account_resp.job_account {
+            job_account_resp.job_account
+        }
+    };
+
+    match job_account {
         None => {
-            // Create account then create job in reply
-            submsgs.push(SubMsg {
-                id: REPLY_ID_CREATE_JOB_ACCOUNT_AND_JOB,
-                msg: build_instantiate_warp_account_msg(
-                    job.id,
-                    env.contract.address.to_string(),
-                    config.warp_account_code_id.u64(),
-                    info.sender.to_string(),
-                    native_funds_minus_operational_amount,
-                    data.cw_funds,
-                    data.account_msgs,
-                ),
-                gas_limit: None,
-                reply_on: ReplyOn::Always,
-            });
+            // Create account at its predicted address, so it is funded and taken right away
+            let account_index = take_account_index(deps.storage, &job_owner)?;
+            let account_addr =
+                account_address(deps.as_ref(), &env, &config, &job_owner, account_index)?;
+
+            // Update job.account from placeholder value to job account
+            job.account = account_addr.clone();
+            JobQueue::sync(deps.storage, env.clone(), job.clone())?;
+
+            msgs.push(build_instantiate_warp_account_msg(
+                job.id,
+                env.contract.address.to_string(),
+                config.warp_account_code_id.u64(),
+                info.sender.to_string(),
+                account_salt(deps.as_ref(), &job_owner, account_index)?,
+                native_funds_minus_operational_amount,
+                data.cw_funds.clone(),
+                data.account_msgs,
+            ));
+
+            if let Some(cw_funds) = data.cw_funds {
+                // Fund account in CW20 / CW721 tokens
+                for cw_fund in cw_funds {
+                    msgs.push(match cw_fund {
+                        CwFund::Cw20(cw20_fund) => build_transfer_cw20_msg(
+                            deps.api
+                                .addr_validate(&cw20_fund.contract_addr)?
+                                .to_string(),
+                            info.sender.clone().to_string(),
+                            account_addr.to_string(),
+                            cw20_fund.amount,
+                        ),
+                        CwFund::Cw721(cw721_fund) => build_transfer_cw721_msg(
+                            deps.api
+                                .addr_validate(&cw721_fund.contract_addr)?
+                                .to_string(),
+                            account_addr.to_string(),
+                            cw721_fund.token_id.clone(),
+                        ),
+                    })
+                }
+            }
+
+            // Take account
+            msgs.push(build_take_job_account_msg(
+                config.account_tracker_address.to_string(),
+                job_owner.to_string(),
+                account_addr.to_string(),
+                job.id,
+            ));
 
             attrs.push(Attribute::new("action", "create_account_and_job"));
+            attrs.push(Attribute::new("job_id", job.id));
+            attrs.push(Attribute::new("account_address", account_addr));
+            attrs.push(Attribute::new("account_index", account_index));
         }
         Some(available_account) => {
             let available_account_addr = &available_account.account_addr;
@@ -278,6 +384,7 @@ pub fn create_job(
             let available_account_addr = &available_account.account_addr;
             // Update funding_account from placeholder value to funding account
             job.funding_account = Some(available_account_addr.clone());
+            job.funding_spent = total_fees + data.reward;
             JobQueue::sync(deps.storage, env.clone(), job.clone())?;
 
             // transfer reward + fees to controller from funding account
@@ -338,10 +445,7 @@ pub fn create_job(
         ),
     );
 
-    Ok(Response::new()
-        .add_submessages(submsgs)
-        .add_messages(msgs)
-        .add_attributes(attrs))
+    Ok(Response::new().add_messages(msgs).add_attributes(attrs))
 }
 
 pub fn delete_job(
@@ -354,7 +458,7 @@ pub fn delete_job(
     let job = JobQueue::get(deps.storage, data.id.into())?;
     let account_addr = job.account.clone();
 
-    if job.status != JobStatus::Pending {
+    if job.status != JobStatus::Pending && job.status != JobStatus::Paused {
         return Err(ContractError::JobNotActive {});
     }
 
@@ -364,25 +468,34 @@ pub fn delete_job(
 
     let _new_job = JobQueue::finalize(deps.storage, env, job.id.into(), JobStatus::Cancelled)?;
 
-    let fee = job.reward * Uint128::from(config.cancellation_fee_rate) / Uint128::new(100);
+    // reward of a job paused for insufficient funds was never paid
+    let reward_paid = job.pause_reason != Some(PauseReason::InsufficientFunds);
+
+    let fee = if reward_paid {
+        job.reward * Uint128::from(config.cancellation_fee_rate) / Uint128::new(100)
+    } else {
+        Uint128::zero()
+    };
 
     let mut msgs = vec![];
 
-    // Controller sends reward minus cancellation fee back to job owner
-    msgs.push(build_transfer_native_funds_msg(
-        job.owner.to_string(),
-        vec![Coin::new(
-            (job.reward - fee).u128(),
-            config.fee_denom.clone(),
-        )],
-    ));
+    if reward_paid {
+        // Controller sends reward minus cancellation fee back to job owner
+        msgs.push(build_transfer_native_funds_msg(
+            job.owner.to_string(),
+            vec![Coin::new(
+                (job.reward - fee).u128(),
+                config.fee_denom.clone(),
+            )],
+        ));
 
-    // Job owner sends fee to controller when it calls delete_job
-    // Controller sends cancellation fee to fee collector
-    msgs.push(build_transfer_native_funds_msg(
-        config.fee_collector.to_string(),
-        vec![Coin::new(fee.u128(), config.fee_denom.clone())],
-    ));
+        // Job owner sends fee to controller when it calls delete_job
+        // Controller sends cancellation fee to fee collector
+        msgs.push(build_transfer_native_funds_msg(
+            config.fee_collector.to_string(),
+            vec![Coin::new(fee.u128(), config.fee_denom.clone())],
+        ));
+    }
 
     // Free account
     msgs.push(build_free_job_account_msg(
@@ -415,6 +528,148 @@ pub fn delete_job(
         .add_attribute("deletion_fee", fee))
 }
 
+pub fn pause_job(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    data: PauseJobMsg,
+) -> Result<Response, ContractError> {
+    let job = JobQueue::get(deps.storage, data.id.into())?;
+
+    if job.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // job keeps its job and funding accounts while paused
+    let paused_at = Uint64::from(env.block.time.seconds());
+    let job = JobQueue::pause(
+        deps.storage,
+        env,
+        job.id.into(),
+        PauseReason::Owner { paused_at },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause_job")
+        .add_attribute("job_id", job.id)
+        .add_attribute("job_status", serde_json_wasm::to_string(&job.status)?))
+}
+
+pub fn resume_job(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    data: ResumeJobMsg,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let mut job = JobQueue::get(deps.storage, data.id.into())?;
+
+    if job.status != JobStatus::Paused {
+        return Err(ContractError::JobNotPaused {});
+    }
+
+    // only the owner resumes a job they paused, reward + fees are already paid
+    if let Some(PauseReason::Owner { .. }) = job.pause_reason {
+        if job.owner != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let job = JobQueue::resume(deps.storage, env, job.id.into())?;
+
+        return Ok(Response::new()
+            .add_attribute("action", "resume_job")
+            .add_attribute("job_id", job.id)
+            .add_attribute("job_status", serde_json_wasm::to_string(&job.status)?));
+    }
+
+    // anyone can resume a job paused for insufficient funds once its funding account is refunded
+    let state = STATE.load(deps.storage)?;
+    let mut msgs = vec![];
+    let mut attrs = vec![];
+
+    let funding_account_addr = job
+        .funding_account
+        .clone()
+        .ok_or(ContractError::FundingAccountMissingForRecurringJob {})?;
+
+    let creation_fee = compute_creation_fee(state.q, &config);
+    let maintenance_fee = compute_maintenance_fee(job.duration_days, &config);
+    let burn_fee = compute_burn_fee(job.reward, &config);
+    let total_fees = creation_fee + maintenance_fee + burn_fee;
+    let reward_plus_fee = job.reward + total_fees;
+
+    if let Some(funding_budget) = job.funding_budget {
+        if job.funding_spent.checked_add(reward_plus_fee)? > funding_budget {
+            return Err(ContractError::FundingBudgetExhausted {});
+        }
+    }
+
+    let operational_amount = deps
+        .querier
+        .query_balance(&funding_account_addr, &config.fee_denom)?
+        .amount;
+    let top_up = compute_top_up(
+        deps.as_ref(),
+        &config,
+        &job,
+        operational_amount,
+        reward_plus_fee,
+    )?;
+
+    if operational_amount + top_up.unwrap_or_default() < reward_plus_fee {
+        return Err(ContractError::InsufficientFundsToPayForRewardAndFee {});
+    }
+
+    if let (Some(amount), Some(source)) = (top_up, job.top_up_source.as_mut()) {
+        // Job owner's top up source refunds the funding account
+        msgs.push(build_top_up_msg(
+            source.account.to_string(),
+            funding_account_addr.to_string(),
+            amount,
+            config.fee_denom.clone(),
+        ));
+        source.allowance -= amount;
+        attrs.push(Attribute::new("job_top_up_amount", amount));
+    }
+
+    msgs.push(build_account_execute_generic_msgs(
+        funding_account_addr.to_string(),
+        vec![
+            // Job owner's funding account sends fee to fee collector
+            build_transfer_native_funds_msg(
+                config.fee_collector.to_string(),
+                vec![Coin::new(total_fees.u128(), config.fee_denom.clone())],
+            ),
+            // Job owner's funding account sends reward to controller
+            build_transfer_native_funds_msg(
+                env.contract.address.to_string(),
+                vec![Coin::new(job.reward.u128(), config.fee_denom.clone())],
+            ),
+        ],
+    ));
+
+    job.funding_spent += reward_plus_fee;
+
+    attrs.push(Attribute::new("job_creation_fee", creation_fee.to_string()));
+    attrs.push(Attribute::new(
+        "job_maintenance_fee",
+        maintenance_fee.to_string(),
+    ));
+    attrs.push(Attribute::new("job_burn_fee", burn_fee.to_string()));
+    attrs.push(Attribute::new("job_total_fees", total_fees.to_string()));
+
+    // sync sets the job back to pending, eviction period starts over
+    job.pause_reason = None;
+    let job = JobQueue::sync(deps.storage, env, job)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "resume_job")
+        .add_attribute("job_id", job.id)
+        .add_attribute("job_status", serde_json_wasm::to_string(&job.status)?)
+        .add_attributes(attrs))
+}
+
 pub fn update_job(
     deps: DepsMut,
     env: Env,
@@ -465,12 +720,19 @@ pub fn execute_job(
         return Err(ContractError::JobNotActive {});
     }
 
-    let vars: String = deps.querier.query_wasm_smart(
-        config.resolver_address.clone(),
-        &resolver::QueryMsg::QueryHydrateVars(resolver::QueryHydrateVarsMsg {
+    let job_env = build_job_env(&job, job.execution_count);
+
+    // vars are hydrated lazily, only when referenced by an evaluated condition or the matched msgs
+    // - hydration errors fail the execution and the job stays pending, condition and msgs errors
+    //   fail the job
+    let resolution: resolver::ResolveExecutionResponse = deps.querier.query_wasm_smart(
+        config.resolver_address,
+        &resolver::QueryMsg::QueryResolveExecution(resolver::QueryResolveExecutionMsg {
             vars: job.vars,
+            executions: job.executions,
             external_inputs: data.external_inputs,
             warp_account_addr: Some(job.account.to_string()),
+            job_env: Some(job_env),
         }),
     )?;
 
@@ -480,56 +742,38 @@ pub fn execute_job(
 
     let mut execution_matched = false;
 
-    for Execution { condition, msgs } in job.executions {
-        let resolution: StdResult<bool> = deps.querier.query_wasm_smart(
-            config.resolver_address.clone(),
-            &resolver::QueryMsg::QueryResolveCondition(resolver::QueryResolveConditionMsg {
-                condition,
-                vars: vars.clone(),
-                warp_account_addr: Some(job.account.to_string()),
+    if let Some(e) = resolution.error {
+        attrs.push(Attribute::new("job_condition_status", "invalid"));
+        attrs.push(Attribute::new("error", e.message));
+        attrs.push(Attribute::new(
+            "error_kind",
+            serde_json_wasm::to_string(&e.kind)?.trim_matches('"'),
+        ));
+        if let Some(variable) = e.variable {
+            attrs.push(Attribute::new("error_variable", variable));
+        }
+        if let Some(path) = e.path {
+            attrs.push(Attribute::new("error_path", path));
+        }
+        JobQueue::finalize(deps.storage, env, job.id.into(), JobStatus::Failed)?;
+
+        execution_matched = true;
+    } else if let Some(warp_msgs) = resolution.msgs {
+        submsgs.push(SubMsg {
+            id: data.id.u64(),
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: job.account.to_string(),
+                msg: to_json_binary(&account::ExecuteMsg::WarpMsgs(WarpMsgs {
+                    msgs: warp_msgs,
+                    job_id: Some(data.id),
+                }))?,
+                funds: vec![],
             }),
-        );
-
-        match resolution {
-            Ok(true) => {
-                submsgs.push(SubMsg {
-                    id: data.id.u64(),
-                    msg: CosmosMsg::Wasm(WasmMsg::Execute {
-                        contract_addr: job.account.to_string(),
-                        msg: to_binary(&account::ExecuteMsg::WarpMsgs(WarpMsgs {
-                            msgs: deps.querier.query_wasm_smart(
-                                config.resolver_address,
-                                &resolver::QueryMsg::QueryHydrateMsgs(QueryHydrateMsgsMsg {
-                                    msgs,
-                                    vars,
-                                }),
-                            )?,
-                            job_id: Some(data.id),
-                        }))?,
-                        funds: vec![],
-                    }),
-                    gas_limit: None,
-                    reply_on: ReplyOn::Always,
-                });
-
-                execution_matched = true;
-
-                break;
-            }
-            Ok(false) => {
-                // Continue to the next condition
-                continue;
-            }
-            Err(e) => {
-                attrs.push(Attribute::new("job_condition_status", "invalid"));
-                attrs.push(Attribute::new("error", e.to_string()));
-                JobQueue::finalize(deps.storage, env, job.id.into(), JobStatus::Failed)?;
-
-                execution_matched = true;
+            gas_limit: None,
+            reply_on: ReplyOn::Always,
+        });
 
-                break;
-            }
-        }
+        execution_matched = true;
     }
 
     if !execution_matched {
@@ -1,4 +1,4 @@
-use cosmwasm_std::{to_binary, Deps, Env, MessageInfo, Response, WasmMsg};
+use cosmwasm_std::{to_json_binary, Deps, Env, MessageInfo, Response, WasmMsg};
 
 use crate::ContractError;
 use account_tracker::{AccountsResponse, MigrateMsg, QueryAccountsMsg};
@@ -29,7 +29,7 @@ pub fn migrate_accounts(
         migration_msgs.push(WasmMsg::Migrate {
             contract_addr: account.account_addr.to_string(),
             new_code_id: msg.warp_account_code_id.u64(),
-            msg: to_binary(&MigrateMsg {})?,
+            msg: to_json_binary(&MigrateMsg {})?,
         });
     }
 
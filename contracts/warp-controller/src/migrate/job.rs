@@ -105,6 +105,12 @@ pub fn migrate_pending_jobs(
                 created_at_time: old_job.last_update_time,
                 // TODO: update to old_job.funding_account
                 funding_account: None,
+                execution_count: Uint64::zero(),
+                funding_budget: None,
+                funding_spent: Uint128::zero(),
+                top_up_source: None,
+                grace_mode: false,
+                pause_reason: None,
             },
         )?;
     }
@@ -174,6 +180,12 @@ pub fn migrate_finished_jobs(
                 created_at_time: old_job.last_update_time,
                 // TODO: update to old_job.funding_account
                 funding_account: None,
+                execution_count: Uint64::zero(),
+                funding_budget: None,
+                funding_spent: Uint128::zero(),
+                top_up_source: None,
+                grace_mode: false,
+                pause_reason: None,
             },
         )?;
     }
@@ -0,0 +1,102 @@
+use crate::execute::fee::{compute_burn_fee, compute_creation_fee, compute_maintenance_fee};
+use crate::state::{CONFIG, PENDING_JOBS, STATE};
+use crate::util::account::{account_address, next_account_index};
+use account_tracker::{AccountStatus, FundingAccountResponse};
+use controller::{
+    AccountAddressResponse, FundingAccountSummaryResponse, QueryAccountAddressMsg,
+    QueryFundingAccountSummaryMsg,
+};
+use cosmwasm_std::{Deps, Env, StdError, StdResult, Uint128, Uint64};
+
+pub fn query_funding_account_summary(
+    deps: Deps,
+    _env: Env,
+    data: QueryFundingAccountSummaryMsg,
+) -> StdResult<FundingAccountSummaryResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let account_addr = deps.api.addr_validate(&data.account)?;
+
+    let account_config: account::ConfigResponse = deps.querier.query_wasm_smart(
+        account_addr.to_string(),
+        &account::QueryMsg::QueryConfig(account::QueryConfigMsg {}),
+    )?;
+    let owner = account_config.config.owner;
+
+    let funding_account_resp: FundingAccountResponse = deps.querier.query_wasm_smart(
+        config.account_tracker_address.to_string(),
+        &account_tracker::QueryMsg::QueryFundingAccount(account_tracker::QueryFundingAccountMsg {
+            account_owner_addr: owner.to_string(),
+            account_addr: account_addr.to_string(),
+        }),
+    )?;
+    let funding_account = funding_account_resp
+        .funding_account
+        .ok_or_else(|| StdError::not_found("funding account"))?;
+
+    // free accounts keep the id of the last job that used them
+    let taken_by_job_ids = match funding_account.account_status {
+        AccountStatus::Taken => funding_account.taken_by_job_ids,
+        AccountStatus::Free => vec![],
+    };
+
+    // each recurring job pays reward + fees from the funding account on every execution
+    let mut cost_per_execution = Uint128::zero();
+    for job_id in &taken_by_job_ids {
+        if let Some(job) = PENDING_JOBS().may_load(deps.storage, job_id.u64())? {
+            let reward_plus_fee = job.reward
+                + compute_creation_fee(state.q, &config)
+                + compute_maintenance_fee(job.duration_days, &config)
+                + compute_burn_fee(job.reward, &config);
+
+            // jobs with an exhausted funding budget stop recurring
+            let budget_exhausted = job
+                .funding_budget
+                .map(|funding_budget| job.funding_spent + reward_plus_fee > funding_budget)
+                .unwrap_or(false);
+
+            if !budget_exhausted {
+                cost_per_execution += reward_plus_fee;
+            }
+        }
+    }
+
+    let balances = deps.querier.query_all_balances(&account_addr)?;
+    let fee_denom_balance = balances
+        .iter()
+        .find(|coin| coin.denom == config.fee_denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+
+    let runway = if cost_per_execution.is_zero() {
+        None
+    } else {
+        Some(Uint64::try_from(fee_denom_balance / cost_per_execution)?)
+    };
+
+    Ok(FundingAccountSummaryResponse {
+        account: account_addr,
+        owner,
+        balances,
+        taken_by_job_ids,
+        cost_per_execution,
+        runway,
+    })
+}
+
+pub fn query_account_address(
+    deps: Deps,
+    env: Env,
+    data: QueryAccountAddressMsg,
+) -> StdResult<AccountAddressResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let owner = deps.api.addr_validate(&data.owner)?;
+
+    let index = match data.index {
+        Some(index) => index,
+        None => next_account_index(deps.storage, &owner)?,
+    };
+    let account = account_address(deps, &env, &config, &owner, index)?;
+
+    Ok(AccountAddressResponse { account, index })
+}
@@ -49,7 +49,7 @@ pub fn query_jobs(deps: Deps, env: Env, data: QueryJobsMsg) -> StdResult<JobsRes
             name,
             owner,
             job_status,
-            start_after.map(|i| (i._1.u64())),
+            start_after.map(|i| i._1.u64()),
             page_size as usize,
         ),
         QueryJobsMsg {
@@ -116,7 +116,7 @@ pub fn query_jobs_by_reward(
     limit: u32,
 ) -> StdResult<JobsResponse> {
     let start = start_after.map(Bound::exclusive);
-    let map = if job_status.is_some() && job_status.clone().unwrap() != JobStatus::Pending {
+    let map = if is_finished_status(&job_status) {
         FINISHED_JOBS()
     } else {
         PENDING_JOBS()
@@ -126,14 +126,17 @@ pub fn query_jobs_by_reward(
         .reward
         .range(deps.storage, None, start, Order::Descending)
         .filter(|h| {
-            resolve_filters(
-                deps,
-                env.clone(),
-                h.as_ref().unwrap().clone().1,
-                name.clone(),
-                owner.clone(),
-                job_status.clone(),
-            )
+            let job = h.as_ref().unwrap().clone().1;
+            // keepers list jobs by reward, paused jobs are only listed when explicitly requested
+            (job_status.is_some() || job.status != JobStatus::Paused)
+                && resolve_filters(
+                    deps,
+                    env.clone(),
+                    job,
+                    name.clone(),
+                    owner.clone(),
+                    job_status.clone(),
+                )
         })
         .take(limit as usize)
         .collect::<StdResult<Vec<_>>>()?;
@@ -158,7 +161,7 @@ pub fn query_jobs_by_owner(
     limit: usize,
 ) -> StdResult<JobsResponse> {
     let start = start_after.map(Bound::exclusive);
-    let map = if job_status.is_some() && job_status.clone().unwrap() != JobStatus::Pending {
+    let map = if is_finished_status(&job_status) {
         FINISHED_JOBS()
     } else {
         PENDING_JOBS()
@@ -190,3 +193,11 @@ pub fn query_jobs_by_owner(
         total_count: infos.len() as u32,
     })
 }
+
+// paused jobs stay in pending jobs
+fn is_finished_status(job_status: &Option<JobStatus>) -> bool {
+    !matches!(
+        job_status,
+        None | Some(JobStatus::Pending) | Some(JobStatus::Paused)
+    )
+}
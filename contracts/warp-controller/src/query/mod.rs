@@ -1,2 +1,3 @@
+pub(crate) mod account;
 pub(crate) mod controller;
 pub(crate) mod job;
@@ -7,15 +7,18 @@ use crate::{
     error::map_contract_error,
     execute::fee::{compute_burn_fee, compute_creation_fee, compute_maintenance_fee},
     state::{JobQueue, CONFIG, STATE},
-    util::msg::{
-        build_account_execute_generic_msgs, build_account_withdraw_assets_msg,
-        build_take_funding_account_msg, build_take_job_account_msg,
-        build_transfer_native_funds_msg,
+    util::{
+        fee::compute_top_up,
+        msg::{
+            build_account_execute_generic_msgs, build_account_withdraw_assets_msg, build_job_env,
+            build_take_funding_account_msg, build_take_job_account_msg, build_top_up_msg,
+            build_transfer_native_funds_msg,
+        },
     },
     ContractError,
 };
 use controller::{
-    job::{Job, JobStatus},
+    job::{Job, JobStatus, PauseReason},
     Config,
 };
 
@@ -72,7 +75,28 @@ pub fn execute_job(
             .amount
             .amount;
 
-        if operational_amount < reward_plus_fee {
+        let budget_exhausted = match finished_job.funding_budget {
+            Some(funding_budget) => {
+                finished_job.funding_spent.checked_add(reward_plus_fee)? > funding_budget
+            }
+            None => false,
+        };
+
+        let top_up = compute_top_up(
+            deps.as_ref(),
+            &config,
+            &finished_job,
+            operational_amount,
+            reward_plus_fee,
+        )?;
+
+        // in grace mode, the next iteration is paused until the funding account is refunded
+        let insufficient_fee = operational_amount + top_up.unwrap_or_default() < reward_plus_fee;
+
+        if budget_exhausted {
+            new_job_attrs.push(Attribute::new("action", "recur_job"));
+            new_job_attrs.push(Attribute::new("creation_status", "failed_budget_exhausted"));
+        } else if insufficient_fee && !finished_job.grace_mode {
             new_job_attrs.push(Attribute::new("action", "recur_job"));
             new_job_attrs.push(Attribute::new("creation_status", "failed_insufficient_fee"));
         } else if !(finished_job.status == JobStatus::Executed
@@ -84,12 +108,21 @@ pub fn execute_job(
                 "failed_invalid_job_status",
             ));
         } else {
+            // finished job's execution is counted for the next iteration
+            let job_env = build_job_env(
+                &finished_job,
+                finished_job.execution_count.checked_add(Uint64::one())?,
+            );
+
             let hydrated_vars: String = deps.querier.query_wasm_smart(
                 config.resolver_address.clone(),
                 &resolver::QueryMsg::QueryHydrateVars(resolver::QueryHydrateVarsMsg {
                     vars: finished_job.vars,
                     warp_account_addr: Some(finished_job.account.to_string()),
+                    job_env: Some(job_env.clone()),
                     external_inputs: None,
+                    // a price going stale since the execution doesn't revert it
+                    skip_price_staleness: Some(true),
                 }),
             )?;
 
@@ -100,6 +133,7 @@ pub fn execute_job(
                     vars: hydrated_vars,
                     status: finished_job.status.clone(),
                     warp_account_addr: Some(finished_job.account.to_string()),
+                    job_env: Some(job_env.clone()),
                 }),
             )?;
 
@@ -115,6 +149,7 @@ pub fn execute_job(
                                 condition: terminate_condition,
                                 vars: new_vars.clone(),
                                 warp_account_addr: Some(finished_job.account.to_string()),
+                                job_env: Some(job_env.clone()),
                             },
                         ),
                     );
@@ -153,6 +188,35 @@ pub fn execute_job(
             if !should_terminate_job {
                 recurring_job_created = true;
 
+                let (status, pause_reason, funding_spent) = if insufficient_fee {
+                    (
+                        JobStatus::Paused,
+                        Some(PauseReason::InsufficientFunds),
+                        finished_job.funding_spent,
+                    )
+                } else {
+                    (
+                        JobStatus::Pending,
+                        None,
+                        finished_job.funding_spent + reward_plus_fee,
+                    )
+                };
+
+                let mut top_up_source = finished_job.top_up_source.clone();
+                if let (Some(amount), Some(source), false) =
+                    (top_up, top_up_source.as_mut(), insufficient_fee)
+                {
+                    // Job owner's top up source refunds the funding account
+                    msgs.push(build_top_up_msg(
+                        source.account.to_string(),
+                        funding_account_addr.to_string(),
+                        amount,
+                        config.fee_denom.clone(),
+                    ));
+                    source.allowance -= amount;
+                    new_job_attrs.push(Attribute::new("job_top_up_amount", amount));
+                }
+
                 let new_job = JobQueue::add(
                     deps.storage,
                     Job {
@@ -164,7 +228,7 @@ pub fn execute_job(
                         name: finished_job.name.clone(),
                         description: finished_job.description,
                         labels: finished_job.labels,
-                        status: JobStatus::Pending,
+                        status,
                         executions: finished_job.executions,
                         terminate_condition: finished_job.terminate_condition.clone(),
                         vars: new_vars,
@@ -174,24 +238,33 @@ pub fn execute_job(
                         duration_days: finished_job.duration_days,
                         created_at_time: Uint64::from(env.block.time.seconds()),
                         funding_account: finished_job.funding_account.clone(),
+                        execution_count: job_env.execution_count,
+                        funding_budget: finished_job.funding_budget,
+                        funding_spent,
+                        top_up_source,
+                        grace_mode: finished_job.grace_mode,
+                        pause_reason,
                     },
                 )?;
 
-                msgs.push(build_account_execute_generic_msgs(
-                    funding_account_addr.to_string(),
-                    vec![
-                        // Job owner's funding account sends fee to fee collector
-                        build_transfer_native_funds_msg(
-                            config.fee_collector.to_string(),
-                            vec![Coin::new(total_fees.u128(), config.fee_denom.clone())],
-                        ),
-                        // Job owner's funding account sends reward to controller
-                        build_transfer_native_funds_msg(
-                            env.contract.address.to_string(),
-                            vec![Coin::new(new_job.reward.u128(), config.fee_denom.clone())],
-                        ),
-                    ],
-                ));
+                // paused job pays reward + fees when resumed
+                if !insufficient_fee {
+                    msgs.push(build_account_execute_generic_msgs(
+                        funding_account_addr.to_string(),
+                        vec![
+                            // Job owner's funding account sends fee to fee collector
+                            build_transfer_native_funds_msg(
+                                config.fee_collector.to_string(),
+                                vec![Coin::new(total_fees.u128(), config.fee_denom.clone())],
+                            ),
+                            // Job owner's funding account sends reward to controller
+                            build_transfer_native_funds_msg(
+                                env.contract.address.to_string(),
+                                vec![Coin::new(new_job.reward.u128(), config.fee_denom.clone())],
+                            ),
+                        ],
+                    ));
+                }
 
                 new_job_attrs.push(Attribute::new("action", "create_job"));
                 new_job_attrs.push(Attribute::new("job_id", new_job.id));
@@ -281,8 +354,8 @@ pub fn instantiate_sub_contracts(
         &account_tracker_instantiate_event
             .attributes
             .iter()
-            .cloned()
             .find(|attr| attr.key == "account_tracker")
+            .cloned()
             .ok_or_else(|| StdError::generic_err("cannot find `account_tracker` attribute"))?
             .value,
     )?;
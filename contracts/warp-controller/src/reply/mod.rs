@@ -1,2 +1 @@
-pub(crate) mod account;
 pub(crate) mod job;
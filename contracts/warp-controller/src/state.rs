@@ -1,8 +1,8 @@
-use cosmwasm_std::{Env, Storage, Uint64};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex, UniqueIndex};
+use cosmwasm_std::{Addr, Env, Storage, Uint64};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
 
 use controller::{
-    job::{Job, JobStatus, UpdateJobMsg},
+    job::{Job, JobStatus, PauseReason, UpdateJobMsg},
     Config, State,
 };
 
@@ -67,6 +67,9 @@ pub const QUERY_PAGE_SIZE: u32 = 50;
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STATE: Item<State> = Item::new("state");
 
+// Key is the account owner, value is the index of the next account created for the owner
+pub const ACCOUNT_INDICES: Map<&Addr, Uint64> = Map::new("account_indices");
+
 pub struct JobQueue;
 
 impl JobQueue {
@@ -117,6 +120,12 @@ impl JobQueue {
                 duration_days: job.duration_days,
                 created_at_time: Uint64::from(env.block.time.seconds()),
                 funding_account: job.funding_account,
+                execution_count: job.execution_count,
+                funding_budget: job.funding_budget,
+                funding_spent: job.funding_spent,
+                top_up_source: job.top_up_source,
+                grace_mode: job.grace_mode,
+                pause_reason: job.pause_reason,
             }),
         })?;
 
@@ -149,12 +158,60 @@ impl JobQueue {
                 duration_days: job.duration_days,
                 created_at_time: job.created_at_time,
                 funding_account: job.funding_account,
+                execution_count: job.execution_count,
+                funding_budget: job.funding_budget,
+                funding_spent: job.funding_spent,
+                top_up_source: job.top_up_source,
+                grace_mode: job.grace_mode,
+                pause_reason: job.pause_reason,
             }),
         })?;
 
         Ok(job)
     }
 
+    pub fn pause(
+        storage: &mut dyn Storage,
+        env: Env,
+        job_id: u64,
+        pause_reason: PauseReason,
+    ) -> Result<Job, ContractError> {
+        let job = PENDING_JOBS().update(storage, job_id, |j| match j {
+            None => Err(ContractError::JobDoesNotExist {}),
+            Some(job) if job.status != JobStatus::Pending => Err(ContractError::JobNotActive {}),
+            Some(job) => Ok(Job {
+                last_update_time: Uint64::new(env.block.time.seconds()),
+                status: JobStatus::Paused,
+                pause_reason: Some(pause_reason),
+                ..job
+            }),
+        })?;
+
+        Ok(job)
+    }
+
+    // resumes a job paused by its owner, paused time doesn't count toward eviction
+    pub fn resume(storage: &mut dyn Storage, env: Env, job_id: u64) -> Result<Job, ContractError> {
+        let job = PENDING_JOBS().update(storage, job_id, |j| match j {
+            None => Err(ContractError::JobDoesNotExist {}),
+            Some(job) => match job.pause_reason {
+                Some(PauseReason::Owner { paused_at }) if job.status == JobStatus::Paused => {
+                    let paused_for = env.block.time.seconds().saturating_sub(paused_at.u64());
+                    Ok(Job {
+                        last_update_time: Uint64::new(env.block.time.seconds()),
+                        status: JobStatus::Pending,
+                        pause_reason: None,
+                        created_at_time: job.created_at_time.checked_add(paused_for.into())?,
+                        ..job
+                    })
+                }
+                _ => Err(ContractError::JobNotPaused {}),
+            },
+        })?;
+
+        Ok(job)
+    }
+
     pub fn finalize(
         storage: &mut dyn Storage,
         env: Env,
@@ -186,6 +243,12 @@ impl JobQueue {
             duration_days: job.duration_days,
             created_at_time: job.created_at_time,
             funding_account: job.funding_account,
+            execution_count: job.execution_count,
+            funding_budget: job.funding_budget,
+            funding_spent: job.funding_spent,
+            top_up_source: job.top_up_source,
+            grace_mode: job.grace_mode,
+            pause_reason: job.pause_reason,
         };
 
         FINISHED_JOBS().update(storage, job_id, |j| match j {
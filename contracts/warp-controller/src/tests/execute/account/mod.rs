@@ -0,0 +1 @@
+mod test_create_funding_account;
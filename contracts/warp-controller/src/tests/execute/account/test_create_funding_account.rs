@@ -0,0 +1,61 @@
+use cosmwasm_std::{coin, Uint64};
+
+use account::{ConfigResponse, QueryConfigMsg, QueryMsg};
+use account_tracker::AccountStatus;
+use controller::{CreateFundingAccountMsg, ExecuteMsg};
+
+use crate::tests::helpers::{attribute, create_job_msg, warp_test, DENOM};
+
+#[test]
+fn test_create_funding_account_at_predicted_address() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+
+    let predicted = test.account_address(&user, None);
+
+    let res = test
+        .execute(
+            &user,
+            ExecuteMsg::CreateFundingAccount(CreateFundingAccountMsg {}),
+            &[coin(100, DENOM)],
+        )
+        .unwrap();
+    let funding_account = predicted.account;
+
+    // instantiate2 creates the account at the predicted address
+    assert_eq!(
+        attribute(&res, "funding_account_address"),
+        Some(funding_account.to_string())
+    );
+    assert_eq!(attribute(&res, "account_index"), Some("0".to_string()));
+    assert_eq!(
+        attribute(&res, "_contract_address").as_deref(),
+        Some(test.controller.as_str())
+    );
+    let account_config: ConfigResponse = test
+        .app
+        .wrap()
+        .query_wasm_smart(&funding_account, &QueryMsg::QueryConfig(QueryConfigMsg {}))
+        .unwrap();
+    assert_eq!(account_config.config.owner, user);
+    assert_eq!(account_config.config.creator_addr, test.controller);
+    assert_eq!(test.balance(&funding_account), 100);
+
+    // funding account is registered as free right away
+    let tracked = test
+        .funding_account(&user, &funding_account)
+        .funding_account
+        .unwrap();
+    assert_eq!(tracked.account_status, AccountStatus::Free);
+
+    // job and funding accounts share the index sequence of the owner
+    let next = test.account_address(&user, None);
+    assert_eq!(next.index, Uint64::one());
+    let job = test.create_job(create_job_msg()).unwrap();
+    assert_eq!(job.account, next.account);
+    assert_eq!(test.account_address(&user, None).index, Uint64::new(2));
+    assert_eq!(
+        test.create_funding_account(10),
+        test.account_address(&user, Some(Uint64::new(2))).account
+    );
+}
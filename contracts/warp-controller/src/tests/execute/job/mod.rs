@@ -1,4 +1,6 @@
 mod test_create_job;
 mod test_delete_job;
 mod test_execute_job;
+mod test_pause_job;
+mod test_resume_job;
 mod test_update_job;
@@ -1,3 +1,13 @@
+use cosmwasm_std::Uint64;
+
+use account::{ConfigResponse, QueryConfigMsg, QueryMsg};
+use account_tracker::AccountStatus;
+use controller::job::{CreateJobMsg, DeleteJobMsg};
+use controller::ExecuteMsg;
+
+use crate::tests::helpers::{assert_err, create_job_msg, warp_test};
+use crate::ContractError;
+
 #[test]
 fn test_create_job_success() {}
 
@@ -24,3 +34,119 @@ fn test_create_job_name_too_short() {}
 
 #[test]
 fn test_create_job_name_too_long() {}
+
+#[test]
+fn test_create_job_account_at_predicted_address() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+
+    let predicted = test.account_address(&user, None);
+    let job = test.create_job(create_job_msg()).unwrap();
+
+    // job account is created with instantiate2 at the predicted address, funded and taken
+    assert_eq!(job.account, predicted.account);
+    let account_config: ConfigResponse = test
+        .app
+        .wrap()
+        .query_wasm_smart(&job.account, &QueryMsg::QueryConfig(QueryConfigMsg {}))
+        .unwrap();
+    assert_eq!(account_config.config.owner, user);
+    assert_eq!(account_config.config.creator_addr, test.controller);
+
+    let job_account = test.job_account(&user, &job.account).job_account.unwrap();
+    assert_eq!(job_account.account_status, AccountStatus::Taken);
+    assert_eq!(job_account.taken_by_job_id, job.id);
+
+    // a second job gets a new account at the next index
+    let predicted = test.account_address(&user, None);
+    assert_eq!(predicted.index, Uint64::one());
+    let job = test.create_job(create_job_msg()).unwrap();
+    assert_eq!(job.account, predicted.account);
+}
+
+#[test]
+fn test_create_job_with_job_account() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+    let other = test.owner.clone();
+    test.fund(&other, 1000);
+
+    let first_job = test.create_job(create_job_msg()).unwrap();
+    let account = first_job.account.clone();
+
+    // account taken by another job
+    assert_err(
+        test.create_job(CreateJobMsg {
+            job_account: Some(account.clone()),
+            ..create_job_msg()
+        }),
+        ContractError::JobAccountNotFree {},
+    );
+
+    // job account can't be combined with fresh_job_account
+    assert_err(
+        test.create_job(CreateJobMsg {
+            job_account: Some(account.clone()),
+            fresh_job_account: Some(true),
+            ..create_job_msg()
+        }),
+        ContractError::JobAccountWithFreshJobAccount {},
+    );
+
+    test.execute(
+        &user,
+        ExecuteMsg::DeleteJob(DeleteJobMsg { id: first_job.id }),
+        &[],
+    )
+    .unwrap();
+
+    // free account owned by someone else
+    assert_err(
+        test.create_job_as(
+            &other,
+            CreateJobMsg {
+                job_account: Some(account.clone()),
+                ..create_job_msg()
+            },
+        ),
+        ContractError::AccountDoesNotExist {},
+    );
+
+    // free account of the job owner
+    let job = test
+        .create_job(CreateJobMsg {
+            job_account: Some(account.clone()),
+            ..create_job_msg()
+        })
+        .unwrap();
+    assert_eq!(job.account, account);
+    assert_eq!(
+        test.job_account(&user, &account)
+            .job_account
+            .unwrap()
+            .taken_by_job_id,
+        job.id
+    );
+
+    test.execute(
+        &user,
+        ExecuteMsg::DeleteJob(DeleteJobMsg { id: job.id }),
+        &[],
+    )
+    .unwrap();
+
+    // fresh job account is created even though a free one exists
+    let predicted = test.account_address(&user, None);
+    let job = test
+        .create_job(CreateJobMsg {
+            fresh_job_account: Some(true),
+            ..create_job_msg()
+        })
+        .unwrap();
+    assert_eq!(job.account, predicted.account);
+    assert_ne!(job.account, account);
+
+    // first free job account is used by default
+    let job = test.create_job(create_job_msg()).unwrap();
+    assert_eq!(job.account, account);
+}
@@ -1,3 +1,10 @@
+use controller::job::{CreateJobMsg, DeleteJobMsg, JobStatus, PauseJobMsg, PauseReason};
+use controller::ExecuteMsg;
+
+use crate::tests::helpers::{
+    attribute, create_job_msg, create_recurring_job_msg, warp_test, REWARD, REWARD_PLUS_FEE,
+};
+
 #[test]
 fn test_delete_job_successful() {}
 
@@ -9,3 +16,70 @@ fn test_delete_job_finished() {}
 
 #[test]
 fn test_delete_job_does_not_exist() {}
+
+#[test]
+fn test_delete_job_paused_insufficient_funds() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+    let controller = test.controller.clone();
+    let fee_collector = test.fee_collector.clone();
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            grace_mode: Some(true),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+    test.execute_job(job.id).unwrap();
+    let paused_job = test.next_job(&job).unwrap();
+    assert_eq!(
+        paused_job.pause_reason,
+        Some(PauseReason::InsufficientFunds)
+    );
+
+    let user_balance = test.balance(&user);
+    let controller_balance = test.balance(&controller);
+    let fee_collector_balance = test.balance(&fee_collector);
+
+    // reward of the paused iteration was never paid, so nothing is refunded
+    let res = test
+        .execute(
+            &user,
+            ExecuteMsg::DeleteJob(DeleteJobMsg { id: paused_job.id }),
+            &[],
+        )
+        .unwrap();
+    assert_eq!(attribute(&res, "deletion_fee"), Some("0".to_string()));
+    assert_eq!(test.job(paused_job.id).status, JobStatus::Cancelled);
+    assert_eq!(test.balance(&user), user_balance);
+    assert_eq!(test.balance(&controller), controller_balance);
+    assert_eq!(test.balance(&fee_collector), fee_collector_balance);
+}
+
+#[test]
+fn test_delete_job_paused_by_owner() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+    let fee_collector = test.fee_collector.clone();
+
+    let job = test.create_job(create_job_msg()).unwrap();
+    test.execute(&user, ExecuteMsg::PauseJob(PauseJobMsg { id: job.id }), &[])
+        .unwrap();
+
+    let user_balance = test.balance(&user);
+    let fee_collector_balance = test.balance(&fee_collector);
+
+    // paid reward is refunded minus the cancellation fee
+    let fee = REWARD / 10;
+    let res = test
+        .execute(
+            &user,
+            ExecuteMsg::DeleteJob(DeleteJobMsg { id: job.id }),
+            &[],
+        )
+        .unwrap();
+    assert_eq!(attribute(&res, "deletion_fee"), Some(fee.to_string()));
+    assert_eq!(test.balance(&user), user_balance + REWARD - fee);
+    assert_eq!(test.balance(&fee_collector), fee_collector_balance + fee);
+}
@@ -1,3 +1,22 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Decimal256, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw_multi_test::{ContractWrapper, Executor};
+
+use controller::job::{CreateJobMsg, Execution, JobStatus, PauseReason};
+use controller::{CloseFundingAccountMsg, ExecuteMsg};
+use resolver::variable::{
+    Encoding, PriceExpr, PriceQueryMsg, PriceResponse, PriceSource, PriceVariable, Variable,
+    VariableKind,
+};
+
+use crate::tests::helpers::{
+    assert_err, attribute, create_job_msg, create_recurring_job_msg, top_up_source, warp_test,
+    REWARD_PLUS_FEE,
+};
+use crate::ContractError;
+
 #[test]
 fn test_execute_job_successful() {}
 
@@ -20,3 +39,270 @@ fn test_execute_job_condition_improper_selector() {}
 
 #[test]
 fn test_execute_job_does_not_exist() {}
+
+#[test]
+fn test_execute_job_funding_budget() {
+    let mut test = warp_test();
+    let funding_account = test.create_funding_account(100);
+
+    // budget must cover the first iteration
+    assert_err(
+        test.create_job(CreateJobMsg {
+            funding_budget: Some(Uint128::new(REWARD_PLUS_FEE - 1)),
+            ..create_recurring_job_msg(&funding_account)
+        }),
+        ContractError::FundingBudgetExhausted {},
+    );
+
+    // budget of two iterations
+    let job = test
+        .create_job(CreateJobMsg {
+            funding_budget: Some(Uint128::new(2 * REWARD_PLUS_FEE)),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+
+    // first iteration is paid from the funding account and counted as spent
+    assert_eq!(job.funding_spent, Uint128::new(REWARD_PLUS_FEE));
+    assert_eq!(test.balance(&funding_account), 100 - REWARD_PLUS_FEE);
+
+    let res = test.execute_job(job.id).unwrap();
+    assert_eq!(attribute(&res, "creation_status"), None);
+    let next_job = test.next_job(&job).unwrap();
+    assert_eq!(next_job.status, JobStatus::Pending);
+    assert_eq!(next_job.funding_spent, Uint128::new(2 * REWARD_PLUS_FEE));
+    assert_eq!(test.balance(&funding_account), 100 - 2 * REWARD_PLUS_FEE);
+
+    // recurrence stops once the next iteration would exceed the budget
+    let res = test.execute_job(next_job.id).unwrap();
+    assert_eq!(
+        attribute(&res, "creation_status"),
+        Some("failed_budget_exhausted".to_string())
+    );
+    assert_eq!(test.next_job(&next_job), None);
+    assert_eq!(test.job(next_job.id).status, JobStatus::Executed);
+    assert_eq!(test.balance(&funding_account), 100 - 2 * REWARD_PLUS_FEE);
+}
+
+#[test]
+fn test_execute_job_top_up() {
+    let mut test = warp_test();
+    // funds the first iteration only
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+    let source = test.create_funding_account(100);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            top_up_source: top_up_source(&source, 2 * REWARD_PLUS_FEE),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+    assert_eq!(test.balance(&funding_account), 0);
+
+    // source covers the shortfall of the next iteration
+    let res = test.execute_job(job.id).unwrap();
+    assert_eq!(
+        attribute(&res, "job_top_up_amount"),
+        Some(REWARD_PLUS_FEE.to_string())
+    );
+    let next_job = test.next_job(&job).unwrap();
+    assert_eq!(next_job.status, JobStatus::Pending);
+    assert_eq!(next_job.funding_spent, Uint128::new(2 * REWARD_PLUS_FEE));
+    assert_eq!(
+        next_job.top_up_source,
+        top_up_source(&source, REWARD_PLUS_FEE)
+    );
+    assert_eq!(test.balance(&funding_account), 0);
+    assert_eq!(test.balance(&source), 100 - REWARD_PLUS_FEE);
+
+    let res = test.execute_job(next_job.id).unwrap();
+    assert_eq!(
+        attribute(&res, "job_top_up_amount"),
+        Some(REWARD_PLUS_FEE.to_string())
+    );
+    let last_job = test.next_job(&next_job).unwrap();
+    assert_eq!(last_job.top_up_source, top_up_source(&source, 0));
+    assert_eq!(test.balance(&source), 100 - 2 * REWARD_PLUS_FEE);
+
+    // allowance is used up, source keeps its balance
+    let res = test.execute_job(last_job.id).unwrap();
+    assert_eq!(attribute(&res, "job_top_up_amount"), None);
+    assert_eq!(
+        attribute(&res, "creation_status"),
+        Some("failed_insufficient_fee".to_string())
+    );
+    assert!(test.next_job(&last_job).is_none());
+    assert_eq!(test.balance(&source), 100 - 2 * REWARD_PLUS_FEE);
+}
+
+#[test]
+fn test_execute_job_top_up_allowance_too_low() {
+    let mut test = warp_test();
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+    let source = test.create_funding_account(100);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            top_up_source: top_up_source(&source, REWARD_PLUS_FEE - 1),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+
+    let res = test.execute_job(job.id).unwrap();
+    assert_eq!(attribute(&res, "job_top_up_amount"), None);
+    assert_eq!(
+        attribute(&res, "creation_status"),
+        Some("failed_insufficient_fee".to_string())
+    );
+    assert!(test.next_job(&job).is_none());
+    assert_eq!(test.balance(&source), 100);
+}
+
+#[test]
+fn test_execute_job_top_up_source_closed() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+    let source = test.create_funding_account(100);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            top_up_source: top_up_source(&source, 100),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+
+    test.execute(
+        &user,
+        ExecuteMsg::CloseFundingAccount(CloseFundingAccountMsg {
+            account: source.to_string(),
+        }),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(test.balance(&source), 0);
+    assert!(test
+        .funding_account(&user, &source)
+        .funding_account
+        .is_none());
+
+    // untracked source is not drawn from even when it holds funds
+    test.fund(&source, 100);
+    let res = test.execute_job(job.id).unwrap();
+    assert_eq!(attribute(&res, "job_top_up_amount"), None);
+    assert_eq!(
+        attribute(&res, "creation_status"),
+        Some("failed_insufficient_fee".to_string())
+    );
+    assert!(test.next_job(&job).is_none());
+    assert_eq!(test.balance(&source), 100);
+}
+
+#[test]
+fn test_execute_job_grace_mode() {
+    let mut test = warp_test();
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            grace_mode: Some(true),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+
+    // next iteration is created paused instead of failing, nothing is paid for it
+    let res = test.execute_job(job.id).unwrap();
+    assert_eq!(attribute(&res, "creation_status"), None);
+    let paused_job = test.next_job(&job).unwrap();
+    assert_eq!(paused_job.status, JobStatus::Paused);
+    assert_eq!(
+        paused_job.pause_reason,
+        Some(PauseReason::InsufficientFunds)
+    );
+    assert_eq!(paused_job.funding_spent, Uint128::new(REWARD_PLUS_FEE));
+    assert_eq!(test.balance(&funding_account), 0);
+
+    // paused job is not executable
+    assert_err(
+        test.execute_job(paused_job.id),
+        ContractError::JobNotActive {},
+    );
+}
+
+fn stale_oracle_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn stale_oracle_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+// price published an hour ago
+fn stale_oracle_query(_deps: Deps, env: Env, _msg: PriceQueryMsg) -> StdResult<Binary> {
+    to_json_binary(&PriceResponse {
+        price: Decimal256::one(),
+        confidence: None,
+        publish_time: env.block.time.seconds() - 3600,
+    })
+}
+
+#[test]
+fn test_execute_job_stale_price() {
+    let mut test = warp_test();
+    let owner = test.owner.clone();
+
+    let oracle_code_id = test.app.store_code(Box::new(ContractWrapper::new(
+        stale_oracle_execute,
+        stale_oracle_instantiate,
+        stale_oracle_query,
+    )));
+    let oracle = test
+        .app
+        .instantiate_contract(oracle_code_id, owner, &Empty {}, &[], "oracle", None)
+        .unwrap();
+
+    let price = Variable::Price(PriceVariable {
+        kind: VariableKind::Decimal,
+        name: "price".to_string(),
+        encode: Encoding::None,
+        init_fn: PriceExpr {
+            source: PriceSource::Custom {
+                contract_addr: oracle.to_string(),
+            },
+            base: "luna".to_string(),
+            quote: "usd".to_string(),
+            max_age_seconds: 60,
+            max_confidence: None,
+        },
+        reinitialize: false,
+        value: None,
+        update_fn: None,
+    });
+
+    let job = test
+        .create_job(CreateJobMsg {
+            vars: serde_json_wasm::to_string(&vec![price]).unwrap(),
+            executions: vec![Execution {
+                condition: r#"{"expr":{"decimal":{"left":{"ref":"$warp.variable.price"},"op":"gt","right":{"simple":"0"}}}}"#.to_string(),
+                msgs: "[]".to_string(),
+            }],
+            ..create_job_msg()
+        })
+        .unwrap();
+
+    // a stale price fails the execution without failing the job
+    let err = test.execute_job(job.id).unwrap_err();
+    assert!(format!("{:?}", err).contains("is stale"));
+    let job = test.job(job.id);
+    assert_eq!(job.status, JobStatus::Pending);
+}
@@ -0,0 +1,95 @@
+use cosmwasm_std::Uint64;
+
+use controller::job::{EvictJobMsg, JobStatus, PauseJobMsg, PauseReason, ResumeJobMsg};
+use controller::ExecuteMsg;
+
+use crate::tests::helpers::{assert_err, create_job_msg, warp_test, DAY};
+use crate::ContractError;
+
+#[test]
+fn test_pause_job_excluded_from_eviction_period() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+    let keeper = test.keeper.clone();
+
+    let job = test.create_job(create_job_msg()).unwrap();
+    let evict_msg = || ExecuteMsg::EvictJob(EvictJobMsg { id: job.id });
+
+    test.advance_time(DAY / 2);
+    let paused_at = test.now();
+    test.execute(&user, ExecuteMsg::PauseJob(PauseJobMsg { id: job.id }), &[])
+        .unwrap();
+    let paused_job = test.job(job.id);
+    assert_eq!(paused_job.status, JobStatus::Paused);
+    assert_eq!(
+        paused_job.pause_reason,
+        Some(PauseReason::Owner {
+            paused_at: Uint64::new(paused_at)
+        })
+    );
+
+    // paused jobs are neither evicted nor executed
+    test.advance_time(2 * DAY);
+    assert_err(
+        test.execute(&keeper, evict_msg(), &[]),
+        ContractError::Unauthorized {},
+    );
+    assert_err(test.execute_job(job.id), ContractError::JobNotActive {});
+
+    // creation time is shifted by the paused time
+    test.execute(
+        &user,
+        ExecuteMsg::ResumeJob(ResumeJobMsg { id: job.id }),
+        &[],
+    )
+    .unwrap();
+    let resumed_job = test.job(job.id);
+    assert_eq!(resumed_job.status, JobStatus::Pending);
+    assert_eq!(resumed_job.pause_reason, None);
+    assert_eq!(
+        resumed_job.created_at_time,
+        job.created_at_time + Uint64::new(2 * DAY)
+    );
+
+    // half a day of the eviction period is left
+    assert_err(
+        test.execute(&keeper, evict_msg(), &[]),
+        ContractError::EvictionPeriodNotElapsed {},
+    );
+
+    test.advance_time(DAY / 2);
+    test.execute(&keeper, evict_msg(), &[]).unwrap();
+    assert_eq!(test.job(job.id).status, JobStatus::Evicted);
+}
+
+#[test]
+fn test_pause_job_unauthorized() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+    let keeper = test.keeper.clone();
+
+    let job = test.create_job(create_job_msg()).unwrap();
+
+    assert_err(
+        test.execute(
+            &keeper,
+            ExecuteMsg::PauseJob(PauseJobMsg { id: job.id }),
+            &[],
+        ),
+        ContractError::Unauthorized {},
+    );
+
+    test.execute(&user, ExecuteMsg::PauseJob(PauseJobMsg { id: job.id }), &[])
+        .unwrap();
+
+    // only the owner resumes a job they paused
+    assert_err(
+        test.execute(
+            &keeper,
+            ExecuteMsg::ResumeJob(ResumeJobMsg { id: job.id }),
+            &[],
+        ),
+        ContractError::Unauthorized {},
+    );
+    assert_eq!(test.job(job.id).status, JobStatus::Paused);
+}
@@ -0,0 +1,141 @@
+use cosmwasm_std::Uint128;
+
+use controller::job::{CreateJobMsg, JobStatus, PauseReason, ResumeJobMsg};
+use controller::{ExecuteMsg, UpdateConfigMsg};
+
+use crate::tests::helpers::{
+    assert_err, create_recurring_job_msg, warp_test, REWARD, REWARD_PLUS_FEE, TOTAL_FEES,
+};
+use crate::ContractError;
+
+fn update_creation_fee(fee: u128) -> ExecuteMsg {
+    ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        owner: None,
+        fee_collector: None,
+        minimum_reward: None,
+        cancellation_fee_rate: None,
+        creation_fee_min: Some(Uint128::new(fee)),
+        creation_fee_max: Some(Uint128::new(fee)),
+        burn_fee_min: None,
+        maintenance_fee_min: None,
+        maintenance_fee_max: None,
+        duration_days_min: None,
+        duration_days_max: None,
+        duration_days_limit: None,
+        queue_size_left: None,
+        queue_size_right: None,
+        burn_fee_rate: None,
+    })
+}
+
+#[test]
+fn test_resume_job_funding_budget() {
+    let mut test = warp_test();
+    let owner = test.owner.clone();
+    let keeper = test.keeper.clone();
+    // funds the first iteration only
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            funding_budget: Some(Uint128::new(60)),
+            grace_mode: Some(true),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+
+    test.execute_job(job.id).unwrap();
+    let paused_job = test.next_job(&job).unwrap();
+    assert_eq!(paused_job.status, JobStatus::Paused);
+    assert_eq!(
+        paused_job.pause_reason,
+        Some(PauseReason::InsufficientFunds)
+    );
+    assert_eq!(paused_job.funding_spent, Uint128::new(REWARD_PLUS_FEE));
+
+    test.fund(&funding_account, 100);
+
+    // higher fees push the iteration over the budget
+    test.execute(&owner, update_creation_fee(20), &[]).unwrap();
+    assert_err(
+        test.execute(
+            &keeper,
+            ExecuteMsg::ResumeJob(ResumeJobMsg { id: paused_job.id }),
+            &[],
+        ),
+        ContractError::FundingBudgetExhausted {},
+    );
+    assert_eq!(test.job(paused_job.id).status, JobStatus::Paused);
+    assert_eq!(test.balance(&funding_account), 100);
+
+    // within the budget again
+    test.execute(&owner, update_creation_fee(5), &[]).unwrap();
+    test.execute(
+        &keeper,
+        ExecuteMsg::ResumeJob(ResumeJobMsg { id: paused_job.id }),
+        &[],
+    )
+    .unwrap();
+    let resumed_job = test.job(paused_job.id);
+    assert_eq!(resumed_job.status, JobStatus::Pending);
+    assert_eq!(resumed_job.funding_spent, Uint128::new(2 * REWARD_PLUS_FEE));
+    assert_eq!(test.balance(&funding_account), 100 - REWARD_PLUS_FEE);
+}
+
+#[test]
+fn test_resume_job_pays_once() {
+    let mut test = warp_test();
+    let keeper = test.keeper.clone();
+    let controller = test.controller.clone();
+    let fee_collector = test.fee_collector.clone();
+    let funding_account = test.create_funding_account(REWARD_PLUS_FEE);
+
+    let job = test
+        .create_job(CreateJobMsg {
+            grace_mode: Some(true),
+            ..create_recurring_job_msg(&funding_account)
+        })
+        .unwrap();
+    test.execute_job(job.id).unwrap();
+    let paused_job = test.next_job(&job).unwrap();
+    let resume_msg = || ExecuteMsg::ResumeJob(ResumeJobMsg { id: paused_job.id });
+
+    // funding account is not refunded yet
+    assert_err(
+        test.execute(&keeper, resume_msg(), &[]),
+        ContractError::InsufficientFundsToPayForRewardAndFee {},
+    );
+    assert_eq!(test.job(paused_job.id).status, JobStatus::Paused);
+
+    test.fund(&funding_account, 100);
+    let controller_balance = test.balance(&controller);
+    let fee_collector_balance = test.balance(&fee_collector);
+
+    test.execute(&keeper, resume_msg(), &[]).unwrap();
+    let resumed_job = test.job(paused_job.id);
+    assert_eq!(resumed_job.status, JobStatus::Pending);
+    assert_eq!(resumed_job.pause_reason, None);
+    assert_eq!(resumed_job.funding_spent, Uint128::new(2 * REWARD_PLUS_FEE));
+    assert_eq!(test.balance(&funding_account), 100 - REWARD_PLUS_FEE);
+    assert_eq!(test.balance(&controller), controller_balance + REWARD);
+    assert_eq!(
+        test.balance(&fee_collector),
+        fee_collector_balance + TOTAL_FEES
+    );
+
+    // resumed job is not paid for again
+    assert_err(
+        test.execute(&keeper, resume_msg(), &[]),
+        ContractError::JobNotPaused {},
+    );
+    assert_eq!(
+        test.job(paused_job.id).funding_spent,
+        resumed_job.funding_spent
+    );
+    assert_eq!(test.balance(&funding_account), 100 - REWARD_PLUS_FEE);
+    assert_eq!(test.balance(&controller), controller_balance + REWARD);
+    assert_eq!(
+        test.balance(&fee_collector),
+        fee_collector_balance + TOTAL_FEES
+    );
+}
@@ -1,3 +1,4 @@
+mod account;
 mod controller;
 mod job;
 mod template;
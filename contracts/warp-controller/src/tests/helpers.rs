@@ -1 +1,375 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_std::{coin, Addr, Coin, Empty, Uint128, Uint64};
+use cw_multi_test::addons::{MockAddressGenerator, MockApiBech32};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
 
+use account_tracker::{FundingAccountResponse, JobAccountResponse};
+use controller::job::{
+    CreateJobMsg, ExecuteJobMsg, Execution, Job, JobResponse, JobsResponse, QueryJobMsg,
+    QueryJobsMsg, TopUpSource,
+};
+use controller::{
+    AccountAddressResponse, CreateFundingAccountMsg, ExecuteMsg, InstantiateMsg,
+    QueryAccountAddressMsg, QueryMsg,
+};
+
+use crate::ContractError;
+
+pub type WarpApp = App<BankKeeper, MockApiBech32>;
+
+pub const DENOM: &str = "uluna";
+pub const DAY: u64 = 86400;
+// creation + maintenance + burn fee
+pub const TOTAL_FEES: u128 = 15;
+pub const REWARD: u128 = 10;
+pub const REWARD_PLUS_FEE: u128 = REWARD + TOTAL_FEES;
+
+// condition that always resolves to true
+pub const TRUE_CONDITION: &str =
+    r#"{"expr":{"uint":{"left":{"simple":"1"},"op":"eq","right":{"simple":"1"}}}}"#;
+
+pub struct WarpTest {
+    pub app: WarpApp,
+    pub controller: Addr,
+    pub account_tracker: Addr,
+    pub owner: Addr,
+    pub user: Addr,
+    pub keeper: Addr,
+    pub fee_collector: Addr,
+}
+
+fn contract_warp_controller() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )
+        .with_reply(crate::contract::reply),
+    )
+}
+
+fn contract_warp_account() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            warp_account::contract::execute,
+            warp_account::contract::instantiate,
+            warp_account::contract::query,
+        )
+        .with_reply(warp_account::contract::reply),
+    )
+}
+
+fn contract_warp_account_tracker() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        warp_account_tracker::contract::execute,
+        warp_account_tracker::contract::instantiate,
+        warp_account_tracker::contract::query,
+    ))
+}
+
+fn contract_warp_resolver() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        warp_resolver::contract::execute,
+        warp_resolver::contract::instantiate,
+        warp_resolver::contract::query,
+    ))
+}
+
+// controller with its resolver and account tracker, warp accounts are created with instantiate2
+pub fn warp_test() -> WarpTest {
+    let api = MockApiBech32::new("terra");
+    let owner = api.addr_make("owner");
+    let user = api.addr_make("user");
+    let keeper = api.addr_make("keeper");
+    let fee_collector = api.addr_make("fee_collector");
+
+    let mut app = AppBuilder::new()
+        .with_api(api)
+        .with_wasm(WasmKeeper::new().with_address_generator(MockAddressGenerator))
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &user, vec![coin(1_000_000, DENOM)])
+                .unwrap();
+        });
+
+    let controller_code_id = app.store_code(contract_warp_controller());
+    let account_code_id = app.store_code(contract_warp_account());
+    let account_tracker_code_id = app.store_code(contract_warp_account_tracker());
+    let resolver_code_id = app.store_code(contract_warp_resolver());
+
+    let resolver = app
+        .instantiate_contract(
+            resolver_code_id,
+            owner.clone(),
+            &resolver::InstantiateMsg {},
+            &[],
+            "warp resolver",
+            None,
+        )
+        .unwrap();
+
+    let controller = app
+        .instantiate_contract(
+            controller_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: None,
+                fee_denom: DENOM.to_string(),
+                fee_collector: Some(fee_collector.to_string()),
+                warp_account_code_id: Uint64::new(account_code_id),
+                account_tracker_code_id: Uint64::new(account_tracker_code_id),
+                minimum_reward: Uint128::one(),
+                cancellation_fee_rate: Uint64::new(10),
+                resolver_address: resolver.to_string(),
+                creation_fee_min: Uint128::new(5),
+                creation_fee_max: Uint128::new(5),
+                burn_fee_min: Uint128::new(5),
+                maintenance_fee_min: Uint128::new(5),
+                maintenance_fee_max: Uint128::new(5),
+                duration_days_min: Uint64::new(1),
+                duration_days_max: Uint64::new(10),
+                duration_days_limit: Uint64::new(100),
+                queue_size_left: Uint64::new(5000),
+                queue_size_right: Uint64::new(50000),
+                burn_fee_rate: Uint128::zero(),
+            },
+            &[],
+            "warp controller",
+            Some(owner.to_string()),
+        )
+        .unwrap();
+
+    let config: controller::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &controller,
+            &QueryMsg::QueryConfig(controller::QueryConfigMsg {}),
+        )
+        .unwrap();
+
+    WarpTest {
+        app,
+        controller,
+        account_tracker: config.config.account_tracker_address,
+        owner,
+        user,
+        keeper,
+        fee_collector,
+    }
+}
+
+// one time job without funding account, paid from the sent funds
+pub fn create_job_msg() -> CreateJobMsg {
+    CreateJobMsg {
+        name: "job".to_string(),
+        description: "".to_string(),
+        labels: vec![],
+        terminate_condition: None,
+        executions: vec![Execution {
+            condition: TRUE_CONDITION.to_string(),
+            msgs: "[]".to_string(),
+        }],
+        vars: "[]".to_string(),
+        recurring: false,
+        reward: Uint128::new(REWARD),
+        operational_amount: Uint128::new(REWARD_PLUS_FEE),
+        duration_days: Uint64::one(),
+        assets_to_withdraw: None,
+        account_msgs: None,
+        cw_funds: None,
+        funding_account: None,
+        funding_budget: None,
+        top_up_source: None,
+        grace_mode: None,
+        job_account: None,
+        fresh_job_account: None,
+    }
+}
+
+// recurring job paid from the funding account
+pub fn create_recurring_job_msg(funding_account: &Addr) -> CreateJobMsg {
+    CreateJobMsg {
+        recurring: true,
+        operational_amount: Uint128::zero(),
+        funding_account: Some(funding_account.clone()),
+        ..create_job_msg()
+    }
+}
+
+pub fn top_up_source(account: &Addr, allowance: u128) -> Option<TopUpSource> {
+    Some(TopUpSource {
+        account: account.clone(),
+        allowance: Uint128::new(allowance),
+    })
+}
+
+pub fn assert_err<T>(res: AnyResult<T>, err: ContractError) {
+    match res {
+        Ok(_) => panic!("Result was not an error"),
+        Err(generic_err) => {
+            let contract_err: ContractError = generic_err.downcast().unwrap();
+            assert_eq!(contract_err, err);
+        }
+    }
+}
+
+// value of the first attribute with the given key across all events
+pub fn attribute(res: &AppResponse, key: &str) -> Option<String> {
+    res.events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.clone())
+}
+
+impl WarpTest {
+    pub fn balance(&self, addr: &Addr) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    pub fn fund(&mut self, addr: &Addr, amount: u128) {
+        let user = self.user.clone();
+        self.app
+            .send_tokens(user, addr.clone(), &[coin(amount, DENOM)])
+            .unwrap();
+    }
+
+    pub fn advance_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = block.time.plus_seconds(seconds);
+            block.height += 1;
+        });
+    }
+
+    pub fn now(&self) -> u64 {
+        self.app.block_info().time.seconds()
+    }
+
+    pub fn execute(
+        &mut self,
+        sender: &Addr,
+        msg: ExecuteMsg,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let controller = self.controller.clone();
+        self.app
+            .execute_contract(sender.clone(), controller, &msg, funds)
+    }
+
+    pub fn query<T: DeserializeOwned>(&self, msg: QueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.controller, &msg)
+            .unwrap()
+    }
+
+    // creates a funding account of the user holding amount of fee denom
+    pub fn create_funding_account(&mut self, amount: u128) -> Addr {
+        let user = self.user.clone();
+        let res = self
+            .execute(
+                &user,
+                ExecuteMsg::CreateFundingAccount(CreateFundingAccountMsg {}),
+                &[coin(amount, DENOM)],
+            )
+            .unwrap();
+
+        Addr::unchecked(attribute(&res, "funding_account_address").unwrap())
+    }
+
+    // creates a job of the user, job funds are sent for jobs without funding account
+    pub fn create_job(&mut self, msg: CreateJobMsg) -> AnyResult<Job> {
+        let user = self.user.clone();
+        self.create_job_as(&user, msg)
+    }
+
+    pub fn create_job_as(&mut self, sender: &Addr, msg: CreateJobMsg) -> AnyResult<Job> {
+        let funds = match msg.funding_account {
+            Some(_) => vec![],
+            None => vec![coin(msg.operational_amount.u128(), DENOM)],
+        };
+        let res = self.execute(sender, ExecuteMsg::CreateJob(msg), &funds)?;
+
+        let job_id = attribute(&res, "job_id").unwrap();
+        Ok(self.job(Uint64::new(job_id.parse().unwrap())))
+    }
+
+    pub fn execute_job(&mut self, id: Uint64) -> AnyResult<AppResponse> {
+        let keeper = self.keeper.clone();
+        self.execute(
+            &keeper,
+            ExecuteMsg::ExecuteJob(ExecuteJobMsg {
+                id,
+                external_inputs: None,
+            }),
+            &[],
+        )
+    }
+
+    pub fn job(&self, id: Uint64) -> Job {
+        self.query::<JobResponse>(QueryMsg::QueryJob(QueryJobMsg { id }))
+            .job
+    }
+
+    // pending or paused follow up job of a recurring job
+    pub fn next_job(&self, prev: &Job) -> Option<Job> {
+        self.query::<JobsResponse>(QueryMsg::QueryJobs(QueryJobsMsg {
+            ids: None,
+            active: None,
+            owner: Some(prev.owner.clone()),
+            name: None,
+            job_status: None,
+            condition_status: None,
+            start_after: None,
+            limit: None,
+        }))
+        .jobs
+        .into_iter()
+        .find(|job| job.prev_id == Some(prev.id))
+    }
+
+    pub fn account_address(&self, owner: &Addr, index: Option<Uint64>) -> AccountAddressResponse {
+        self.query(QueryMsg::QueryAccountAddress(QueryAccountAddressMsg {
+            owner: owner.to_string(),
+            index,
+        }))
+    }
+
+    pub fn job_account(&self, owner: &Addr, account: &Addr) -> JobAccountResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.account_tracker,
+                &account_tracker::QueryMsg::QueryJobAccount(account_tracker::QueryJobAccountMsg {
+                    account_owner_addr: owner.to_string(),
+                    account_addr: account.to_string(),
+                }),
+            )
+            .unwrap()
+    }
+
+    pub fn funding_account(&self, owner: &Addr, account: &Addr) -> FundingAccountResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.account_tracker,
+                &account_tracker::QueryMsg::QueryFundingAccount(
+                    account_tracker::QueryFundingAccountMsg {
+                        account_owner_addr: owner.to_string(),
+                        account_addr: account.to_string(),
+                    },
+                ),
+            )
+            .unwrap()
+    }
+}
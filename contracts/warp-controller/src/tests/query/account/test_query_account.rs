@@ -1 +1,31 @@
+use cosmwasm_std::Uint64;
 
+use crate::tests::helpers::{create_job_msg, warp_test};
+
+#[test]
+fn test_query_account_address() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+
+    // next index of an owner without accounts is 0
+    let next = test.account_address(&user, None);
+    assert_eq!(next.index, Uint64::zero());
+    assert_eq!(next, test.account_address(&user, Some(Uint64::zero())));
+
+    // addresses differ per owner and index
+    let other = test.account_address(&test.owner.clone(), None);
+    assert_ne!(other.account, next.account);
+    assert_ne!(
+        test.account_address(&user, Some(Uint64::one())).account,
+        next.account
+    );
+
+    // creating an account reserves the index
+    let job = test.create_job(create_job_msg()).unwrap();
+    assert_eq!(job.account, next.account);
+    assert_eq!(test.account_address(&user, None).index, Uint64::one());
+    assert_eq!(
+        test.account_address(&user, Some(Uint64::zero())).account,
+        job.account
+    );
+}
@@ -1,3 +1,8 @@
+use controller::job::{JobStatus, JobsResponse, PauseJobMsg, QueryJobsMsg};
+use controller::{ExecuteMsg, QueryMsg};
+
+use crate::tests::helpers::{create_job_msg, warp_test};
+
 #[test]
 fn test_query_jobs_successful_under_50() {}
 
@@ -19,3 +24,44 @@ fn test_query_jobs_limit_over_50() {
 fn test_query_jobs_by_id_limit_over_50() {
     //should fail out here
 }
+
+#[test]
+fn test_query_jobs_by_reward_hides_paused() {
+    let mut test = warp_test();
+    let user = test.user.clone();
+
+    let job = test.create_job(create_job_msg()).unwrap();
+    let paused_job = test.create_job(create_job_msg()).unwrap();
+    test.execute(
+        &user,
+        ExecuteMsg::PauseJob(PauseJobMsg { id: paused_job.id }),
+        &[],
+    )
+    .unwrap();
+
+    // without owner and ids, jobs are listed by reward
+    let query_jobs_by_reward = |job_status: Option<JobStatus>| {
+        QueryMsg::QueryJobs(QueryJobsMsg {
+            ids: None,
+            active: None,
+            owner: None,
+            name: None,
+            job_status,
+            condition_status: None,
+            start_after: None,
+            limit: None,
+        })
+    };
+
+    let ids = |res: JobsResponse| res.jobs.into_iter().map(|job| job.id).collect::<Vec<_>>();
+
+    assert_eq!(ids(test.query(query_jobs_by_reward(None))), vec![job.id]);
+    assert_eq!(
+        ids(test.query(query_jobs_by_reward(Some(JobStatus::Pending)))),
+        vec![job.id]
+    );
+    assert_eq!(
+        ids(test.query(query_jobs_by_reward(Some(JobStatus::Paused)))),
+        vec![paused_job.id]
+    );
+}
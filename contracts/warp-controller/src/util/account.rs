@@ -0,0 +1,49 @@
+use cosmwasm_std::{
+    instantiate2_address, Addr, Binary, Deps, Env, StdError, StdResult, Storage, Uint64,
+};
+
+use controller::Config;
+
+use crate::state::ACCOUNT_INDICES;
+
+// salt of an account is the canonical owner address followed by the big endian account index
+pub fn account_salt(deps: Deps, owner: &Addr, index: Uint64) -> StdResult<Binary> {
+    let mut salt = deps.api.addr_canonicalize(owner.as_str())?.to_vec();
+    salt.extend_from_slice(&index.u64().to_be_bytes());
+
+    Ok(Binary::from(salt))
+}
+
+pub fn account_address(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    owner: &Addr,
+    index: Uint64,
+) -> StdResult<Addr> {
+    let checksum = deps
+        .querier
+        .query_wasm_code_info(config.warp_account_code_id.u64())?
+        .checksum;
+    let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let salt = account_salt(deps, owner, index)?;
+
+    let account = instantiate2_address(&checksum, &creator, &salt)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    deps.api.addr_humanize(&account)
+}
+
+pub fn next_account_index(storage: &dyn Storage, owner: &Addr) -> StdResult<Uint64> {
+    Ok(ACCOUNT_INDICES
+        .may_load(storage, owner)?
+        .unwrap_or_default())
+}
+
+// reserves the next account index of the owner
+pub fn take_account_index(storage: &mut dyn Storage, owner: &Addr) -> StdResult<Uint64> {
+    let index = next_account_index(storage, owner)?;
+    ACCOUNT_INDICES.save(storage, owner, &(index + Uint64::one()))?;
+
+    Ok(index)
+}
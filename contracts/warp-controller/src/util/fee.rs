@@ -1,4 +1,6 @@
-use cosmwasm_std::{Coin, Uint128};
+use account_tracker::FundingAccountResponse;
+use controller::{job::Job, Config};
+use cosmwasm_std::{Coin, Deps, StdResult, Uint128};
 
 pub fn deduct_from_native_funds(
     funds: Vec<Coin>,
@@ -19,3 +21,46 @@ pub fn deduct_from_native_funds(
 
     funds
 }
+
+// amount pulled from the job's top up source to cover the funding account shortfall,
+// none when not needed or when the source can't cover all of it
+pub fn compute_top_up(
+    deps: Deps,
+    config: &Config,
+    job: &Job,
+    operational_amount: Uint128,
+    reward_plus_fee: Uint128,
+) -> StdResult<Option<Uint128>> {
+    let top_up_source = match &job.top_up_source {
+        Some(top_up_source) if operational_amount < reward_plus_fee => top_up_source,
+        _ => return Ok(None),
+    };
+
+    let shortfall = reward_plus_fee - operational_amount;
+    if top_up_source.allowance < shortfall {
+        return Ok(None);
+    }
+
+    // source must still be a funding account of the job owner
+    let funding_account_resp: FundingAccountResponse = deps.querier.query_wasm_smart(
+        config.account_tracker_address.to_string(),
+        &account_tracker::QueryMsg::QueryFundingAccount(account_tracker::QueryFundingAccountMsg {
+            account_owner_addr: job.owner.to_string(),
+            account_addr: top_up_source.account.to_string(),
+        }),
+    )?;
+    if funding_account_resp.funding_account.is_none() {
+        return Ok(None);
+    }
+
+    let source_balance = deps
+        .querier
+        .query_balance(&top_up_source.account, &config.fee_denom)?
+        .amount;
+
+    Ok(if source_balance < shortfall {
+        None
+    } else {
+        Some(shortfall)
+    })
+}
@@ -1,12 +1,15 @@
-use cosmwasm_std::{to_binary, BankMsg, Coin, CosmosMsg, Uint128, Uint64, WasmMsg};
+use cosmwasm_std::{to_json_binary, BankMsg, Binary, Coin, CosmosMsg, Uint128, Uint64, WasmMsg};
 
 use account_tracker::{
-    FreeFundingAccountMsg, FreeJobAccountMsg, TakeFundingAccountMsg, TakeJobAccountMsg,
+    CloseFundingAccountMsg, FreeFundingAccountMsg, FreeJobAccountMsg, TakeFundingAccountMsg,
+    TakeJobAccountMsg,
 };
 use controller::account::{
     AssetInfo, CwFund, FundTransferMsgs, TransferFromMsg, TransferNftMsg, WarpMsg, WarpMsgs,
     WithdrawAssetsMsg,
 };
+use controller::job::Job;
+use resolver::JobEnv;
 
 #[allow(clippy::too_many_arguments)]
 pub fn build_instantiate_account_tracker_msg(
@@ -17,7 +20,7 @@ pub fn build_instantiate_account_tracker_msg(
     CosmosMsg::Wasm(WasmMsg::Instantiate {
         admin: Some(admin_addr.clone()),
         code_id,
-        msg: to_binary(&account_tracker::InstantiateMsg {
+        msg: to_json_binary(&account_tracker::InstantiateMsg {
             admin: admin_addr,
             warp_addr: controller_addr,
         })
@@ -33,14 +36,15 @@ pub fn build_instantiate_warp_account_msg(
     admin_addr: String,
     code_id: u64,
     account_owner: String,
+    salt: Binary,
     native_funds: Vec<Coin>,
     cw_funds: Option<Vec<CwFund>>,
     msgs: Option<Vec<WarpMsg>>,
 ) -> CosmosMsg {
-    CosmosMsg::Wasm(WasmMsg::Instantiate {
+    CosmosMsg::Wasm(WasmMsg::Instantiate2 {
         admin: Some(admin_addr),
         code_id,
-        msg: to_binary(&account::InstantiateMsg {
+        msg: to_json_binary(&account::InstantiateMsg {
             owner: account_owner.clone(),
             job_id,
             native_funds: native_funds.clone(),
@@ -50,6 +54,7 @@ pub fn build_instantiate_warp_account_msg(
         .unwrap(),
         funds: native_funds,
         label: format!("warp account, owner: {}", account_owner,),
+        salt,
     })
 }
 
@@ -61,7 +66,7 @@ pub fn build_free_job_account_msg(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: account_tracker_addr,
-        msg: to_binary(&account_tracker::ExecuteMsg::FreeJobAccount(
+        msg: to_json_binary(&account_tracker::ExecuteMsg::FreeJobAccount(
             FreeJobAccountMsg {
                 account_owner_addr,
                 account_addr,
@@ -81,7 +86,7 @@ pub fn build_take_job_account_msg(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: account_tracker_addr,
-        msg: to_binary(&account_tracker::ExecuteMsg::TakeJobAccount(
+        msg: to_json_binary(&account_tracker::ExecuteMsg::TakeJobAccount(
             TakeJobAccountMsg {
                 account_owner_addr,
                 account_addr,
@@ -101,7 +106,7 @@ pub fn build_free_funding_account_msg(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: account_tracker_addr,
-        msg: to_binary(&account_tracker::ExecuteMsg::FreeFundingAccount(
+        msg: to_json_binary(&account_tracker::ExecuteMsg::FreeFundingAccount(
             FreeFundingAccountMsg {
                 account_owner_addr,
                 account_addr,
@@ -121,7 +126,7 @@ pub fn build_take_funding_account_msg(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: account_tracker_addr,
-        msg: to_binary(&account_tracker::ExecuteMsg::TakeFundingAccount(
+        msg: to_json_binary(&account_tracker::ExecuteMsg::TakeFundingAccount(
             TakeFundingAccountMsg {
                 account_owner_addr,
                 account_addr,
@@ -133,6 +138,24 @@ pub fn build_take_funding_account_msg(
     })
 }
 
+pub fn build_close_funding_account_msg(
+    account_tracker_addr: String,
+    account_owner_addr: String,
+    account_addr: String,
+) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: account_tracker_addr,
+        msg: to_json_binary(&account_tracker::ExecuteMsg::CloseFundingAccount(
+            CloseFundingAccountMsg {
+                account_owner_addr,
+                account_addr,
+            },
+        ))
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
 pub fn build_transfer_cw20_msg(
     cw20_token_contract_addr: String,
     owner_addr: String,
@@ -141,7 +164,7 @@ pub fn build_transfer_cw20_msg(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: cw20_token_contract_addr,
-        msg: to_binary(&FundTransferMsgs::TransferFrom(TransferFromMsg {
+        msg: to_json_binary(&FundTransferMsgs::TransferFrom(TransferFromMsg {
             owner: owner_addr,
             recipient: recipient_addr,
             amount,
@@ -158,7 +181,7 @@ pub fn build_transfer_cw721_msg(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: cw721_token_contract_addr,
-        msg: to_binary(&FundTransferMsgs::TransferNft(TransferNftMsg {
+        msg: to_json_binary(&FundTransferMsgs::TransferNft(TransferNftMsg {
             recipient: recipient_addr,
             token_id,
         }))
@@ -196,7 +219,7 @@ pub fn build_account_execute_warp_msgs(
 ) -> CosmosMsg {
     CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: account_addr,
-        msg: to_binary(&account::ExecuteMsg::WarpMsgs(WarpMsgs {
+        msg: to_json_binary(&account::ExecuteMsg::WarpMsgs(WarpMsgs {
             msgs: warp_msgs_for_account_to_execute,
             job_id: None,
         }))
@@ -205,6 +228,21 @@ pub fn build_account_execute_warp_msgs(
     })
 }
 
+pub fn build_top_up_msg(
+    top_up_source_addr: String,
+    funding_account_addr: String,
+    amount: Uint128,
+    fee_denom: String,
+) -> CosmosMsg {
+    build_account_execute_generic_msgs(
+        top_up_source_addr,
+        vec![build_transfer_native_funds_msg(
+            funding_account_addr,
+            vec![Coin::new(amount.u128(), fee_denom)],
+        )],
+    )
+}
+
 pub fn build_account_withdraw_assets_msg(
     account_addr: String,
     assets_to_withdraw: Vec<AssetInfo>,
@@ -216,3 +254,12 @@ pub fn build_account_withdraw_assets_msg(
         })],
     )
 }
+
+pub fn build_job_env(job: &Job, execution_count: Uint64) -> JobEnv {
+    JobEnv {
+        job_id: job.id,
+        job_owner: job.owner.clone(),
+        funding_account_addr: job.funding_account.clone(),
+        execution_count,
+    }
+}
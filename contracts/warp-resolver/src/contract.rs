@@ -1,12 +1,14 @@
-use crate::util::condition::{resolve_cond, resolve_query_expr};
+use crate::util::condition::{resolve_cond, resolve_query_expr, WarpEnv};
+use crate::util::lint::lint_job;
 use crate::util::variable::{
-    apply_var_fn, has_duplicates, hydrate_msgs, hydrate_vars, msgs_valid, string_vars_in_vector,
-    vars_valid,
+    apply_var_fn, dependency_order, has_duplicates, hydrate_msgs, hydrate_vars, msgs_valid,
+    string_vars_in_vector, vars_valid, VarCache,
 };
 use crate::ContractError;
 use controller::account::{warp_msgs_to_cosmos_msgs, WarpMsg};
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult,
 };
 
 use cw_utils::nonpayable;
@@ -15,9 +17,10 @@ use resolver::variable::{QueryExpr, Variable};
 use resolver::{
     ExecuteApplyVarFnMsg, ExecuteHydrateMsgsMsg, ExecuteHydrateVarsMsg, ExecuteMsg,
     ExecuteResolveConditionMsg, ExecuteSimulateQueryMsg, ExecuteValidateJobCreationMsg,
-    InstantiateMsg, MigrateMsg, QueryApplyVarFnMsg, QueryHydrateMsgsMsg, QueryHydrateVarsMsg,
-    QueryMsg, QueryResolveConditionMsg, QueryValidateJobCreationMsg, SimulateQueryMsg,
-    SimulateResponse, WarpMsgsToCosmosMsgsMsg,
+    InstantiateMsg, LintJobResponse, MigrateMsg, QueryApplyVarFnMsg, QueryHydrateMsgsMsg,
+    QueryHydrateVarsMsg, QueryMsg, QueryResolveConditionMsg, QueryResolveExecutionMsg,
+    QueryValidateJobCreationMsg, ResolveConditionResponse, ResolveExecutionResponse,
+    SimulateQueryMsg, SimulateResponse, WarpMsgsToCosmosMsgsMsg,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -61,7 +64,7 @@ fn execute_warp_msgs_to_cosmos_msgs(
     _info: MessageInfo,
     msg: WarpMsgsToCosmosMsgsMsg,
 ) -> Result<Response, ContractError> {
-    let result = warp_msgs_to_cosmos_msgs(deps.as_ref(), env, msg.msgs, &msg.owner)?;
+    let result = warp_msgs_to_cosmos_msgs(deps.as_ref(), env, msg.msgs, &msg.owner, None)?;
 
     Ok(Response::new()
         .add_attribute("action", "warp_msgs_to_cosmos_msgs")
@@ -122,6 +125,8 @@ pub fn execute_hydrate_vars(
             vars: data.vars,
             external_inputs: data.external_inputs,
             warp_account_addr: data.warp_account_addr,
+            job_env: data.job_env,
+            skip_price_staleness: None,
         },
     )?;
 
@@ -143,6 +148,7 @@ pub fn execute_resolve_condition(
             condition: data.condition,
             vars: data.vars,
             warp_account_addr: data.warp_account_addr,
+            job_env: data.job_env,
         },
     )?;
 
@@ -164,6 +170,7 @@ pub fn execute_apply_var_fn(
             vars: data.vars,
             status: data.status,
             warp_account_addr: data.warp_account_addr,
+            job_env: data.job_env,
         },
     )?;
     Ok(Response::new()
@@ -194,16 +201,25 @@ pub fn execute_hydrate_msgs(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::SimulateQuery(data) => to_binary(&query_simulate_query(deps, env, data)?),
+        QueryMsg::SimulateQuery(data) => to_json_binary(&query_simulate_query(deps, env, data)?),
         QueryMsg::QueryValidateJobCreation(data) => {
-            to_binary(&query_validate_job_creation(deps, env, data)?)
+            to_json_binary(&query_validate_job_creation(deps, env, data)?)
         }
-        QueryMsg::QueryHydrateVars(data) => to_binary(&query_hydrate_vars(deps, env, data)?),
+        QueryMsg::QueryHydrateVars(data) => to_json_binary(&query_hydrate_vars(deps, env, data)?),
         QueryMsg::QueryResolveCondition(data) => {
-            to_binary(&query_resolve_condition(deps, env, data)?)
+            to_json_binary(&query_resolve_condition(deps, env, data)?)
         }
-        QueryMsg::QueryApplyVarFn(data) => to_binary(&query_apply_var_fn(deps, env, data)?),
-        QueryMsg::QueryHydrateMsgs(data) => to_binary(&query_hydrate_msgs(deps, env, data)?),
+        QueryMsg::QueryResolveConditionV2(data) => {
+            to_json_binary(&query_resolve_condition_v2(deps, env, data))
+        }
+        QueryMsg::QueryApplyVarFn(data) => to_json_binary(&query_apply_var_fn(deps, env, data)?),
+        QueryMsg::QueryHydrateMsgs(data) => to_json_binary(&query_hydrate_msgs(deps, env, data)?),
+        QueryMsg::QueryResolveExecution(data) => {
+            to_json_binary(&query_resolve_execution(deps, env, data)?)
+        }
+        QueryMsg::QueryLintJob(data) => to_json_binary(&LintJobResponse {
+            issues: lint_job(deps, env, data),
+        }),
     }
 }
 
@@ -237,6 +253,8 @@ fn query_validate_job_creation(
             ));
         }
 
+        dependency_order(&vars).map_err(|e| StdError::generic_err(e.to_string()))?;
+
         if !(string_vars_in_vector(&vars, &execution.condition)
             && string_vars_in_vector(&vars, &terminate_condition_str)
             && string_vars_in_vector(&vars, &execution.msgs))
@@ -269,7 +287,11 @@ fn query_hydrate_vars(deps: Deps, env: Env, data: QueryHydrateVarsMsg) -> StdRes
             env,
             vars,
             data.external_inputs,
-            data.warp_account_addr,
+            &WarpEnv {
+                warp_account_addr: data.warp_account_addr,
+                job: data.job_env,
+                skip_price_staleness: data.skip_price_staleness.unwrap_or(false),
+            },
         )
         .map_err(|e| StdError::generic_err(e.to_string()))?,
     )
@@ -286,15 +308,54 @@ fn query_resolve_condition(
     let vars: Vec<Variable> =
         serde_json_wasm::from_str(&data.vars).map_err(|e| StdError::generic_err(e.to_string()))?;
 
-    resolve_cond(deps, env, condition, &vars, data.warp_account_addr)
+    let warp_env = WarpEnv {
+        warp_account_addr: data.warp_account_addr,
+        job: data.job_env,
+        skip_price_staleness: false,
+    };
+
+    resolve_cond(deps, env, condition, &vars, &warp_env)
         .map_err(|e| StdError::generic_err(e.to_string()))
 }
 
+fn query_resolve_condition_v2(
+    deps: Deps,
+    env: Env,
+    data: QueryResolveConditionMsg,
+) -> ResolveConditionResponse {
+    let warp_env = WarpEnv {
+        warp_account_addr: data.warp_account_addr,
+        job: data.job_env,
+        skip_price_staleness: false,
+    };
+
+    let res = serde_json_wasm::from_str::<Condition>(&data.condition)
+        .map_err(|e| (ContractError::from(e), "condition"))
+        .and_then(|condition| {
+            let vars: Vec<Variable> = serde_json_wasm::from_str(&data.vars)
+                .map_err(|e| (ContractError::from(e), "vars"))?;
+            resolve_cond(deps, env, condition, &vars, &warp_env).map_err(|e| (e, "condition"))
+        });
+
+    match res {
+        Ok(resolution) => ResolveConditionResponse::Ok(resolution),
+        Err((e, path)) => {
+            ResolveConditionResponse::Err(e.to_resolver_error(Some(path.to_string())))
+        }
+    }
+}
+
 fn query_apply_var_fn(deps: Deps, env: Env, data: QueryApplyVarFnMsg) -> StdResult<String> {
     let vars: Vec<Variable> =
         serde_json_wasm::from_str(&data.vars).map_err(|e| StdError::generic_err(e.to_string()))?;
 
-    apply_var_fn(deps, env, vars, data.status, data.warp_account_addr)
+    let warp_env = WarpEnv {
+        warp_account_addr: data.warp_account_addr,
+        job: data.job_env,
+        skip_price_staleness: false,
+    };
+
+    apply_var_fn(deps, env, vars, data.status, &warp_env)
         .map_err(|e| StdError::generic_err(e.to_string()))
 }
 
@@ -309,6 +370,77 @@ fn query_hydrate_msgs(
     hydrate_msgs(data.msgs, vars).map_err(|e| StdError::generic_err(e.to_string()))
 }
 
+fn query_resolve_execution(
+    deps: Deps,
+    env: Env,
+    data: QueryResolveExecutionMsg,
+) -> StdResult<ResolveExecutionResponse> {
+    let vars: Vec<Variable> =
+        serde_json_wasm::from_str(&data.vars).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let warp_env = WarpEnv {
+        warp_account_addr: data.warp_account_addr,
+        job: data.job_env,
+        skip_price_staleness: false,
+    };
+
+    let mut cache = match VarCache::new(vars, data.external_inputs, &warp_env) {
+        Ok(cache) => cache,
+        Err(e) => {
+            return Ok(ResolveExecutionResponse {
+                index: None,
+                msgs: None,
+                error: Some(e.to_resolver_error(Some("vars".to_string()))),
+            })
+        }
+    };
+
+    for (idx, execution) in data.executions.into_iter().enumerate() {
+        let error_response = |e: ContractError, path: &str| ResolveExecutionResponse {
+            index: Some(idx as u64),
+            msgs: None,
+            error: Some(e.to_resolver_error(Some(format!("executions[{}].{}", idx, path)))),
+        };
+
+        // hydration errors (failing queries, stale prices) fail the query rather than the job,
+        // they may be temporary
+        let hydrated_vars = cache
+            .hydrate_referenced(deps, env.clone(), &execution.condition)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        let resolution = serde_json_wasm::from_str::<Condition>(&execution.condition)
+            .map_err(ContractError::from)
+            .and_then(|condition| {
+                resolve_cond(deps, env.clone(), condition, &hydrated_vars, &warp_env)
+            });
+
+        match resolution {
+            Ok(true) => {
+                let hydrated_vars = cache
+                    .hydrate_referenced(deps, env.clone(), &execution.msgs)
+                    .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+                return Ok(match hydrate_msgs(execution.msgs, hydrated_vars) {
+                    Ok(msgs) => ResolveExecutionResponse {
+                        index: Some(idx as u64),
+                        msgs: Some(msgs),
+                        error: None,
+                    },
+                    Err(e) => error_response(e, "msgs"),
+                });
+            }
+            Ok(false) => continue,
+            Err(e) => return Ok(error_response(e, "condition")),
+        }
+    }
+
+    Ok(ResolveExecutionResponse {
+        index: None,
+        msgs: None,
+        error: None,
+    })
+}
+
 pub fn query_simulate_query(
     deps: Deps,
     env: Env,
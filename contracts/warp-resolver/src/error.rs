@@ -1,5 +1,6 @@
 use crate::ContractError::{CustomError, DecodeError, DeserializationError, SerializationError};
 use cosmwasm_std::{OverflowError, StdError};
+use resolver::{ResolverError, ResolverErrorKind};
 use std::num::ParseIntError;
 use std::str::ParseBoolError;
 use thiserror::Error;
@@ -73,11 +74,81 @@ pub enum ContractError {
     #[error("Variables in condition or msgs missing from variables vector.")]
     VariablesMissingFromVector {},
 
+    #[error("Variables contain a dependency cycle: {names:?}.")]
+    VariableDependencyCycle { names: Vec<String> },
+
     #[error("Variable vector contains unused variables.")]
     ExcessVariablesInVector {},
 
     #[error("Msg error: {msg:?}")]
     MsgError { msg: String },
+
+    #[error("Variable {name:?}: {error}")]
+    VariableError {
+        name: String,
+        error: Box<ContractError>,
+    },
+}
+
+impl ContractError {
+    // attributes the error to a variable, the innermost variable is kept
+    pub fn for_variable(self, name: &str) -> Self {
+        match self {
+            ContractError::VariableError { .. } | ContractError::VariableNotFound { .. } => self,
+            error => ContractError::VariableError {
+                name: name.trim_start_matches("$warp.variable.").to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    pub fn to_resolver_error(&self, path: Option<String>) -> ResolverError {
+        let (kind, variable, message) = match self {
+            ContractError::VariableError { name, error } => {
+                let inner = error.to_resolver_error(None);
+                (inner.kind, Some(name.clone()), inner.message)
+            }
+            ContractError::VariableNotFound { name } => (
+                ResolverErrorKind::VariableNotFound,
+                Some(name.trim_start_matches("$warp.variable.").to_string()),
+                self.to_string(),
+            ),
+            ContractError::ConditionError { msg } => {
+                (ResolverErrorKind::Condition, None, msg.clone())
+            }
+            ContractError::HydrationError { msg } => {
+                (ResolverErrorKind::Hydration, None, msg.clone())
+            }
+            ContractError::FunctionError { msg } => {
+                (ResolverErrorKind::Function, None, msg.clone())
+            }
+            ContractError::MsgError { msg } => (ResolverErrorKind::Msg, None, msg.clone()),
+            ContractError::InvalidVariables {}
+            | ContractError::VariablesContainDuplicates {}
+            | ContractError::VariablesMissingFromVector {}
+            | ContractError::ExcessVariablesInVector {} => {
+                (ResolverErrorKind::InvalidVariables, None, self.to_string())
+            }
+            ContractError::VariableDependencyCycle { names } => (
+                ResolverErrorKind::InvalidVariables,
+                names.first().cloned(),
+                self.to_string(),
+            ),
+            ContractError::DeserializationError {}
+            | ContractError::SerializationError {}
+            | ContractError::DecodeError {}
+            | ContractError::ResolveError {} => (ResolverErrorKind::Parse, None, self.to_string()),
+            ContractError::Std(e) => (ResolverErrorKind::Std, None, e.to_string()),
+            _ => (ResolverErrorKind::Other, None, self.to_string()),
+        };
+
+        ResolverError {
+            kind,
+            variable,
+            path,
+            message,
+        }
+    }
 }
 
 impl From<ParseIntError> for ContractError {
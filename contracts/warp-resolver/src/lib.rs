@@ -2,6 +2,8 @@ pub mod contract;
 mod error;
 pub mod util;
 
+#[cfg(test)]
+mod mock_oracle;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,146 @@
+// Oracle contract for tests, answering the query interface of every PriceSource
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Decimal256, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Item;
+use resolver::variable::{CumulativePricesResponse, PriceResponse};
+use schemars::_serde_json::json;
+
+#[cw_serde]
+pub struct PricePoint {
+    pub price: Decimal256,
+    pub confidence: Option<Decimal256>,
+    pub publish_time: u64,
+}
+
+// price history, ordered by publish_time
+const PRICES: Item<Vec<PricePoint>> = Item::new("prices");
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    SetPrice {
+        price: Decimal256,
+        confidence: Option<Decimal256>,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    // Custom
+    Price {
+        base: String,
+        quote: String,
+    },
+    // Pyth
+    PriceFeed {
+        id: String,
+    },
+    // Band
+    GetReferenceData {
+        base_symbol: String,
+        quote_symbol: String,
+    },
+    // PoolTwap
+    CumulativePrices {
+        base: String,
+        quote: String,
+        seconds_ago: Vec<u64>,
+    },
+}
+
+pub fn mock_oracle_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    PRICES.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SetPrice { price, confidence } => {
+            PRICES.update(deps.storage, |mut prices| -> StdResult<_> {
+                prices.push(PricePoint {
+                    price,
+                    confidence,
+                    publish_time: env.block.time.seconds(),
+                });
+                Ok(prices)
+            })?;
+            Ok(Response::new())
+        }
+    }
+}
+
+fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let prices = PRICES.load(deps.storage)?;
+    let last = prices
+        .last()
+        .cloned()
+        .ok_or_else(|| StdError::generic_err("no price"))?;
+
+    match msg {
+        QueryMsg::Price { .. } => to_json_binary(&PriceResponse {
+            price: last.price,
+            confidence: last.confidence,
+            publish_time: last.publish_time,
+        }),
+        // price and conf scaled by 10^8
+        QueryMsg::PriceFeed { id } => {
+            let scale = |value: Decimal256| {
+                (value * Decimal256::from_ratio(100_000_000u64, 1u64)).to_uint_floor()
+            };
+            let price = json!({
+                "price": scale(last.price).to_string(),
+                "conf": scale(last.confidence.unwrap_or_default()).to_string(),
+                "expo": -8,
+                "publish_time": last.publish_time,
+            });
+            to_json_binary(&json!({
+                "price_feed": { "id": id, "price": price, "ema_price": price }
+            }))
+        }
+        // rate scaled by 10^18
+        QueryMsg::GetReferenceData { .. } => to_json_binary(&json!({
+            "rate": last.price.atomics().to_string(),
+            "last_updated_base": last.publish_time,
+            "last_updated_quote": last.publish_time,
+        })),
+        QueryMsg::CumulativePrices { seconds_ago, .. } => {
+            let now = env.block.time.seconds();
+            let cumulative_prices = seconds_ago
+                .into_iter()
+                .map(|ago| cumulative_price(&prices, now.saturating_sub(ago)))
+                .collect::<StdResult<Vec<Decimal256>>>()?;
+            to_json_binary(&CumulativePricesResponse {
+                cumulative_prices,
+                // cumulative prices accrue up to the current block
+                observed_at: now,
+            })
+        }
+    }
+}
+
+// sum of price * seconds it held up to time
+fn cumulative_price(prices: &[PricePoint], time: u64) -> StdResult<Decimal256> {
+    if !matches!(prices.first(), Some(p) if p.publish_time <= time) {
+        return Err(StdError::generic_err("no price observed at time"));
+    }
+
+    let mut cumulative = Decimal256::zero();
+    for (i, point) in prices.iter().enumerate() {
+        if point.publish_time >= time {
+            break;
+        }
+        let end = prices
+            .get(i + 1)
+            .map_or(time, |next| next.publish_time.min(time));
+        cumulative += point.price * Decimal256::from_ratio(end - point.publish_time, 1u64);
+    }
+
+    Ok(cumulative)
+}
@@ -1,24 +1,45 @@
-use controller::account::WarpMsg;
-use controller::job::Execution;
-use resolver::condition::{NumValue, StringEnvValue, StringValue};
+use controller::account::{AssetInfo, WarpMsg};
+use controller::job::{Execution, JobStatus};
+use resolver::condition::{
+    Condition, DecimalFnOp, Duration, Expr, GenExpr, IntFnOp, NumEnvValue, NumExprOp, NumExprValue,
+    NumFnValue, NumOp, NumValue, SeriesFnOp, SeriesValue, StringEnvValue, StringFnOp,
+    StringFnValue, StringFormatValue, StringOp, StringReplaceValue, StringSubstringValue,
+    StringValue, TimeExpr,
+};
 use schemars::_serde_json::json;
+use std::str::FromStr;
 
-use crate::util::variable::{hydrate_msgs, hydrate_vars};
+use crate::util::condition::{resolve_cond, WarpEnv};
+use crate::util::variable::{
+    apply_var_fn, dependency_order, hydrate_msgs, hydrate_vars, msgs_valid, update_fn_kind_valid,
+    vars_valid,
+};
+use crate::ContractError;
 
-use cosmwasm_std::{testing::mock_env, WasmQuery};
+use cosmwasm_std::{testing::mock_env, Addr, Uint128, Uint64, WasmQuery};
 use cosmwasm_std::{
-    to_binary, BankQuery, Binary, ContractResult, CosmosMsg, OwnedDeps, Uint256, WasmMsg,
+    to_json_binary, BalanceResponse, BankMsg, BankQuery, Binary, Coin, ContractResult, CosmosMsg,
+    Decimal256, Int256, OwnedDeps, SignedDecimal256, Uint256, WasmMsg,
 };
 
 use crate::contract::query;
+use crate::mock_oracle::{mock_oracle_contract, ExecuteMsg as MockOracleExecuteMsg};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::{mock_info, MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{from_slice, Empty, Querier, QueryRequest, SystemError, SystemResult};
+use cosmwasm_std::{from_json, Empty, Querier, QueryRequest, StdResult, SystemError, SystemResult};
+use cw_multi_test::{App, ContractWrapper, Executor};
 
 use resolver::variable::{
-    FnValue, QueryExpr, QueryVariable, StaticVariable, Variable, VariableKind,
+    BalanceExpr, BalanceVariable, Encoding, FnValue, JsonValue, PriceExpr, PriceSource,
+    PriceVariable, QueryExpr, QueryVariable, StaticVariable, UpdateFn, Variable, VariableKind,
+    SERIES_MAX_CAPACITY,
+};
+use resolver::JobEnv;
+use resolver::{
+    LintJobResponse, LintKind, LintSeverity, QueryHydrateVarsMsg, QueryLintJobMsg, QueryMsg,
+    QueryResolveConditionMsg, QueryResolveExecutionMsg, QueryValidateJobCreationMsg,
+    ResolveConditionResponse, ResolveExecutionResponse, ResolverError, ResolverErrorKind,
 };
-use resolver::{QueryMsg, QueryValidateJobCreationMsg};
 use std::marker::PhantomData;
 
 #[cw_serde]
@@ -82,7 +103,7 @@ pub struct WasmMockQuerier {
 
 impl Querier for WasmMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> SystemResult<ContractResult<Binary>> {
-        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+        let request: QueryRequest<Empty> = match from_json(bin_request) {
             Ok(v) => v,
             Err(e) => {
                 return SystemResult::Err(SystemError::InvalidRequest {
@@ -101,9 +122,14 @@ impl WasmMockQuerier {
         request: &QueryRequest<Empty>,
     ) -> SystemResult<ContractResult<Binary>> {
         match &request {
-            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. }) => {
                 // Check if the query is for the vault contract address to get subaccount_id
-                if contract_addr == "mock_vault_contract_addr" {
+                if contract_addr == "mock_cw20_contract_addr" {
+                    let response = cw20::BalanceResponse {
+                        balance: Uint128::new(500),
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
+                } else if contract_addr == "mock_vault_contract_addr" {
                     // Simulate response with subaccount_id
                     let response = json!({
                         "config": {
@@ -112,7 +138,7 @@ impl WasmMockQuerier {
                             }
                         }
                     });
-                    SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
                 } else {
                     // Default mock response for other smart contract queries
                     let response = json!({
@@ -120,14 +146,22 @@ impl WasmMockQuerier {
                         "msg": "Mock message"
                     })
                     .to_string();
-                    SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
                 }
             }
+            QueryRequest::Bank(BankQuery::Balance { denom, .. }) if denom == "uluna" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&BalanceResponse {
+                        amount: Coin::new(1000, "uluna"),
+                    })
+                    .unwrap(),
+                ))
+            }
             QueryRequest::Bank(BankQuery::Balance {
                 address: contract_addr,
                 denom: _,
             }) => SystemResult::Ok(ContractResult::Ok(
-                to_binary(&contract_addr.to_string()).unwrap(),
+                to_json_binary(&contract_addr.to_string()).unwrap(),
             )),
             _ => self.base.handle_query(request),
         }
@@ -148,7 +182,7 @@ fn test_hydrate_vars_nested_variables_binary_json() {
     let var5 = Variable::Static(StaticVariable {
         kind: VariableKind::String,
         name: "var5".to_string(),
-        encode: false,
+        encode: Encoding::None,
         value: None,
         init_fn: FnValue::String(StringValue::Simple("contract_addr".to_string())),
         reinitialize: false,
@@ -158,7 +192,7 @@ fn test_hydrate_vars_nested_variables_binary_json() {
     let var4 = Variable::Static(StaticVariable {
         kind: VariableKind::String,
         name: "var4".to_string(),
-        encode: false,
+        encode: Encoding::None,
         value: None,
         init_fn: FnValue::String(StringValue::Ref("$warp.variable.var5".to_string())),
         reinitialize: false,
@@ -178,7 +212,7 @@ fn test_hydrate_vars_nested_variables_binary_json() {
         value: None,
         reinitialize: false,
         update_fn: None,
-        encode: true,
+        encode: Encoding::Base64,
     });
 
     let var1 = Variable::Query(QueryVariable {
@@ -194,7 +228,7 @@ fn test_hydrate_vars_nested_variables_binary_json() {
         value: None,
         reinitialize: false,
         update_fn: None,
-        encode: true,
+        encode: Encoding::Base64,
     });
 
     let var2 = Variable::Query(QueryVariable {
@@ -210,11 +244,11 @@ fn test_hydrate_vars_nested_variables_binary_json() {
         value: None,
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let vars = vec![var5, var4, var3, var1, var2];
-    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, None).unwrap();
+    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, &WarpEnv::default()).unwrap();
 
     assert_eq!(
         hydrated_vars[4],
@@ -231,7 +265,7 @@ fn test_hydrate_vars_nested_variables_binary_json() {
             value: Some(r#"{"address":"contract_addr","msg":"Mock message"}"#.to_string()),
             reinitialize: false,
             update_fn: None,
-            encode: false,
+            encode: Encoding::None,
         })
     );
 }
@@ -248,7 +282,7 @@ fn test_hydrate_vars_nested_variables_binary() {
         init_fn: FnValue::String(StringValue::Simple("static_value".to_string())),
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let init_fn = QueryExpr {
@@ -266,11 +300,11 @@ fn test_hydrate_vars_nested_variables_binary() {
         value: None,
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let vars = vec![var1, var2];
-    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, None).unwrap();
+    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, &WarpEnv::default()).unwrap();
 
     assert_eq!(
         hydrated_vars[1],
@@ -287,7 +321,7 @@ fn test_hydrate_vars_nested_variables_binary() {
             value: Some(r#"{"address":"static_value","msg":"Mock message"}"#.to_string()),
             reinitialize: false,
             update_fn: None,
-            encode: false,
+            encode: Encoding::None,
         })
     );
 }
@@ -303,7 +337,7 @@ fn test_hydrate_vars_nested_variables_non_binary() {
         init_fn: FnValue::String(StringValue::Simple("static_value".to_string())),
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let init_fn = QueryExpr {
@@ -321,11 +355,11 @@ fn test_hydrate_vars_nested_variables_non_binary() {
         value: None,
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let vars = vec![var1, var2];
-    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, None).unwrap();
+    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, &WarpEnv::default()).unwrap();
 
     assert_eq!(
         hydrated_vars[1],
@@ -342,7 +376,7 @@ fn test_hydrate_vars_nested_variables_non_binary() {
             value: Some("static_value".to_string()),
             reinitialize: false,
             update_fn: None,
-            encode: false,
+            encode: Encoding::None,
         })
     );
 }
@@ -359,7 +393,7 @@ fn test_hydrate_static_nested_vars_and_hydrate_msgs() {
         init_fn: FnValue::String(StringValue::Simple("static_value_1".to_string())),
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     // ============ TEST HYDRATED VALUE  ============
@@ -380,11 +414,12 @@ fn test_hydrate_static_nested_vars_and_hydrate_msgs() {
         reinitialize: false,
         update_fn: None,
         // when encode is false, value will not be base64 encoded after msgs hydration
-        encode: false,
+        encode: Encoding::None,
     });
 
     let vars = vec![var1.clone(), var2];
-    let hydrated_vars = hydrate_vars(deps.as_ref(), env.clone(), vars, None, None).unwrap();
+    let hydrated_vars =
+        hydrate_vars(deps.as_ref(), env.clone(), vars, None, &WarpEnv::default()).unwrap();
     let hydrated_var1 = hydrated_vars[0].clone();
     let hydrated_var2 = hydrated_vars[1].clone();
     match hydrated_var2.clone() {
@@ -406,11 +441,11 @@ fn test_hydrate_static_nested_vars_and_hydrate_msgs() {
         reinitialize: false,
         update_fn: None,
         // when encode is true, value will be base64 encoded after msgs hydration
-        encode: true,
+        encode: Encoding::Base64,
     });
 
     let vars = vec![var1, var3];
-    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, None).unwrap();
+    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, &WarpEnv::default()).unwrap();
     let hydrated_var3 = hydrated_vars[1].clone();
     match hydrated_var3.clone() {
         Variable::Static(static_var) => {
@@ -483,7 +518,7 @@ fn test_hydrate_static_env_vars_and_hydrate_msgs() {
         init_fn: FnValue::String(StringValue::Simple("static_value_1".to_string())),
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let var2 = Variable::Static(StaticVariable {
@@ -493,7 +528,7 @@ fn test_hydrate_static_env_vars_and_hydrate_msgs() {
         init_fn: FnValue::Uint(NumValue::Simple(Uint256::from(100_u64))),
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let var3 = Variable::Static(StaticVariable {
@@ -503,7 +538,7 @@ fn test_hydrate_static_env_vars_and_hydrate_msgs() {
         init_fn: FnValue::String(StringValue::Simple(json_str)),
         reinitialize: false,
         update_fn: None,
-        encode: true,
+        encode: Encoding::Base64,
     });
 
     let var4 = Variable::Static(StaticVariable {
@@ -513,7 +548,7 @@ fn test_hydrate_static_env_vars_and_hydrate_msgs() {
         init_fn: FnValue::String(StringValue::Env(StringEnvValue::WarpAccountAddr)),
         reinitialize: false,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     let vars = vec![var1, var2, var3, var4];
@@ -522,7 +557,11 @@ fn test_hydrate_static_env_vars_and_hydrate_msgs() {
         env,
         vars,
         None,
-        Some(dummy_warp_account_addr.clone()),
+        &WarpEnv {
+            warp_account_addr: Some(dummy_warp_account_addr.clone()),
+            job: None,
+            skip_price_staleness: false,
+        },
     )
     .unwrap();
 
@@ -604,13 +643,13 @@ fn test_hydrate_static_and_custom_query_vars() {
     let subaccount_id = Variable::Query(QueryVariable {
         kind: VariableKind::String,
         name: "subaccount_id".to_string(),
-        encode: false,
+        encode: Encoding::None,
         value: None,
         init_fn: QueryExpr {
             selector: "$.config.base.subaccount_id".to_string(),
             query: QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: vault_contract_addr.clone(),
-                msg: to_binary(&json!({"base": {"config": {}}})).unwrap(),
+                msg: to_json_binary(&json!({"base": {"config": {}}})).unwrap(),
             }),
         },
         reinitialize: true,
@@ -624,19 +663,1904 @@ fn test_hydrate_static_and_custom_query_vars() {
             selector: "$.config".to_string(),
             query: QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: vault_contract_addr.clone(),
-                msg: to_binary(&json!({ "base": { "config": {} } })).unwrap(),
+                msg: to_json_binary(&json!({ "base": { "config": {} } })).unwrap(),
             }),
         },
         value: None,
         reinitialize: true,
         update_fn: None,
-        encode: false,
+        encode: Encoding::None,
     });
 
     // Hydrate variables
     let vars = vec![subaccount_id, next_config];
     // let vars = vec![next_config];
-    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, None).unwrap();
+    let hydrated_vars = hydrate_vars(deps.as_ref(), env, vars, None, &WarpEnv::default()).unwrap();
 
     println!("{:?}", hydrated_vars);
 }
+
+#[test]
+fn test_hydrate_static_json_vars_and_hydrate_msgs() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let memo = Variable::Static(StaticVariable {
+        name: "memo".to_string(),
+        kind: VariableKind::String,
+        value: None,
+        init_fn: FnValue::String(StringValue::Simple("say \"hi\"\n".to_string())),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let amount = Variable::Static(StaticVariable {
+        name: "amount".to_string(),
+        kind: VariableKind::Uint,
+        value: None,
+        init_fn: FnValue::Uint(NumValue::Simple(Uint256::from(100_u64))),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let swap_msg = Variable::Static(StaticVariable {
+        name: "swap_msg".to_string(),
+        kind: VariableKind::Json,
+        value: None,
+        init_fn: FnValue::Json(JsonValue::Object(vec![(
+            "swap".to_string(),
+            FnValue::Json(JsonValue::Object(vec![
+                (
+                    "memo".to_string(),
+                    FnValue::String(StringValue::Ref("$warp.variable.memo".to_string())),
+                ),
+                (
+                    "amount".to_string(),
+                    FnValue::Uint(NumValue::Ref("$warp.variable.amount".to_string())),
+                ),
+                (
+                    "route".to_string(),
+                    FnValue::Json(JsonValue::Array(vec![
+                        FnValue::Json(JsonValue::Simple(r#"{"pool":"pool_1"}"#.to_string())),
+                        FnValue::Json(JsonValue::Simple(r#"{"pool":"pool_2"}"#.to_string())),
+                    ])),
+                ),
+            ])),
+        )])),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::Base64,
+    });
+
+    let hydrated_vars = hydrate_vars(
+        deps.as_ref(),
+        env,
+        vec![memo, amount, swap_msg],
+        None,
+        &WarpEnv::default(),
+    )
+    .unwrap();
+
+    // object keys are written sorted, not in declaration order
+    let raw_str = r#"{"swap":{"amount":"100","memo":"say \"hi\"\n","route":[{"pool":"pool_1"},{"pool":"pool_2"}]}}"#;
+
+    match hydrated_vars[2].clone() {
+        Variable::Static(static_var) => assert_eq!(static_var.value.unwrap(), raw_str),
+        _ => panic!("Expected static variable"),
+    };
+
+    let msgs = r#"[{"wasm":{"execute":{"contract_addr":"contract","msg":"$warp.variable.swap_msg","funds":[]}}}]"#
+        .to_string();
+
+    let hydrated_msgs = hydrate_msgs(msgs, hydrated_vars).unwrap();
+
+    assert_eq!(
+        hydrated_msgs[0],
+        WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "contract".to_string(),
+            msg: Binary::from(raw_str.as_bytes()),
+            funds: vec![]
+        }))
+    );
+}
+
+#[test]
+fn test_hydrate_static_json_var_merge() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let config = Variable::Static(StaticVariable {
+        name: "config".to_string(),
+        kind: VariableKind::Json,
+        value: None,
+        init_fn: FnValue::Json(JsonValue::Merge(vec![
+            JsonValue::Simple(r#"{"a":1,"b":{"c":2,"d":3}}"#.to_string()),
+            JsonValue::Simple(r#"{"b":{"c":null,"e":[true]},"f":"x"}"#.to_string()),
+        ])),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let patched = Variable::Static(StaticVariable {
+        name: "patched".to_string(),
+        kind: VariableKind::Json,
+        value: None,
+        init_fn: FnValue::Json(JsonValue::Merge(vec![
+            JsonValue::Ref("$warp.variable.config".to_string()),
+            JsonValue::Object(vec![(
+                "a".to_string(),
+                FnValue::Json(JsonValue::Simple("null".to_string())),
+            )]),
+        ])),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let hydrated_vars = hydrate_vars(
+        deps.as_ref(),
+        env,
+        vec![config, patched],
+        None,
+        &WarpEnv::default(),
+    )
+    .unwrap();
+
+    match hydrated_vars[0].clone() {
+        Variable::Static(static_var) => assert_eq!(
+            static_var.value.unwrap(),
+            r#"{"a":1,"b":{"d":3,"e":[true]},"f":"x"}"#
+        ),
+        _ => panic!("Expected static variable"),
+    };
+
+    match hydrated_vars[1].clone() {
+        Variable::Static(static_var) => assert_eq!(
+            static_var.value.unwrap(),
+            r#"{"b":{"d":3,"e":[true]},"f":"x"}"#
+        ),
+        _ => panic!("Expected static variable"),
+    };
+}
+
+#[test]
+fn test_hydrate_static_string_fn_vars() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let addr = Variable::Static(StaticVariable {
+        name: "addr".to_string(),
+        kind: VariableKind::String,
+        value: None,
+        init_fn: FnValue::String(StringValue::Simple("TERRA1Creator".to_string())),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let denom = Variable::Static(StaticVariable {
+        name: "denom".to_string(),
+        kind: VariableKind::String,
+        value: None,
+        init_fn: FnValue::String(StringValue::Concat(vec![
+            StringValue::Simple("factory/".to_string()),
+            StringValue::Fn(StringFnValue {
+                op: StringFnOp::ToLower,
+                right: Box::new(StringValue::Ref("$warp.variable.addr".to_string())),
+            }),
+            StringValue::Simple("/token".to_string()),
+        ])),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let memo = Variable::Static(StaticVariable {
+        name: "memo".to_string(),
+        kind: VariableKind::String,
+        value: None,
+        init_fn: FnValue::String(StringValue::Concat(vec![
+            StringValue::Replace(StringReplaceValue {
+                value: Box::new(StringValue::Substring(StringSubstringValue {
+                    value: Box::new(StringValue::Ref("$warp.variable.denom".to_string())),
+                    start: 8,
+                    end: Some(21),
+                })),
+                from: Box::new(StringValue::Simple("creator".to_string())),
+                to: Box::new(StringValue::Simple("owner".to_string())),
+            }),
+            StringValue::Simple(":".to_string()),
+            StringValue::Fn(StringFnValue {
+                op: StringFnOp::Len,
+                right: Box::new(StringValue::Ref("$warp.variable.denom".to_string())),
+            }),
+            StringValue::Simple(":".to_string()),
+            StringValue::Format(StringFormatValue::Uint(NumValue::Simple(Uint256::from(
+                100_u64,
+            )))),
+            StringValue::Simple(":".to_string()),
+            StringValue::Format(StringFormatValue::Decimal {
+                value: NumValue::Simple(SignedDecimal256::from_str("1.23456").unwrap()),
+                precision: Some(2),
+            }),
+            StringValue::Simple(":".to_string()),
+            StringValue::Format(StringFormatValue::Decimal {
+                value: NumValue::Simple(SignedDecimal256::from_str("7").unwrap()),
+                precision: Some(3),
+            }),
+        ])),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let hydrated_vars = hydrate_vars(
+        deps.as_ref(),
+        env.clone(),
+        vec![addr, denom, memo],
+        None,
+        &WarpEnv::default(),
+    )
+    .unwrap();
+
+    match hydrated_vars[1].clone() {
+        Variable::Static(static_var) => {
+            assert_eq!(static_var.value.unwrap(), "factory/terra1creator/token")
+        }
+        _ => panic!("Expected static variable"),
+    };
+
+    match hydrated_vars[2].clone() {
+        Variable::Static(static_var) => {
+            assert_eq!(static_var.value.unwrap(), "terra1owner:27:100:1.23:7.000")
+        }
+        _ => panic!("Expected static variable"),
+    };
+
+    let out_of_bounds = Variable::Static(StaticVariable {
+        name: "out_of_bounds".to_string(),
+        kind: VariableKind::String,
+        value: None,
+        init_fn: FnValue::String(StringValue::Substring(StringSubstringValue {
+            value: Box::new(StringValue::Simple("short".to_string())),
+            start: 2,
+            end: Some(10),
+        })),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    assert!(hydrate_vars(
+        deps.as_ref(),
+        env.clone(),
+        vec![out_of_bounds],
+        None,
+        &WarpEnv::default()
+    )
+    .is_err());
+
+    let huge_precision = Variable::Static(StaticVariable {
+        name: "huge_precision".to_string(),
+        kind: VariableKind::String,
+        value: None,
+        init_fn: FnValue::String(StringValue::Format(StringFormatValue::Decimal {
+            value: NumValue::Simple(SignedDecimal256::from_str("1.5").unwrap()),
+            precision: Some(4_000_000_000),
+        })),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let err = hydrate_vars(
+        deps.as_ref(),
+        env,
+        vec![huge_precision],
+        None,
+        &WarpEnv::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("exceeds max of 18"));
+}
+
+#[test]
+fn test_resolve_string_regex_and_addr_ops() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let recipient = Variable::Static(StaticVariable {
+        name: "recipient".to_string(),
+        kind: VariableKind::String,
+        value: Some("terra1recipient".to_string()),
+        init_fn: FnValue::String(StringValue::Simple("terra1recipient".to_string())),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+    let vars = vec![recipient];
+
+    let cond = |left: StringValue<String>, op: StringOp, right: &str| {
+        Condition::Expr(Box::new(Expr::String(GenExpr {
+            left,
+            op,
+            right: StringValue::Simple(right.to_string()),
+        })))
+    };
+    let recipient_ref = || StringValue::Ref("$warp.variable.recipient".to_string());
+
+    let resolve = |cond: Condition| {
+        resolve_cond(deps.as_ref(), env.clone(), cond, &vars, &WarpEnv::default())
+    };
+
+    assert!(resolve(cond(
+        recipient_ref(),
+        StringOp::Matches,
+        "^terra1[a-z0-9]+$"
+    ))
+    .unwrap());
+    assert!(!resolve(cond(recipient_ref(), StringOp::Matches, "^osmo1")).unwrap());
+    assert!(resolve(cond(recipient_ref(), StringOp::Matches, "(unclosed")).is_err());
+
+    assert!(resolve(cond(recipient_ref(), StringOp::IsValidAddr, "terra")).unwrap());
+    assert!(resolve(cond(recipient_ref(), StringOp::IsValidAddr, "")).unwrap());
+    assert!(!resolve(cond(recipient_ref(), StringOp::IsValidAddr, "osmo")).unwrap());
+    assert!(!resolve(cond(
+        StringValue::Simple("Terra1Invalid".to_string()),
+        StringOp::IsValidAddr,
+        ""
+    ))
+    .unwrap());
+
+    assert!(resolve(cond(
+        StringValue::Fn(StringFnValue {
+            op: StringFnOp::Bech32Prefix,
+            right: Box::new(recipient_ref()),
+        }),
+        StringOp::Eq,
+        "terra"
+    ))
+    .unwrap());
+}
+
+#[test]
+fn test_resolve_env_values() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let warp_env = WarpEnv {
+        warp_account_addr: Some("terra1account".to_string()),
+        job: Some(JobEnv {
+            job_id: Uint64::new(7),
+            job_owner: Addr::unchecked("terra1owner"),
+            funding_account_addr: Some(Addr::unchecked("terra1funding")),
+            execution_count: Uint64::new(3),
+        }),
+        skip_price_staleness: false,
+    };
+
+    let resolve = |cond: Condition, warp_env: &WarpEnv| {
+        resolve_cond(deps.as_ref(), env.clone(), cond, &vec![], warp_env)
+    };
+
+    let uint_cond = |left: NumEnvValue, right: u64| {
+        Condition::Expr(Box::new(Expr::Uint(GenExpr {
+            left: NumValue::Env(left),
+            op: NumOp::Eq,
+            right: NumValue::Simple(Uint256::from(right)),
+        })))
+    };
+    let string_cond = |left: StringEnvValue, right: &str| {
+        Condition::Expr(Box::new(Expr::String(GenExpr {
+            left: StringValue::Env(left),
+            op: StringOp::Eq,
+            right: StringValue::Simple(right.to_string()),
+        })))
+    };
+
+    assert!(resolve(uint_cond(NumEnvValue::JobId, 7), &warp_env).unwrap());
+    assert!(resolve(uint_cond(NumEnvValue::ExecutionCount, 3), &warp_env).unwrap());
+    assert!(resolve(
+        uint_cond(NumEnvValue::AccountBalance("uluna".to_string()), 1000),
+        &warp_env
+    )
+    .unwrap());
+
+    assert!(resolve(
+        Condition::Expr(Box::new(Expr::Int(GenExpr {
+            left: NumValue::Env(NumEnvValue::BlockHeight),
+            op: NumOp::Eq,
+            right: NumValue::Simple(Int256::from(env.block.height)),
+        }))),
+        &warp_env
+    )
+    .unwrap());
+    assert!(resolve(
+        Condition::Expr(Box::new(Expr::Decimal(GenExpr {
+            left: NumValue::Env(NumEnvValue::ExecutionCount),
+            op: NumOp::Eq,
+            right: NumValue::Simple(SignedDecimal256::from_str("3").unwrap()),
+        }))),
+        &warp_env
+    )
+    .unwrap());
+
+    assert!(resolve(
+        string_cond(StringEnvValue::ChainId, &env.block.chain_id),
+        &warp_env
+    )
+    .unwrap());
+    assert!(resolve(string_cond(StringEnvValue::JobId, "7"), &warp_env).unwrap());
+    assert!(resolve(
+        string_cond(StringEnvValue::JobOwner, "terra1owner"),
+        &warp_env
+    )
+    .unwrap());
+    assert!(resolve(
+        string_cond(StringEnvValue::FundingAccountAddr, "terra1funding"),
+        &warp_env
+    )
+    .unwrap());
+
+    // job env values are only available when supplied by the controller
+    let no_job_env = WarpEnv {
+        warp_account_addr: Some("terra1account".to_string()),
+        job: None,
+        skip_price_staleness: false,
+    };
+    assert!(resolve(uint_cond(NumEnvValue::JobId, 7), &no_job_env).is_err());
+    assert!(resolve(
+        string_cond(StringEnvValue::JobOwner, "terra1owner"),
+        &no_job_env
+    )
+    .is_err());
+    assert!(resolve(
+        uint_cond(NumEnvValue::AccountBalance("uluna".to_string()), 1000),
+        &WarpEnv::default()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_hydrate_balance_vars_and_hydrate_msgs() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let warp_env = WarpEnv {
+        warp_account_addr: Some("terra1account".to_string()),
+        job: None,
+        skip_price_staleness: false,
+    };
+
+    let native_balance = Variable::Balance(BalanceVariable {
+        name: "native_balance".to_string(),
+        kind: VariableKind::Amount,
+        init_fn: BalanceExpr {
+            address: StringValue::Env(StringEnvValue::WarpAccountAddr),
+            asset: AssetInfo::Native("uluna".to_string()),
+        },
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let cw20_balance = Variable::Balance(BalanceVariable {
+        name: "cw20_balance".to_string(),
+        kind: VariableKind::Uint,
+        init_fn: BalanceExpr {
+            address: StringValue::Simple("terra1account".to_string()),
+            asset: AssetInfo::Cw20(Addr::unchecked("mock_cw20_contract_addr")),
+        },
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let vars = vec![native_balance, cw20_balance];
+    assert!(vars_valid(&vars));
+
+    let msgs = r#"[{"generic":{"bank":{"send":{"to_address":"terra1recipient","amount":[{"denom":"uluna","amount":"$warp.variable.native_balance"}]}}}}]"#;
+    assert!(msgs_valid(msgs, &vars).unwrap());
+
+    let hydrated_vars = hydrate_vars(deps.as_ref(), env.clone(), vars, None, &warp_env).unwrap();
+
+    match hydrated_vars[0].clone() {
+        Variable::Balance(v) => assert_eq!(v.value, Some("1000".to_string())),
+        _ => panic!("Expected balance variable"),
+    };
+    match hydrated_vars[1].clone() {
+        Variable::Balance(v) => assert_eq!(v.value, Some("500".to_string())),
+        _ => panic!("Expected balance variable"),
+    };
+
+    // values carried over from a previous execution are re-queried
+    let carried_vars = hydrated_vars
+        .iter()
+        .cloned()
+        .map(|var| match var {
+            Variable::Balance(mut v) => {
+                v.value = Some("1".to_string());
+                Variable::Balance(v)
+            }
+            var => var,
+        })
+        .collect();
+    let rehydrated_vars = hydrate_vars(deps.as_ref(), env, carried_vars, None, &warp_env).unwrap();
+    assert_eq!(rehydrated_vars, hydrated_vars);
+
+    let hydrated_msgs = hydrate_msgs(msgs.to_string(), hydrated_vars).unwrap();
+
+    assert_eq!(
+        hydrated_msgs[0],
+        WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "terra1recipient".to_string(),
+            amount: vec![Coin::new(1000, "uluna")],
+        }))
+    );
+
+    let invalid_kind = Variable::Balance(BalanceVariable {
+        name: "invalid_kind".to_string(),
+        kind: VariableKind::String,
+        init_fn: BalanceExpr {
+            address: StringValue::Env(StringEnvValue::WarpAccountAddr),
+            asset: AssetInfo::Native("uluna".to_string()),
+        },
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+    assert!(!vars_valid(&vec![invalid_kind]));
+}
+
+fn static_var(name: &str, kind: VariableKind, init_fn: FnValue) -> Variable {
+    Variable::Static(StaticVariable {
+        name: name.to_string(),
+        kind,
+        init_fn,
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    })
+}
+
+// var as if already hydrated
+fn initialized(mut var: Variable, value: &str) -> Variable {
+    if let Variable::Static(v) = &mut var {
+        v.value = Some(value.to_string());
+    }
+    var
+}
+
+// hydrated var with a simple string init_fn, the value is parsed by kind
+fn encoded_var(name: &str, kind: VariableKind, value: &str, encode: Encoding) -> Variable {
+    Variable::Static(StaticVariable {
+        name: name.to_string(),
+        kind,
+        init_fn: FnValue::String(StringValue::Simple(value.to_string())),
+        value: Some(value.to_string()),
+        reinitialize: false,
+        update_fn: None,
+        encode,
+    })
+}
+
+// string var initialized from a reference to dep
+fn cycle_var(name: &str, dep: &str) -> Variable {
+    static_var(
+        name,
+        VariableKind::String,
+        FnValue::String(StringValue::Ref(format!("$warp.variable.{}", dep))),
+    )
+}
+
+fn static_uint(name: &str, value: &str) -> Variable {
+    static_var(
+        name,
+        VariableKind::Uint,
+        FnValue::Uint(NumValue::Simple(Uint256::from_str(value).unwrap())),
+    )
+}
+
+// condition JSON of var > 5
+fn uint_gt(var: &str) -> String {
+    format!(
+        r#"{{"expr":{{"uint":{{"op":"gt","left":{{"ref":"$warp.variable.{}"}},"right":{{"simple":"5"}}}}}}}}"#,
+        var
+    )
+}
+
+fn price_var(name: &str, source: PriceSource, base: &str) -> Variable {
+    Variable::Price(PriceVariable {
+        name: name.to_string(),
+        kind: VariableKind::Decimal,
+        init_fn: PriceExpr {
+            source,
+            base: base.to_string(),
+            quote: "usd".to_string(),
+            max_age_seconds: 60,
+            max_confidence: Some(Decimal256::from_str("0.01").unwrap()),
+        },
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    })
+}
+
+fn price_value(var: &Variable) -> Option<String> {
+    match var {
+        Variable::Price(v) => v.value.clone(),
+        _ => panic!("Expected price variable"),
+    }
+}
+
+// resolver and mock oracles in a multi-test app
+fn mock_price_app() -> (App, Addr, Addr, Addr) {
+    let mut app = App::default();
+    let resolver_code_id = app.store_code(Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        query,
+    )));
+    let oracle_code_id = app.store_code(mock_oracle_contract());
+
+    let owner = Addr::unchecked("owner");
+    let resolver_addr = app
+        .instantiate_contract(
+            resolver_code_id,
+            owner.clone(),
+            &resolver::InstantiateMsg {},
+            &[],
+            "resolver",
+            None,
+        )
+        .unwrap();
+    let oracle_addr = app
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "oracle",
+            None,
+        )
+        .unwrap();
+    let pool_addr = app
+        .instantiate_contract(oracle_code_id, owner, &Empty {}, &[], "pool", None)
+        .unwrap();
+
+    (app, resolver_addr, oracle_addr, pool_addr)
+}
+
+fn set_price(app: &mut App, oracle: &Addr, price: &str, confidence: Option<&str>) {
+    app.execute_contract(
+        Addr::unchecked("owner"),
+        oracle.clone(),
+        &MockOracleExecuteMsg::SetPrice {
+            price: Decimal256::from_str(price).unwrap(),
+            confidence: confidence.map(|c| Decimal256::from_str(c).unwrap()),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn advance_time(app: &mut App, seconds: u64) {
+    app.update_block(|block| block.time = block.time.plus_seconds(seconds));
+}
+
+fn hydrate_prices(app: &App, resolver: &Addr, vars: &Vec<Variable>) -> StdResult<Vec<Variable>> {
+    hydrate_prices_with(app, resolver, vars, None)
+}
+
+fn hydrate_prices_with(
+    app: &App,
+    resolver: &Addr,
+    vars: &Vec<Variable>,
+    skip_price_staleness: Option<bool>,
+) -> StdResult<Vec<Variable>> {
+    let res: String = app.wrap().query_wasm_smart(
+        resolver,
+        &QueryMsg::QueryHydrateVars(QueryHydrateVarsMsg {
+            vars: serde_json_wasm::to_string(vars).unwrap(),
+            external_inputs: None,
+            warp_account_addr: None,
+            job_env: None,
+            skip_price_staleness,
+        }),
+    )?;
+    Ok(serde_json_wasm::from_str(&res).unwrap())
+}
+
+#[test]
+fn test_hydrate_price_vars() {
+    let (mut app, resolver_addr, oracle_addr, pool_addr) = mock_price_app();
+
+    let custom = PriceSource::Custom {
+        contract_addr: oracle_addr.to_string(),
+    };
+    let pyth = PriceSource::Pyth {
+        contract_addr: oracle_addr.to_string(),
+    };
+    let band = PriceSource::Band {
+        contract_addr: oracle_addr.to_string(),
+    };
+    let twap = |window_seconds| PriceSource::PoolTwap {
+        contract_addr: pool_addr.to_string(),
+        window_seconds,
+    };
+
+    // pool price is 1 for 100s then 3 for 100s, spot price is 1.5
+    set_price(&mut app, &pool_addr, "1", None);
+    advance_time(&mut app, 100);
+    set_price(&mut app, &pool_addr, "3", None);
+    advance_time(&mut app, 100);
+    set_price(&mut app, &oracle_addr, "1.5", Some("0.003"));
+
+    let spot_vars = vec![
+        price_var("custom_price", custom.clone(), "luna"),
+        price_var("pyth_price", pyth, "luna_feed"),
+        price_var("band_price", band, "LUNA"),
+    ];
+    assert!(vars_valid(&spot_vars));
+
+    let hydrated_vars = hydrate_prices(&app, &resolver_addr, &spot_vars).unwrap();
+    for var in hydrated_vars.iter() {
+        assert_eq!(price_value(var), Some("1.5".to_string()));
+    }
+
+    // time weighted over the window
+    let twap_vars = vec![
+        price_var("twap_price", twap(200), "luna"),
+        price_var("half_window_price", twap(150), "luna"),
+    ];
+    let hydrated_twap_vars = hydrate_prices(&app, &resolver_addr, &twap_vars).unwrap();
+    assert_eq!(price_value(&hydrated_twap_vars[0]), Some("2".to_string()));
+    assert_eq!(
+        price_value(&hydrated_twap_vars[1]),
+        Some("2.333333333333333333".to_string())
+    );
+
+    // window not covered by the pool history
+    let err = hydrate_prices(
+        &app,
+        &resolver_addr,
+        &vec![price_var("long", twap(1000), "luna")],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("no price observed"));
+
+    // staleness is measured from the end of the window, not from the last pool price change
+    advance_time(&mut app, 100);
+    let hydrated_twap_vars = hydrate_prices(
+        &app,
+        &resolver_addr,
+        &vec![price_var("twap", twap(200), "luna")],
+    )
+    .unwrap();
+    assert_eq!(price_value(&hydrated_twap_vars[0]), Some("3".to_string()));
+
+    // hydrated prices are re-checked on every hydration, and go stale
+    set_price(&mut app, &oracle_addr, "1.5", Some("0.003"));
+    advance_time(&mut app, 61);
+    for var in hydrated_vars.iter() {
+        let err = hydrate_prices(&app, &resolver_addr, &vec![var.clone()]).unwrap_err();
+        assert!(err.to_string().contains("is stale"));
+
+        // unless hydrated for the next iteration of a recurring job
+        let res = hydrate_prices_with(&app, &resolver_addr, &vec![var.clone()], Some(true));
+        assert_eq!(price_value(&res.unwrap()[0]), Some("1.5".to_string()));
+    }
+
+    // confidence interval of 20% is over the 1% max, band reports no confidence
+    set_price(&mut app, &oracle_addr, "1.5", Some("0.3"));
+    for var in spot_vars.iter() {
+        let res = hydrate_prices(&app, &resolver_addr, &vec![var.clone()]);
+        match var {
+            Variable::Price(PriceVariable {
+                init_fn:
+                    PriceExpr {
+                        source: PriceSource::Band { .. },
+                        ..
+                    },
+                ..
+            }) => assert_eq!(price_value(&res.unwrap()[0]), Some("1.5".to_string())),
+            _ => assert!(res.unwrap_err().to_string().contains("confidence interval")),
+        }
+    }
+
+    // relative confidence of a tiny price overflows
+    set_price(
+        &mut app,
+        &oracle_addr,
+        "0.000000000000000001",
+        Some("1000000000000000000000000000000000000000000000000"),
+    );
+    let err = hydrate_prices(
+        &app,
+        &resolver_addr,
+        &vec![price_var("custom_price", custom.clone(), "luna")],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("confidence interval out of range"));
+
+    let deps = mock_dependencies();
+    let condition = Condition::Expr(Box::new(Expr::Decimal(GenExpr {
+        left: NumValue::Ref("$warp.variable.custom_price".to_string()),
+        op: NumOp::Gt,
+        right: NumValue::Simple(SignedDecimal256::one()),
+    })));
+    assert!(resolve_cond(
+        deps.as_ref(),
+        mock_env(),
+        condition,
+        &hydrated_vars,
+        &WarpEnv::default()
+    )
+    .unwrap());
+
+    let mut invalid_kind = price_var("invalid_kind", custom, "luna");
+    if let Variable::Price(v) = &mut invalid_kind {
+        v.kind = VariableKind::Uint;
+    }
+    assert!(!vars_valid(&vec![invalid_kind]));
+
+    // preset values are rejected
+    assert!(!vars_valid(&hydrated_vars));
+}
+
+#[test]
+fn test_hydrate_vars_in_dependency_order() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    // declared before the static var it references
+    let cw20_balance = Variable::Query(QueryVariable {
+        name: "cw20_balance".to_string(),
+        kind: VariableKind::Uint,
+        init_fn: QueryExpr {
+            selector: "$.balance".to_string(),
+            query: QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: "$warp.variable.cw20_addr".to_string(),
+                msg: Binary::from(r#"{"balance":{"address":"$warp.variable.owner"}}"#.as_bytes()),
+            }),
+        },
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let cw20_addr = Variable::Static(StaticVariable {
+        name: "cw20_addr".to_string(),
+        kind: VariableKind::String,
+        init_fn: FnValue::String(StringValue::Simple("mock_cw20_contract_addr".to_string())),
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let owner = Variable::Static(StaticVariable {
+        name: "owner".to_string(),
+        kind: VariableKind::String,
+        init_fn: FnValue::String(StringValue::Ref("$warp.variable.owner_prefix".to_string())),
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    // shares a prefix with owner without being referenced by it
+    let owner_prefix = Variable::Static(StaticVariable {
+        name: "owner_prefix".to_string(),
+        kind: VariableKind::String,
+        init_fn: FnValue::String(StringValue::Simple("terra1account".to_string())),
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let vars = vec![cw20_balance, cw20_addr, owner, owner_prefix];
+    assert_eq!(dependency_order(&vars).unwrap(), vec![1, 3, 2, 0]);
+
+    let hydrated_vars =
+        hydrate_vars(deps.as_ref(), env.clone(), vars, None, &WarpEnv::default()).unwrap();
+
+    // declaration order is kept
+    match hydrated_vars[0].clone() {
+        Variable::Query(v) => assert_eq!(v.value, Some("500".to_string())),
+        _ => panic!("Expected query variable"),
+    };
+    match hydrated_vars[2].clone() {
+        Variable::Static(v) => assert_eq!(v.value, Some("terra1account".to_string())),
+        _ => panic!("Expected static variable"),
+    };
+
+    let cycle = vec![cycle_var("a", "b"), cycle_var("b", "a")];
+
+    assert_eq!(
+        dependency_order(&cycle).unwrap_err(),
+        ContractError::VariableDependencyCycle {
+            names: vec!["a".to_string(), "b".to_string()]
+        }
+    );
+
+    let msg = QueryValidateJobCreationMsg {
+        executions: vec![Execution {
+            condition: r#"{"expr":{"string":{"op":"eq","left":{"ref":"$warp.variable.a"},"right":{"simple":"a"}}}}"#.to_string(),
+            msgs: "[]".to_string(),
+        }],
+        terminate_condition: None,
+        vars: serde_json_wasm::to_string(&cycle).unwrap(),
+    };
+    let err = query(deps.as_ref(), env, QueryMsg::QueryValidateJobCreation(msg)).unwrap_err();
+    assert!(err.to_string().contains("dependency cycle"));
+}
+
+#[test]
+fn test_resolve_execution_hydrates_lazily() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let missing_price = Variable::Query(QueryVariable {
+        name: "missing_price".to_string(),
+        kind: VariableKind::Decimal,
+        init_fn: QueryExpr {
+            selector: "$.price".to_string(),
+            query: QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: "contract_addr".to_string(),
+                msg: Binary::from(r#"{"price":{}}"#.as_bytes()),
+            }),
+        },
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+
+    let vars = serde_json_wasm::to_string(&vec![
+        static_uint("low", "1"),
+        static_uint("amount", "1000"),
+        missing_price,
+    ])
+    .unwrap();
+
+    let msgs = r#"[{"generic":{"bank":{"send":{"to_address":"terra1recipient","amount":[{"denom":"uluna","amount":"$warp.variable.amount"}]}}}}]"#;
+    let missing_condition = r#"{"expr":{"decimal":{"op":"gt","left":{"ref":"$warp.variable.missing_price"},"right":{"simple":"1"}}}}"#;
+
+    let resolve_with = |vars: String, executions: Vec<Execution>| {
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::QueryResolveExecution(QueryResolveExecutionMsg {
+                vars,
+                executions,
+                external_inputs: None,
+                warp_account_addr: None,
+                job_env: None,
+            }),
+        )
+        .map(|res| from_json::<ResolveExecutionResponse>(&res).unwrap())
+    };
+    let resolve = |executions: Vec<Execution>| resolve_with(vars.clone(), executions);
+
+    // the failing query of the last execution is never hydrated
+    let res = resolve(vec![
+        Execution {
+            condition: uint_gt("low"),
+            msgs: "[]".to_string(),
+        },
+        Execution {
+            condition: uint_gt("amount"),
+            msgs: msgs.to_string(),
+        },
+        Execution {
+            condition: missing_condition.to_string(),
+            msgs: "[]".to_string(),
+        },
+    ])
+    .unwrap();
+    assert_eq!(
+        res,
+        ResolveExecutionResponse {
+            index: Some(1),
+            msgs: Some(vec![WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "terra1recipient".to_string(),
+                amount: vec![Coin::new(1000, "uluna")],
+            }))]),
+            error: None,
+        }
+    );
+
+    // no execution matched
+    let res = resolve(vec![Execution {
+        condition: uint_gt("low"),
+        msgs: "[]".to_string(),
+    }])
+    .unwrap();
+    assert_eq!(res.index, None);
+    assert_eq!(res.msgs, None);
+
+    // hydration errors of an evaluated condition fail the query, they may be temporary
+    let err = resolve(vec![
+        Execution {
+            condition: uint_gt("low"),
+            msgs: "[]".to_string(),
+        },
+        Execution {
+            condition: missing_condition.to_string(),
+            msgs: "[]".to_string(),
+        },
+    ])
+    .unwrap_err();
+    assert!(err.to_string().contains("missing_price"));
+
+    // as do hydration errors of the matched msgs
+    let err = resolve(vec![Execution {
+        condition: uint_gt("amount"),
+        msgs: r#"[{"generic":{"bank":{"send":{"to_address":"terra1recipient","amount":[{"denom":"uluna","amount":"$warp.variable.missing_price"}]}}}}]"#.to_string(),
+    }])
+    .unwrap_err();
+    assert!(err.to_string().contains("missing_price"));
+
+    // msgs errors are returned with the msgs path
+    let res = resolve(vec![Execution {
+        condition: uint_gt("amount"),
+        msgs: r#"[{"generic":"$warp.variable.amount"}]"#.to_string(),
+    }])
+    .unwrap();
+    assert_eq!(res.index, Some(0));
+    assert_eq!(res.msgs, None);
+    assert_eq!(
+        res.error.unwrap().path,
+        Some("executions[0].msgs".to_string())
+    );
+
+    // invalid vars are returned without an execution index
+    let cycle =
+        serde_json_wasm::to_string(&vec![cycle_var("a", "b"), cycle_var("b", "a")]).unwrap();
+    let res = resolve_with(
+        cycle,
+        vec![Execution {
+            condition: uint_gt("low"),
+            msgs: "[]".to_string(),
+        }],
+    )
+    .unwrap();
+    assert_eq!(res.index, None);
+    let error = res.error.unwrap();
+    assert_eq!(error.kind, ResolverErrorKind::InvalidVariables);
+    assert_eq!(error.variable, Some("a".to_string()));
+    assert_eq!(error.path, Some("vars".to_string()));
+
+    // condition errors are returned with the index of the execution
+    let res = resolve(vec![
+        Execution {
+            condition: uint_gt("low"),
+            msgs: "[]".to_string(),
+        },
+        Execution {
+            condition: "invalid".to_string(),
+            msgs: "[]".to_string(),
+        },
+    ])
+    .unwrap();
+    assert_eq!(res.index, Some(1));
+    assert_eq!(
+        res.error.map(|e| (e.kind, e.path)),
+        Some((
+            ResolverErrorKind::Parse,
+            Some("executions[1].condition".to_string())
+        ))
+    );
+}
+
+#[test]
+fn test_resolve_condition_v2_structured_errors() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let uninitialized = Variable::Static(StaticVariable {
+        name: "uninitialized".to_string(),
+        kind: VariableKind::Uint,
+        init_fn: FnValue::Uint(NumValue::Simple(Uint256::one())),
+        value: None,
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+    let initialized = Variable::Static(StaticVariable {
+        name: "initialized".to_string(),
+        kind: VariableKind::Uint,
+        init_fn: FnValue::Uint(NumValue::Simple(Uint256::one())),
+        value: Some("10".to_string()),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+    let vars = serde_json_wasm::to_string(&vec![uninitialized, initialized]).unwrap();
+
+    let resolve = |condition: &str| {
+        from_json::<ResolveConditionResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::QueryResolveConditionV2(QueryResolveConditionMsg {
+                    condition: condition.to_string(),
+                    vars: vars.clone(),
+                    warp_account_addr: None,
+                    job_env: None,
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        resolve(&uint_gt("initialized")),
+        ResolveConditionResponse::Ok(true)
+    );
+
+    assert_eq!(
+        resolve(&uint_gt("uninitialized")),
+        ResolveConditionResponse::Err(ResolverError {
+            kind: ResolverErrorKind::Condition,
+            variable: Some("uninitialized".to_string()),
+            path: Some("condition".to_string()),
+            message: "Uint Static value not found: uninitialized".to_string(),
+        })
+    );
+
+    match resolve(&uint_gt("missing")) {
+        ResolveConditionResponse::Err(e) => {
+            assert_eq!(e.kind, ResolverErrorKind::VariableNotFound);
+            assert_eq!(e.variable, Some("missing".to_string()));
+        }
+        _ => panic!("Expected error"),
+    }
+
+    match resolve("invalid") {
+        ResolveConditionResponse::Err(e) => {
+            assert_eq!(e.kind, ResolverErrorKind::Parse);
+            assert_eq!(e.variable, None);
+        }
+        _ => panic!("Expected error"),
+    }
+}
+
+#[test]
+fn test_lint_job() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let mut amount = static_uint("amount", "10");
+    if let Variable::Static(v) = &mut amount {
+        v.update_fn = Some(UpdateFn {
+            on_success: Some(FnValue::Decimal(NumValue::Simple(SignedDecimal256::one()))),
+            on_error: None,
+        });
+    }
+    let price = static_var(
+        "price",
+        VariableKind::Decimal,
+        FnValue::Decimal(NumValue::Simple(SignedDecimal256::one())),
+    );
+    let unused = static_var(
+        "unused",
+        VariableKind::String,
+        FnValue::String(StringValue::Simple("unused".to_string())),
+    );
+
+    let execution = |condition: String| Execution {
+        condition,
+        msgs: "[]".to_string(),
+    };
+
+    let msg = QueryLintJobMsg {
+        terminate_condition: None,
+        vars: serde_json_wasm::to_string(&vec![amount, price, unused]).unwrap(),
+        executions: vec![
+            // decimal var used in a uint expression
+            execution(uint_gt("price")),
+            // in the past
+            execution(
+                serde_json_wasm::to_string(&Condition::Expr(Box::new(Expr::Timestamp(TimeExpr {
+                    comparator: Uint64::new(1),
+                    op: NumOp::Lt,
+                }))))
+                .unwrap(),
+            ),
+            // constant
+            execution(
+                r#"{"expr":{"uint":{"op":"gt","left":{"simple":"6"},"right":{"simple":"5"}}}}"#
+                    .to_string(),
+            ),
+            execution(uint_gt("amount")),
+        ],
+    };
+
+    let res: LintJobResponse =
+        from_json(query(deps.as_ref(), env, QueryMsg::QueryLintJob(msg)).unwrap()).unwrap();
+
+    let issues: Vec<(LintSeverity, LintKind, String, Option<String>)> = res
+        .issues
+        .into_iter()
+        .map(|i| (i.severity, i.kind, i.path, i.variable))
+        .collect();
+
+    assert_eq!(
+        issues,
+        vec![
+            (
+                LintSeverity::Error,
+                LintKind::UpdateFnKindMismatch,
+                "vars[0].update_fn.on_success".to_string(),
+                Some("amount".to_string())
+            ),
+            (
+                LintSeverity::Error,
+                LintKind::RefKindMismatch,
+                "executions[0].condition".to_string(),
+                Some("price".to_string())
+            ),
+            (
+                LintSeverity::Warning,
+                LintKind::NeverTrueCondition,
+                "executions[1].condition".to_string(),
+                None
+            ),
+            (
+                LintSeverity::Warning,
+                LintKind::UnreachableExecution,
+                "executions[3].condition".to_string(),
+                None
+            ),
+            (
+                LintSeverity::Warning,
+                LintKind::UnusedVariable,
+                "vars[2]".to_string(),
+                Some("unused".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_timestamp_and_block_height_vars() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+    let now = env.block.time.seconds();
+    let height = env.block.height;
+
+    // now + 1 day, moved 1 hour forward after every execution
+    let next_run = Variable::Static(StaticVariable {
+        name: "next_run".to_string(),
+        kind: VariableKind::Timestamp,
+        init_fn: FnValue::Timestamp(NumValue::Expr(NumExprValue {
+            left: Box::new(NumValue::Env(NumEnvValue::Time)),
+            op: NumExprOp::Add,
+            right: Box::new(NumValue::Duration(Duration::Days(1))),
+        })),
+        value: None,
+        reinitialize: false,
+        update_fn: Some(UpdateFn {
+            on_success: Some(FnValue::Timestamp(NumValue::Expr(NumExprValue {
+                left: Box::new(NumValue::Ref("$warp.variable.next_run".to_string())),
+                op: NumExprOp::Add,
+                right: Box::new(NumValue::Duration(Duration::Hours(1))),
+            }))),
+            on_error: None,
+        }),
+        encode: Encoding::None,
+    });
+
+    let start_height = Variable::Static(StaticVariable {
+        name: "start_height".to_string(),
+        kind: VariableKind::BlockHeight,
+        init_fn: FnValue::BlockHeight(NumValue::Expr(NumExprValue {
+            left: Box::new(NumValue::Env(NumEnvValue::BlockHeight)),
+            op: NumExprOp::Add,
+            right: Box::new(NumValue::Simple(Int256::from(100u64))),
+        })),
+        value: None,
+        reinitialize: false,
+        update_fn: Some(UpdateFn {
+            on_success: Some(FnValue::BlockHeight(NumValue::Env(
+                NumEnvValue::BlockHeight,
+            ))),
+            on_error: None,
+        }),
+        encode: Encoding::None,
+    });
+
+    let vars = vec![next_run, start_height];
+    assert!(vars_valid(&vars));
+
+    let hydrated_vars =
+        hydrate_vars(deps.as_ref(), env.clone(), vars, None, &WarpEnv::default()).unwrap();
+    let values = |vars: &Vec<Variable>| -> Vec<Option<String>> {
+        vars.iter()
+            .map(|v| match v {
+                Variable::Static(v) => v.value.clone(),
+                _ => panic!("Expected static variable"),
+            })
+            .collect()
+    };
+    assert_eq!(
+        values(&hydrated_vars),
+        vec![
+            Some((now + 86_400).to_string()),
+            Some((height + 100).to_string())
+        ]
+    );
+
+    let updated_vars: Vec<Variable> = serde_json_wasm::from_str(
+        &apply_var_fn(
+            deps.as_ref(),
+            env.clone(),
+            hydrated_vars,
+            JobStatus::Executed,
+            &WarpEnv::default(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        values(&updated_vars),
+        vec![
+            Some((now + 86_400 + 3_600).to_string()),
+            Some(height.to_string())
+        ]
+    );
+
+    // jobs created before the Timestamp kind keep their time in Int vars
+    let legacy_fn = FnValue::Timestamp(NumValue::Env(NumEnvValue::Time));
+    assert!(update_fn_kind_valid(&legacy_fn, &VariableKind::Int));
+    assert!(!update_fn_kind_valid(&legacy_fn, &VariableKind::Uint));
+    assert!(!update_fn_kind_valid(
+        &legacy_fn,
+        &VariableKind::BlockHeight
+    ));
+    assert!(!update_fn_kind_valid(
+        &FnValue::BlockHeight(NumValue::Env(NumEnvValue::BlockHeight)),
+        &VariableKind::Int
+    ));
+    let mut last_run = initialized(
+        static_var(
+            "last_run",
+            VariableKind::Int,
+            FnValue::Int(NumValue::Simple(Int256::zero())),
+        ),
+        "0",
+    );
+    if let Variable::Static(v) = &mut last_run {
+        v.update_fn = Some(UpdateFn {
+            on_success: Some(legacy_fn),
+            on_error: None,
+        });
+    }
+    let updated_vars: Vec<Variable> = serde_json_wasm::from_str(
+        &apply_var_fn(
+            deps.as_ref(),
+            env.clone(),
+            vec![last_run],
+            JobStatus::Executed,
+            &WarpEnv::default(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(values(&updated_vars), vec![Some(now.to_string())]);
+
+    let time_cond = |op: NumOp, comparator: u64| {
+        resolve_cond(
+            deps.as_ref(),
+            env.clone(),
+            Condition::Expr(Box::new(Expr::Timestamp(TimeExpr {
+                comparator: Uint64::new(comparator),
+                op,
+            }))),
+            &vec![],
+            &WarpEnv::default(),
+        )
+        .unwrap()
+    };
+    assert!(time_cond(NumOp::Gte, now));
+    assert!(time_cond(NumOp::Lte, now));
+    assert!(time_cond(NumOp::Eq, now));
+    assert!(!time_cond(NumOp::Neq, now));
+    assert!(time_cond(NumOp::Lt, now + 1));
+    assert!(!time_cond(NumOp::Gt, now));
+}
+
+#[test]
+fn test_int256_and_signed_decimal_exprs() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    // jobs stored before the switch to Int256 / SignedDecimal256 serialized i128 and Decimal256 as strings
+    let legacy_cond = format!(
+        "{{\"expr\":{{\"int\":{{\"left\":{{\"simple\":{}}},\"op\":\"lt\",\"right\":{{\"simple\":\"3\"}}}}}}}}",
+        serde_json_wasm::to_string(&-5i128).unwrap()
+    );
+    let legacy_cond: Condition = serde_json_wasm::from_str(&legacy_cond).unwrap();
+    assert_eq!(
+        legacy_cond,
+        Condition::Expr(Box::new(Expr::Int(GenExpr {
+            left: NumValue::Simple(Int256::from(-5i128)),
+            op: NumOp::Lt,
+            right: NumValue::Simple(Int256::from(3i128)),
+        })))
+    );
+    assert!(resolve_cond(
+        deps.as_ref(),
+        env.clone(),
+        legacy_cond,
+        &vec![],
+        &WarpEnv::default()
+    )
+    .unwrap());
+
+    let int_var = |name: &str, value: &str| {
+        initialized(
+            static_var(
+                name,
+                VariableKind::Int,
+                FnValue::Int(NumValue::Simple(Int256::from_str(value).unwrap())),
+            ),
+            value,
+        )
+    };
+
+    // PnL over 18 decimal amounts, beyond the i128 range
+    let vars = vec![
+        int_var("entry", "300000000000000000000000000000000000000"),
+        int_var("exit", "100000000000000000000000000000000000000"),
+    ];
+    assert!(vars_valid(&vars));
+
+    let pnl = NumValue::Expr(NumExprValue {
+        left: Box::new(NumValue::Ref("$warp.variable.exit".to_string())),
+        op: NumExprOp::Sub,
+        right: Box::new(NumValue::Ref("$warp.variable.entry".to_string())),
+    });
+    assert!(resolve_cond(
+        deps.as_ref(),
+        env.clone(),
+        Condition::Expr(Box::new(Expr::Int(GenExpr {
+            left: pnl.clone(),
+            op: NumOp::Eq,
+            right: NumValue::Simple(
+                Int256::from_str("-200000000000000000000000000000000000000").unwrap()
+            ),
+        }))),
+        &vars,
+        &WarpEnv::default(),
+    )
+    .unwrap());
+    assert!(resolve_cond(
+        deps.as_ref(),
+        env.clone(),
+        Condition::Expr(Box::new(Expr::Int(GenExpr {
+            left: NumValue::Fn(NumFnValue {
+                op: IntFnOp::Abs,
+                right: Box::new(pnl),
+            }),
+            op: NumOp::Gt,
+            right: NumValue::Ref("$warp.variable.exit".to_string()),
+        }))),
+        &vars,
+        &WarpEnv::default(),
+    )
+    .unwrap());
+
+    // overflow is an error rather than a wrap around
+    let overflow = resolve_cond(
+        deps.as_ref(),
+        env.clone(),
+        Condition::Expr(Box::new(Expr::Int(GenExpr {
+            left: NumValue::Expr(NumExprValue {
+                left: Box::new(NumValue::Simple(Int256::MAX)),
+                op: NumExprOp::Add,
+                right: Box::new(NumValue::Simple(Int256::one())),
+            }),
+            op: NumOp::Gt,
+            right: NumValue::Simple(Int256::zero()),
+        }))),
+        &vec![],
+        &WarpEnv::default(),
+    );
+    assert_eq!(
+        overflow,
+        Err(ContractError::ConditionError {
+            msg: "Int checked add error.".to_string()
+        })
+    );
+
+    let decimal_fn = |op: DecimalFnOp, value: &str| {
+        NumValue::Fn(NumFnValue {
+            op,
+            right: Box::new(NumValue::Simple(SignedDecimal256::from_str(value).unwrap())),
+        })
+    };
+    let decimal_eq = |left: NumValue<SignedDecimal256, NumExprOp, DecimalFnOp>, right: &str| {
+        resolve_cond(
+            deps.as_ref(),
+            env.clone(),
+            Condition::Expr(Box::new(Expr::Decimal(GenExpr {
+                left,
+                op: NumOp::Eq,
+                right: NumValue::Simple(SignedDecimal256::from_str(right).unwrap()),
+            }))),
+            &vec![],
+            &WarpEnv::default(),
+        )
+    };
+    assert_eq!(
+        decimal_eq(decimal_fn(DecimalFnOp::Neg, "1.5"), "-1.5"),
+        Ok(true)
+    );
+    assert_eq!(
+        decimal_eq(decimal_fn(DecimalFnOp::Abs, "-1.5"), "1.5"),
+        Ok(true)
+    );
+    assert_eq!(
+        decimal_eq(decimal_fn(DecimalFnOp::Floor, "-1.5"), "-2"),
+        Ok(true)
+    );
+    assert_eq!(
+        decimal_eq(decimal_fn(DecimalFnOp::Sqrt, "2.25"), "1.5"),
+        Ok(true)
+    );
+    assert_eq!(
+        decimal_eq(decimal_fn(DecimalFnOp::Sqrt, "-2.25"), "1.5"),
+        Err(ContractError::ConditionError {
+            msg: "Decimal sqrt of a negative value.".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_series_vars() {
+    let deps = mock_dependencies();
+    let env = mock_env();
+
+    let price = |value: &str| {
+        Variable::Static(StaticVariable {
+            name: "price".to_string(),
+            kind: VariableKind::Decimal,
+            init_fn: FnValue::Decimal(NumValue::Simple(SignedDecimal256::from_str(value).unwrap())),
+            value: Some(value.to_string()),
+            reinitialize: false,
+            update_fn: None,
+            encode: Encoding::None,
+        })
+    };
+    // moving window of the last 3 prices, sampled after every execution
+    let series = Variable::Static(StaticVariable {
+        name: "prices".to_string(),
+        kind: VariableKind::Series { capacity: 3 },
+        init_fn: FnValue::Decimal(NumValue::Ref("$warp.variable.price".to_string())),
+        value: None,
+        reinitialize: false,
+        update_fn: Some(UpdateFn {
+            on_success: Some(FnValue::Decimal(NumValue::Ref(
+                "$warp.variable.price".to_string(),
+            ))),
+            on_error: None,
+        }),
+        encode: Encoding::None,
+    });
+
+    let vars = vec![series, price("1")];
+    assert!(vars_valid(&vars));
+    let mut vars =
+        hydrate_vars(deps.as_ref(), env.clone(), vars, None, &WarpEnv::default()).unwrap();
+
+    for next in ["2", "-3", "4"] {
+        vars[1] = price(next);
+        vars = serde_json_wasm::from_str(
+            &apply_var_fn(
+                deps.as_ref(),
+                env.clone(),
+                vars,
+                JobStatus::Executed,
+                &WarpEnv::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    }
+
+    // oldest sample dropped beyond capacity
+    match &vars[0] {
+        Variable::Static(v) => assert_eq!(v.value, Some("[\"2\",\"-3\",\"4\"]".to_string())),
+        _ => panic!("Expected static variable"),
+    }
+    assert!(vars_valid(&vars));
+
+    let series_eq = |op: SeriesFnOp, expected: &str| {
+        resolve_cond(
+            deps.as_ref(),
+            env.clone(),
+            Condition::Expr(Box::new(Expr::Decimal(GenExpr {
+                left: NumValue::Series(SeriesValue {
+                    op,
+                    series: "$warp.variable.prices".to_string(),
+                }),
+                op: NumOp::Eq,
+                right: NumValue::Simple(SignedDecimal256::from_str(expected).unwrap()),
+            }))),
+            &vars,
+            &WarpEnv::default(),
+        )
+    };
+    assert_eq!(series_eq(SeriesFnOp::Avg, "1"), Ok(true));
+    assert_eq!(series_eq(SeriesFnOp::Min, "-3"), Ok(true));
+    assert_eq!(series_eq(SeriesFnOp::Max, "4"), Ok(true));
+    assert_eq!(series_eq(SeriesFnOp::Last(1), "4"), Ok(true));
+    assert_eq!(series_eq(SeriesFnOp::Last(3), "2"), Ok(true));
+    assert_eq!(
+        series_eq(SeriesFnOp::Last(4), "2"),
+        Err(ContractError::VariableError {
+            name: "prices".to_string(),
+            error: Box::new(ContractError::ConditionError {
+                msg: "Series has 3 samples, last(4) requested.".to_string()
+            })
+        })
+    );
+
+    // uint aggregates truncate
+    assert!(resolve_cond(
+        deps.as_ref(),
+        env.clone(),
+        Condition::Expr(Box::new(Expr::Uint(GenExpr {
+            left: NumValue::Series(SeriesValue {
+                op: SeriesFnOp::Max,
+                series: "$warp.variable.prices".to_string(),
+            }),
+            op: NumOp::Eq,
+            right: NumValue::Simple(Uint256::from(4u64)),
+        }))),
+        &vars,
+        &WarpEnv::default(),
+    )
+    .unwrap());
+
+    assert_eq!(
+        serde_json_wasm::to_string(&VariableKind::Series { capacity: 3 }).unwrap(),
+        "{\"series\":{\"capacity\":3}}"
+    );
+
+    let valid = |kind: VariableKind, value: &str| {
+        vars_valid(&vec![Variable::Static(StaticVariable {
+            name: "prices".to_string(),
+            kind,
+            init_fn: FnValue::Decimal(NumValue::Simple(SignedDecimal256::one())),
+            value: Some(value.to_string()),
+            reinitialize: false,
+            update_fn: None,
+            encode: Encoding::None,
+        })])
+    };
+    assert!(valid(VariableKind::Series { capacity: 2 }, "[\"1\"]"));
+    assert!(!valid(VariableKind::Series { capacity: 0 }, "[]"));
+    assert!(!valid(
+        VariableKind::Series { capacity: 2 },
+        "[\"1\",\"2\",\"3\"]"
+    ));
+    assert!(!valid(VariableKind::Series { capacity: 2 }, "1"));
+    assert!(valid(
+        VariableKind::Series {
+            capacity: SERIES_MAX_CAPACITY
+        },
+        "[]"
+    ));
+    assert!(!valid(
+        VariableKind::Series {
+            capacity: SERIES_MAX_CAPACITY + 1
+        },
+        "[]"
+    ));
+
+    // reinitializing would reset the history on every execution
+    assert!(!vars_valid(&vec![Variable::Static(StaticVariable {
+        name: "prices".to_string(),
+        kind: VariableKind::Series { capacity: 3 },
+        init_fn: FnValue::Decimal(NumValue::Simple(SignedDecimal256::one())),
+        value: None,
+        reinitialize: true,
+        update_fn: None,
+        encode: Encoding::None,
+    })]));
+}
+
+#[test]
+fn test_variable_encodings() {
+    // legacy bool encode
+    let legacy: StaticVariable = serde_json_wasm::from_str(
+        "{\"kind\":\"string\",\"name\":\"a\",\"encode\":true,\"init_fn\":{\"string\":{\"simple\":\"x\"}},\"reinitialize\":false,\"value\":null,\"update_fn\":null}",
+    )
+    .unwrap();
+    assert_eq!(legacy.encode, Encoding::Base64);
+    let legacy: StaticVariable = serde_json_wasm::from_str(
+        "{\"kind\":\"string\",\"name\":\"a\",\"encode\":false,\"init_fn\":{\"string\":{\"simple\":\"x\"}},\"reinitialize\":false,\"value\":null,\"update_fn\":null}",
+    )
+    .unwrap();
+    assert_eq!(legacy.encode, Encoding::None);
+    assert_eq!(
+        serde_json_wasm::to_string(&Encoding::Base64Json).unwrap(),
+        "\"base64_json\""
+    );
+    assert_eq!(
+        serde_json_wasm::from_str::<Encoding>("\"json_string\"").unwrap(),
+        Encoding::JsonString
+    );
+    assert!(serde_json_wasm::from_str::<Encoding>("\"protobuf\"").is_err());
+
+    let vars = vec![
+        encoded_var("addr", VariableKind::String, "abc", Encoding::Hex),
+        encoded_var(
+            "payload",
+            VariableKind::String,
+            "swap",
+            Encoding::Base64Json,
+        ),
+        encoded_var(
+            "denom",
+            VariableKind::Json,
+            "{\"a\":1}",
+            Encoding::JsonString,
+        ),
+        encoded_var("amount", VariableKind::Uint, "5", Encoding::None),
+    ];
+    let msgs = "[{\"generic\":{\"wasm\":{\"execute\":{\"contract_addr\":\"$warp.variable.addr\",\"msg\":\"$warp.variable.payload\",\"funds\":[{\"denom\":\"$warp.variable.denom\",\"amount\":\"$warp.variable.amount\"}]}}}}]";
+    assert_eq!(msgs_valid(msgs, &vars), Ok(true));
+
+    assert_eq!(
+        hydrate_msgs(msgs.to_string(), vars).unwrap(),
+        vec![WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "616263".to_string(),
+            msg: Binary::from("\"swap\"".as_bytes()),
+            funds: vec![Coin {
+                denom: "{\"a\":1}".to_string(),
+                amount: Uint128::new(5),
+            }],
+        }))]
+    );
+}
+
+#[test]
+fn test_hydrate_msgs_json_tree() {
+    let var = |name: &str, kind: VariableKind, value: &str| {
+        encoded_var(name, kind, value, Encoding::None)
+    };
+    let msgs = "[{\"generic\":{\"bank\":{\"send\":{\"to_address\":\"$warp.variable.to\",\"amount\":[{\"denom\":\"$warp.variable.amount_out\",\"amount\":\"$warp.variable.amount\"}]}}}}]";
+    let send = |to: &str, denom: &str, amount: u128| {
+        vec![WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin {
+                denom: denom.to_string(),
+                amount: Uint128::new(amount),
+            }],
+        }))]
+    };
+
+    // a name that is a prefix of another only replaces its own placeholder
+    assert_eq!(
+        hydrate_msgs(
+            msgs.to_string(),
+            vec![
+                var("amount", VariableKind::Uint, "5"),
+                var("amount_out", VariableKind::String, "uluna"),
+                var("to", VariableKind::String, "terra1"),
+            ]
+        )
+        .unwrap(),
+        send("terra1", "uluna", 5)
+    );
+
+    // string values stay inside their node, placeholders in values are not expanded
+    assert_eq!(
+        hydrate_msgs(
+            msgs.to_string(),
+            vec![
+                var("amount", VariableKind::Uint, "5"),
+                var("amount_out", VariableKind::String, "$warp.variable.to"),
+                var("to", VariableKind::String, "x\",\"amount\":[]}}}}]"),
+            ]
+        )
+        .unwrap(),
+        send("x\",\"amount\":[]}}}}]", "$warp.variable.to", 5)
+    );
+
+    // values that don't match the var kind are rejected
+    assert_eq!(
+        hydrate_msgs(
+            msgs.to_string(),
+            vec![
+                var("amount", VariableKind::Uint, "5\",\"denom\":\"uusd"),
+                var("amount_out", VariableKind::String, "uluna"),
+                var("to", VariableKind::String, "terra1"),
+            ]
+        ),
+        Err(ContractError::VariableError {
+            name: "amount".to_string(),
+            error: Box::new(ContractError::HydrationError {
+                msg: "Value \"5\\\",\\\"denom\\\":\\\"uusd\" is not of kind Uint.".to_string()
+            })
+        })
+    );
+
+    // placeholders must reference a var
+    assert_eq!(
+        hydrate_msgs(
+            msgs.to_string(),
+            vec![
+                var("amount", VariableKind::Uint, "5"),
+                var("to", VariableKind::String, "terra1"),
+            ]
+        ),
+        Err(ContractError::VariableNotFound {
+            name: "$warp.variable.amount_out".to_string()
+        })
+    );
+    assert!(msgs_valid(msgs, &[var("to", VariableKind::String, "terra1")]).is_err());
+
+    // a query result can't add structure at a string placeholder
+    let coin = Variable::Query(QueryVariable {
+        name: "coin".to_string(),
+        kind: VariableKind::Json,
+        init_fn: QueryExpr {
+            selector: "$.coin".to_string(),
+            query: QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: "contract_addr".to_string(),
+                msg: Binary::from(r#"{"coin":{}}"#.as_bytes()),
+            }),
+        },
+        value: Some(r#"{"denom":"uluna","amount":"5","to_address":"terra2"}"#.to_string()),
+        reinitialize: false,
+        update_fn: None,
+        encode: Encoding::None,
+    });
+    let coin_msgs = "[{\"generic\":{\"bank\":{\"send\":{\"to_address\":\"terra1\",\"amount\":[\"$warp.variable.coin\"]}}}}]";
+    assert_eq!(
+        hydrate_msgs(coin_msgs.to_string(), vec![coin]),
+        Err(ContractError::VariableError {
+            name: "coin".to_string(),
+            error: Box::new(ContractError::HydrationError {
+                msg: "Value of a string placeholder is not a string, use {\"$warp.json\": \"$warp.variable.coin\"} to insert JSON.".to_string()
+            })
+        })
+    );
+
+    // explicit Json placeholders insert the value as is
+    let coin_msgs = "[{\"generic\":{\"bank\":{\"send\":{\"to_address\":\"terra1\",\"amount\":[{\"$warp.json\":\"$warp.variable.coin\"}]}}}}]";
+    assert_eq!(
+        hydrate_msgs(
+            coin_msgs.to_string(),
+            vec![var(
+                "coin",
+                VariableKind::Json,
+                r#"{"denom":"uluna","amount":"5"}"#
+            )]
+        )
+        .unwrap(),
+        send("terra1", "uluna", 5)
+    );
+    assert!(msgs_valid(
+        "[{\"generic\":{\"bank\":{\"send\":{\"to_address\":\"$warp.variable.to\",\"amount\":[]}}}}]",
+        &[var("to", VariableKind::Json, "\"terra1\"")]
+    )
+    .unwrap());
+}
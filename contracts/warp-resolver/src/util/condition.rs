@@ -1,31 +1,62 @@
+use crate::util::json::to_value_string;
 use crate::util::path::resolve_path;
+use crate::util::series::resolve_series_value;
 use crate::util::variable::get_var;
 use crate::ContractError;
+use controller::account::AssetInfo;
 use cosmwasm_std::{
-    to_vec, ContractResult, Decimal256, Deps, Env, StdError, SystemResult, Uint256,
+    to_json_vec, ContractResult, Decimal256, Deps, Env, Int256, SignedDecimal256, StdError,
+    SystemResult, Uint128, Uint256,
 };
 use cw_storage_plus::KeyDeserialize;
 use json_codec_wasm::ast::Ref;
 use json_codec_wasm::Decoder;
+use regex_lite::Regex;
 use resolver::condition::{
     BlockExpr, Condition, DecimalFnOp, Expr, GenExpr, IntFnOp, NumEnvValue, NumExprOp,
-    NumExprValue, NumFnValue, NumOp, NumValue, StringEnvValue, StringOp, StringValue, TimeExpr,
-    TimeOp,
+    NumExprValue, NumFnValue, NumOp, NumValue, StringEnvValue, StringFnOp, StringFnValue,
+    StringFormatValue, StringOp, StringValue, TimeExpr,
 };
-use resolver::variable::{QueryExpr, Variable};
+use resolver::variable::{BalanceExpr, QueryExpr, Variable};
+use resolver::JobEnv;
 use std::str::FromStr;
 
+// values supplied by the controller alongside the job, used to resolve Env values
+// - both are optional, resolution fails only when a missing value is referenced
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WarpEnv {
+    pub warp_account_addr: Option<String>,
+    pub job: Option<JobEnv>,
+    pub skip_price_staleness: bool,
+}
+
+impl WarpEnv {
+    pub fn warp_account_addr(&self) -> Result<String, ContractError> {
+        self.warp_account_addr
+            .clone()
+            .ok_or(ContractError::HydrationError {
+                msg: "Warp account addr not found.".to_string(),
+            })
+    }
+
+    pub fn job(&self) -> Result<JobEnv, ContractError> {
+        self.job.clone().ok_or(ContractError::HydrationError {
+            msg: "Job env not found.".to_string(),
+        })
+    }
+}
+
 pub fn resolve_cond(
     deps: Deps,
     env: Env,
     cond: Condition,
     vars: &Vec<Variable>,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<bool, ContractError> {
     match cond {
         Condition::And(conds) => {
             for cond in conds {
-                if !resolve_cond(deps, env.clone(), *cond, vars, warp_account_addr.clone())? {
+                if !resolve_cond(deps, env.clone(), *cond, vars, warp_env)? {
                     return Ok(false);
                 }
             }
@@ -33,14 +64,14 @@ pub fn resolve_cond(
         }
         Condition::Or(conds) => {
             for cond in conds {
-                if resolve_cond(deps, env.clone(), *cond, vars, warp_account_addr.clone())? {
+                if resolve_cond(deps, env.clone(), *cond, vars, warp_env)? {
                     return Ok(true);
                 }
             }
             Ok(false)
         }
-        Condition::Not(cond) => Ok(!resolve_cond(deps, env, *cond, vars, warp_account_addr)?),
-        Condition::Expr(expr) => Ok(resolve_expr(deps, env, *expr, vars, warp_account_addr)?),
+        Condition::Not(cond) => Ok(!resolve_cond(deps, env, *cond, vars, warp_env)?),
+        Condition::Expr(expr) => Ok(resolve_expr(deps, env, *expr, vars, warp_env)?),
     }
 }
 
@@ -49,27 +80,30 @@ pub fn resolve_expr(
     env: Env,
     expr: Expr,
     vars: &Vec<Variable>,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<bool, ContractError> {
     match expr {
-        Expr::String(expr) => resolve_string_expr(deps, env, expr, vars, warp_account_addr),
-        Expr::Uint(expr) => resolve_uint_expr(deps, env, expr, vars),
-        Expr::Int(expr) => resolve_int_expr(deps, env, expr, vars),
-        Expr::Decimal(expr) => resolve_decimal_expr(deps, env, expr, vars),
+        Expr::String(expr) => resolve_string_expr(deps, env, expr, vars, warp_env),
+        Expr::Uint(expr) => resolve_uint_expr(deps, env, expr, vars, warp_env),
+        Expr::Int(expr) => resolve_int_expr(deps, env, expr, vars, warp_env),
+        Expr::Decimal(expr) => resolve_decimal_expr(deps, env, expr, vars, warp_env),
         Expr::Timestamp(expr) => resolve_timestamp_expr(deps, env, expr),
         Expr::BlockHeight(expr) => resolve_block_expr(deps, env, expr),
-        Expr::Bool(expr) => resolve_ref_bool(deps, env, expr, vars),
+        Expr::Bool(expr) => {
+            resolve_ref_bool(deps, env, expr.clone(), vars).map_err(|e| e.for_variable(&expr))
+        }
     }
 }
 
 pub fn resolve_int_expr(
     deps: Deps,
     env: Env,
-    expr: GenExpr<NumValue<i128, NumExprOp, IntFnOp>, NumOp>,
+    expr: GenExpr<NumValue<Int256, NumExprOp, IntFnOp>, NumOp>,
     vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
 ) -> Result<bool, ContractError> {
-    let left = resolve_num_value_int(deps, env.clone(), expr.left, vars)?;
-    let right = resolve_num_value_int(deps, env.clone(), expr.right, vars)?;
+    let left = resolve_num_value_int(deps, env.clone(), expr.left, vars, warp_env)?;
+    let right = resolve_num_value_int(deps, env.clone(), expr.right, vars, warp_env)?;
 
     Ok(resolve_int_op(deps, env, left, right, expr.op))
 }
@@ -77,17 +111,20 @@ pub fn resolve_int_expr(
 pub fn resolve_num_value_int(
     deps: Deps,
     env: Env,
-    value: NumValue<i128, NumExprOp, IntFnOp>,
+    value: NumValue<Int256, NumExprOp, IntFnOp>,
     vars: &Vec<Variable>,
-) -> Result<i128, ContractError> {
+    warp_env: &WarpEnv,
+) -> Result<Int256, ContractError> {
     match value {
         NumValue::Simple(value) => Ok(value),
-        NumValue::Expr(expr) => resolve_num_expr_int(deps, env, expr, vars),
-        NumValue::Ref(expr) => resolve_ref_int(deps, env, expr, vars),
-        NumValue::Fn(expr) => resolve_num_fn_int(deps, env, expr, vars),
-        NumValue::Env(_expr) => Err(ContractError::ConditionError {
-            msg: "Int resolve Env.".to_string(),
-        }),
+        NumValue::Expr(expr) => resolve_num_expr_int(deps, env, expr, vars, warp_env),
+        NumValue::Ref(expr) => {
+            resolve_ref_int(deps, env, expr.clone(), vars).map_err(|e| e.for_variable(&expr))
+        }
+        NumValue::Fn(expr) => resolve_num_fn_int(deps, env, expr, vars, warp_env),
+        NumValue::Env(expr) => resolve_num_env_int(deps, env, expr, vars, warp_env),
+        NumValue::Duration(duration) => Ok(Int256::from(duration.seconds())),
+        NumValue::Series(expr) => Ok(resolve_series_value(expr, vars)?.to_int_trunc()),
     }
 }
 
@@ -96,26 +133,38 @@ fn resolve_ref_int(
     _env: Env,
     r: String,
     vars: &Vec<Variable>,
-) -> Result<i128, ContractError> {
+) -> Result<Int256, ContractError> {
     let var = get_var(r, vars)?;
     let res = match var {
         Variable::Static(s) => {
             let val = s.clone().value.ok_or(ContractError::ConditionError {
                 msg: format!("Int Static value not found: {}", s.name),
             })?;
-            str::parse::<i128>(&val)?
+            Int256::from_str(&val)?
         }
         Variable::Query(q) => {
             let val = q.clone().value.ok_or(ContractError::ConditionError {
                 msg: format!("Int Query value not found: {}", q.name),
             })?;
-            str::parse::<i128>(&val)?
+            Int256::from_str(&val)?
         }
         Variable::External(e) => {
             let val = e.clone().value.ok_or(ContractError::ConditionError {
                 msg: format!("Int External value not found: {}", e.name),
             })?;
-            str::parse::<i128>(&val)?
+            Int256::from_str(&val)?
+        }
+        Variable::Balance(b) => {
+            let val = b.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Int Balance value not found: {}", b.name),
+            })?;
+            Int256::from_str(&val)?
+        }
+        Variable::Price(p) => {
+            let val = p.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Int Price value not found: {}", p.name),
+            })?;
+            Int256::from_str(&val)?
         }
     };
 
@@ -125,17 +174,18 @@ fn resolve_ref_int(
 fn resolve_num_fn_int(
     deps: Deps,
     env: Env,
-    expr: NumFnValue<i128, NumExprOp, IntFnOp>,
+    expr: NumFnValue<Int256, NumExprOp, IntFnOp>,
     vars: &Vec<Variable>,
-) -> Result<i128, ContractError> {
-    let right = resolve_num_value_int(deps, env, *expr.right, vars)?;
+    warp_env: &WarpEnv,
+) -> Result<Int256, ContractError> {
+    let right = resolve_num_value_int(deps, env, *expr.right, vars, warp_env)?;
 
     match expr.op {
-        IntFnOp::Abs => Ok(right.abs()),
-        IntFnOp::Neg => {
-            Ok(right
-                .checked_mul(i128::from(-1i64))
-                .ok_or(ContractError::ConditionError {
+        IntFnOp::Abs if !right.is_negative() => Ok(right),
+        IntFnOp::Abs | IntFnOp::Neg => {
+            Ok(Int256::zero()
+                .checked_sub(right)
+                .map_err(|_| ContractError::ConditionError {
                     msg: "Int negation error.".to_string(),
                 })?)
         }
@@ -145,38 +195,49 @@ fn resolve_num_fn_int(
 pub fn resolve_num_expr_int(
     deps: Deps,
     env: Env,
-    expr: NumExprValue<i128, NumExprOp, IntFnOp>,
+    expr: NumExprValue<Int256, NumExprOp, IntFnOp>,
     vars: &Vec<Variable>,
-) -> Result<i128, ContractError> {
-    let left = resolve_num_value_int(deps, env.clone(), *expr.left, vars)?;
-    let right = resolve_num_value_int(deps, env, *expr.right, vars)?;
+    warp_env: &WarpEnv,
+) -> Result<Int256, ContractError> {
+    let left = resolve_num_value_int(deps, env.clone(), *expr.left, vars, warp_env)?;
+    let right = resolve_num_value_int(deps, env, *expr.right, vars, warp_env)?;
 
     match expr.op {
-        NumExprOp::Sub => Ok(left
-            .checked_sub(right)
-            .ok_or(ContractError::ConditionError {
-                msg: "Int checked sub error.".to_string(),
-            })?),
-        NumExprOp::Add => Ok(left
-            .checked_add(right)
-            .ok_or(ContractError::ConditionError {
-                msg: "Int checked add error.".to_string(),
-            })?),
-        NumExprOp::Div => Ok(left
-            .checked_div(right)
-            .ok_or(ContractError::ConditionError {
-                msg: "Int checked div error.".to_string(),
-            })?),
-        NumExprOp::Mul => Ok(left
-            .checked_mul(right)
-            .ok_or(ContractError::ConditionError {
-                msg: "Int checked mul error.".to_string(),
-            })?),
-        NumExprOp::Mod => Ok(left
-            .checked_rem(right)
-            .ok_or(ContractError::ConditionError {
-                msg: "Int checked rem error.".to_string(),
-            })?),
+        NumExprOp::Sub => {
+            Ok(left
+                .checked_sub(right)
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Int checked sub error.".to_string(),
+                })?)
+        }
+        NumExprOp::Add => {
+            Ok(left
+                .checked_add(right)
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Int checked add error.".to_string(),
+                })?)
+        }
+        NumExprOp::Div => {
+            Ok(left
+                .checked_div(right)
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Int checked div error.".to_string(),
+                })?)
+        }
+        NumExprOp::Mul => {
+            Ok(left
+                .checked_mul(right)
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Int checked mul error.".to_string(),
+                })?)
+        }
+        NumExprOp::Mod => {
+            Ok(left
+                .checked_rem(right)
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Int checked rem error.".to_string(),
+                })?)
+        }
     }
 }
 
@@ -185,9 +246,10 @@ pub fn resolve_uint_expr(
     env: Env,
     expr: GenExpr<NumValue<Uint256, NumExprOp, IntFnOp>, NumOp>,
     vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
 ) -> Result<bool, ContractError> {
-    let left = resolve_num_value_uint(deps, env.clone(), expr.left, vars)?;
-    let right = resolve_num_value_uint(deps, env.clone(), expr.right, vars)?;
+    let left = resolve_num_value_uint(deps, env.clone(), expr.left, vars, warp_env)?;
+    let right = resolve_num_value_uint(deps, env.clone(), expr.right, vars, warp_env)?;
 
     Ok(resolve_uint_op(deps, env, left, right, expr.op))
 }
@@ -197,15 +259,26 @@ pub fn resolve_num_value_uint(
     env: Env,
     value: NumValue<Uint256, NumExprOp, IntFnOp>,
     vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
 ) -> Result<Uint256, ContractError> {
     match value {
         NumValue::Simple(value) => Ok(value),
-        NumValue::Expr(expr) => resolve_num_expr_uint(deps, env, expr, vars),
-        NumValue::Ref(expr) => resolve_ref_uint(deps, env, expr, vars),
+        NumValue::Expr(expr) => resolve_num_expr_uint(deps, env, expr, vars, warp_env),
+        NumValue::Ref(expr) => {
+            resolve_ref_uint(deps, env, expr.clone(), vars).map_err(|e| e.for_variable(&expr))
+        }
         NumValue::Fn(_) => Err(ContractError::ConditionError {
             msg: "Uint resolve Fn.".to_string(),
         }),
-        NumValue::Env(expr) => resolve_num_env_uint(deps, env, expr, vars),
+        NumValue::Env(expr) => resolve_num_env_uint(deps, env, expr, vars, warp_env),
+        NumValue::Duration(duration) => Ok(Uint256::from(duration.seconds())),
+        NumValue::Series(expr) => {
+            Uint256::try_from(resolve_series_value(expr, vars)?.to_int_trunc()).map_err(|_| {
+                ContractError::ConditionError {
+                    msg: "Uint Series value is negative.".to_string(),
+                }
+            })
+        }
     }
 }
 
@@ -235,6 +308,18 @@ fn resolve_ref_uint(
             })?;
             Uint256::from_str(&val)?
         }
+        Variable::Balance(b) => {
+            let val = b.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Uint Balance value not found: {}", b.name),
+            })?;
+            Uint256::from_str(&val)?
+        }
+        Variable::Price(p) => {
+            let val = p.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Uint Price value not found: {}", p.name),
+            })?;
+            Uint256::from_str(&val)?
+        }
     };
 
     Ok(res)
@@ -245,9 +330,10 @@ pub fn resolve_num_expr_uint(
     env: Env,
     expr: NumExprValue<Uint256, NumExprOp, IntFnOp>,
     vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
 ) -> Result<Uint256, ContractError> {
-    let left = resolve_num_value_uint(deps, env.clone(), *expr.left, vars)?;
-    let right = resolve_num_value_uint(deps, env, *expr.right, vars)?;
+    let left = resolve_num_value_uint(deps, env.clone(), *expr.left, vars, warp_env)?;
+    let right = resolve_num_value_uint(deps, env, *expr.right, vars, warp_env)?;
 
     match expr.op {
         NumExprOp::Sub => {
@@ -289,25 +375,66 @@ pub fn resolve_num_expr_uint(
 }
 
 pub fn resolve_num_env_uint(
-    _deps: Deps,
+    deps: Deps,
     env: Env,
     expr: NumEnvValue,
     _vars: &[Variable],
+    warp_env: &WarpEnv,
 ) -> Result<Uint256, ContractError> {
     match expr {
         NumEnvValue::Time => Ok(env.block.time.seconds().into()),
         NumEnvValue::BlockHeight => Ok(env.block.height.into()),
+        NumEnvValue::JobId => Ok(warp_env.job()?.job_id.u64().into()),
+        NumEnvValue::ExecutionCount => Ok(warp_env.job()?.execution_count.u64().into()),
+        NumEnvValue::AccountBalance(denom) => {
+            let balance = deps
+                .querier
+                .query_balance(warp_env.warp_account_addr()?, denom)?;
+            Ok(balance.amount.into())
+        }
     }
 }
 
+pub fn resolve_num_env_int(
+    deps: Deps,
+    env: Env,
+    expr: NumEnvValue,
+    vars: &[Variable],
+    warp_env: &WarpEnv,
+) -> Result<Int256, ContractError> {
+    let value = resolve_num_env_uint(deps, env, expr, vars, warp_env)?;
+
+    Int256::try_from(value).map_err(|_| ContractError::ConditionError {
+        msg: "Int resolve Env overflow.".to_string(),
+    })
+}
+
+pub fn resolve_num_env_decimal(
+    deps: Deps,
+    env: Env,
+    expr: NumEnvValue,
+    vars: &[Variable],
+    warp_env: &WarpEnv,
+) -> Result<SignedDecimal256, ContractError> {
+    let value = resolve_num_env_uint(deps, env, expr, vars, warp_env)?;
+
+    Int256::try_from(value)
+        .ok()
+        .and_then(|value| SignedDecimal256::checked_from_ratio(value, Int256::one()).ok())
+        .ok_or(ContractError::ConditionError {
+            msg: "Decimal resolve Env overflow.".to_string(),
+        })
+}
+
 pub fn resolve_decimal_expr(
     deps: Deps,
     env: Env,
-    expr: GenExpr<NumValue<Decimal256, NumExprOp, DecimalFnOp>, NumOp>,
+    expr: GenExpr<NumValue<SignedDecimal256, NumExprOp, DecimalFnOp>, NumOp>,
     vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
 ) -> Result<bool, ContractError> {
-    let left = resolve_num_value_decimal(deps, env.clone(), expr.left, vars)?;
-    let right = resolve_num_value_decimal(deps, env.clone(), expr.right, vars)?;
+    let left = resolve_num_value_decimal(deps, env.clone(), expr.left, vars, warp_env)?;
+    let right = resolve_num_value_decimal(deps, env.clone(), expr.right, vars, warp_env)?;
 
     Ok(resolve_decimal_op(deps, env, left, right, expr.op))
 }
@@ -315,17 +442,20 @@ pub fn resolve_decimal_expr(
 pub fn resolve_num_value_decimal(
     deps: Deps,
     env: Env,
-    value: NumValue<Decimal256, NumExprOp, DecimalFnOp>,
+    value: NumValue<SignedDecimal256, NumExprOp, DecimalFnOp>,
     vars: &Vec<Variable>,
-) -> Result<Decimal256, ContractError> {
+    warp_env: &WarpEnv,
+) -> Result<SignedDecimal256, ContractError> {
     match value {
         NumValue::Simple(value) => Ok(value),
-        NumValue::Expr(expr) => resolve_num_expr_decimal(deps, env, expr, vars),
-        NumValue::Ref(expr) => resolve_ref_decimal(deps, env, expr, vars),
-        NumValue::Fn(expr) => resolve_num_fn_decimal(deps, env, expr, vars),
-        NumValue::Env(_expr) => Err(ContractError::ConditionError {
-            msg: "Decimal resolve Env.".to_string(),
-        }),
+        NumValue::Expr(expr) => resolve_num_expr_decimal(deps, env, expr, vars, warp_env),
+        NumValue::Ref(expr) => {
+            resolve_ref_decimal(deps, env, expr.clone(), vars).map_err(|e| e.for_variable(&expr))
+        }
+        NumValue::Fn(expr) => resolve_num_fn_decimal(deps, env, expr, vars, warp_env),
+        NumValue::Env(expr) => resolve_num_env_decimal(deps, env, expr, vars, warp_env),
+        NumValue::Duration(duration) => Ok(SignedDecimal256::from_ratio(duration.seconds(), 1u64)),
+        NumValue::Series(expr) => resolve_series_value(expr, vars),
     }
 }
 
@@ -334,26 +464,38 @@ fn resolve_ref_decimal(
     _env: Env,
     r: String,
     vars: &Vec<Variable>,
-) -> Result<Decimal256, ContractError> {
+) -> Result<SignedDecimal256, ContractError> {
     let var = get_var(r, vars)?;
     let res = match var {
         Variable::Static(s) => {
             let val = s.clone().value.ok_or(ContractError::ConditionError {
                 msg: format!("Decimal Static value not found: {}", s.name),
             })?;
-            Decimal256::from_str(&val)?
+            SignedDecimal256::from_str(&val)?
         }
         Variable::Query(q) => {
             let val = q.clone().value.ok_or(ContractError::ConditionError {
                 msg: format!("Decimal Query value not found: {}", q.name),
             })?;
-            Decimal256::from_str(&val)?
+            SignedDecimal256::from_str(&val)?
         }
         Variable::External(e) => {
             let val = e.clone().value.ok_or(ContractError::ConditionError {
                 msg: format!("Decimal External value not found: {}", e.name),
             })?;
-            Decimal256::from_str(&val)?
+            SignedDecimal256::from_str(&val)?
+        }
+        Variable::Balance(b) => {
+            let val = b.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Decimal Balance value not found: {}", b.name),
+            })?;
+            SignedDecimal256::from_str(&val)?
+        }
+        Variable::Price(p) => {
+            let val = p.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Decimal Price value not found: {}", p.name),
+            })?;
+            SignedDecimal256::from_str(&val)?
         }
     };
 
@@ -363,30 +505,53 @@ fn resolve_ref_decimal(
 fn resolve_num_fn_decimal(
     deps: Deps,
     env: Env,
-    expr: NumFnValue<Decimal256, NumExprOp, DecimalFnOp>,
+    expr: NumFnValue<SignedDecimal256, NumExprOp, DecimalFnOp>,
     vars: &Vec<Variable>,
-) -> Result<Decimal256, ContractError> {
-    let right = resolve_num_value_decimal(deps, env, *expr.right, vars)?;
+    warp_env: &WarpEnv,
+) -> Result<SignedDecimal256, ContractError> {
+    let right = resolve_num_value_decimal(deps, env, *expr.right, vars, warp_env)?;
 
     match expr.op {
-        DecimalFnOp::Abs => Ok(right.abs_diff(Decimal256::zero())),
-        DecimalFnOp::Neg => {
-            Ok(right.checked_mul(Decimal256::zero().checked_sub(Decimal256::one())?)?)
+        DecimalFnOp::Abs if !right.is_negative() => Ok(right),
+        DecimalFnOp::Abs | DecimalFnOp::Neg => Ok(SignedDecimal256::zero().checked_sub(right)?),
+        DecimalFnOp::Floor => {
+            Ok(right
+                .checked_floor()
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Decimal floor error.".to_string(),
+                })?)
+        }
+        DecimalFnOp::Sqrt => {
+            let sqrt = Decimal256::try_from(right)
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Decimal sqrt of a negative value.".to_string(),
+                })?
+                .sqrt();
+            Ok(
+                SignedDecimal256::try_from(sqrt).map_err(|_| ContractError::ConditionError {
+                    msg: "Decimal sqrt error.".to_string(),
+                })?,
+            )
+        }
+        DecimalFnOp::Ceil => {
+            Ok(right
+                .checked_ceil()
+                .map_err(|_| ContractError::ConditionError {
+                    msg: "Decimal ceil error.".to_string(),
+                })?)
         }
-        DecimalFnOp::Floor => Ok(right.floor()),
-        DecimalFnOp::Sqrt => Ok(right.sqrt()),
-        DecimalFnOp::Ceil => Ok(right.ceil()),
     }
 }
 
 pub fn resolve_num_expr_decimal(
     deps: Deps,
     env: Env,
-    expr: NumExprValue<Decimal256, NumExprOp, DecimalFnOp>,
+    expr: NumExprValue<SignedDecimal256, NumExprOp, DecimalFnOp>,
     vars: &Vec<Variable>,
-) -> Result<Decimal256, ContractError> {
-    let left = resolve_num_value_decimal(deps, env.clone(), *expr.left, vars)?;
-    let right = resolve_num_value_decimal(deps, env, *expr.right, vars)?;
+    warp_env: &WarpEnv,
+) -> Result<SignedDecimal256, ContractError> {
+    let left = resolve_num_value_decimal(deps, env.clone(), *expr.left, vars, warp_env)?;
+    let right = resolve_num_value_decimal(deps, env, *expr.right, vars, warp_env)?;
 
     match expr.op {
         NumExprOp::Sub => {
@@ -433,8 +598,12 @@ pub fn resolve_timestamp_expr(
     expr: TimeExpr,
 ) -> Result<bool, ContractError> {
     let res = match expr.op {
-        TimeOp::Lt => env.block.time.seconds().lt(&expr.comparator.u64()),
-        TimeOp::Gt => env.block.time.seconds().gt(&expr.comparator.u64()),
+        NumOp::Eq => env.block.time.seconds().eq(&expr.comparator.u64()),
+        NumOp::Neq => env.block.time.seconds().ne(&expr.comparator.u64()),
+        NumOp::Lt => env.block.time.seconds().lt(&expr.comparator.u64()),
+        NumOp::Gt => env.block.time.seconds().gt(&expr.comparator.u64()),
+        NumOp::Gte => env.block.time.seconds().ge(&expr.comparator.u64()),
+        NumOp::Lte => env.block.time.seconds().le(&expr.comparator.u64()),
     };
 
     Ok(res)
@@ -464,7 +633,7 @@ pub fn resolve_uint_op(_deps: Deps, _env: Env, left: Uint256, right: Uint256, op
     }
 }
 
-pub fn resolve_int_op(_deps: Deps, _env: Env, left: i128, right: i128, op: NumOp) -> bool {
+pub fn resolve_int_op(_deps: Deps, _env: Env, left: Int256, right: Int256, op: NumOp) -> bool {
     match op {
         NumOp::Eq => left.eq(&right),
         NumOp::Neq => left.ne(&right),
@@ -478,8 +647,8 @@ pub fn resolve_int_op(_deps: Deps, _env: Env, left: i128, right: i128, op: NumOp
 pub fn resolve_decimal_op(
     _deps: Deps,
     _env: Env,
-    left: Decimal256,
-    right: Decimal256,
+    left: SignedDecimal256,
+    right: SignedDecimal256,
     op: NumOp,
 ) -> bool {
     match op {
@@ -497,19 +666,11 @@ pub fn resolve_string_expr(
     env: Env,
     expr: GenExpr<StringValue<String>, StringOp>,
     vars: &Vec<Variable>,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<bool, ContractError> {
-    let left = match expr.left {
-        StringValue::Simple(left) => left,
-        StringValue::Ref(left) => resolve_ref_string(deps, env.clone(), left, vars)?,
-        StringValue::Env(left) => resolve_string_value_env(deps, left, warp_account_addr.clone())?,
-    };
-    let right = match expr.right {
-        StringValue::Simple(right) => right,
-        StringValue::Ref(right) => resolve_ref_string(deps, env.clone(), right, vars)?,
-        StringValue::Env(right) => resolve_string_value_env(deps, right, warp_account_addr)?,
-    };
-    Ok(resolve_str_op(deps, env, left, right, expr.op))
+    let left = resolve_string_value(deps, env.clone(), expr.left, vars, warp_env)?;
+    let right = resolve_string_value(deps, env.clone(), expr.right, vars, warp_env)?;
+    resolve_str_op(deps, env, left, right, expr.op)
 }
 
 pub fn resolve_string_value(
@@ -517,28 +678,129 @@ pub fn resolve_string_value(
     env: Env,
     value: StringValue<String>,
     vars: &Vec<Variable>,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<String, ContractError> {
     match value {
         StringValue::Simple(value) => Ok(value),
-        StringValue::Ref(r) => resolve_ref_string(deps, env, r, vars),
-        StringValue::Env(value) => resolve_string_value_env(deps, value, warp_account_addr),
+        StringValue::Ref(r) => {
+            resolve_ref_string(deps, env, r.clone(), vars).map_err(|e| e.for_variable(&r))
+        }
+        StringValue::Env(value) => resolve_string_value_env(deps, env, value, warp_env),
+        StringValue::Concat(values) => {
+            let mut res = String::new();
+            for value in values {
+                res.push_str(&resolve_string_value(
+                    deps,
+                    env.clone(),
+                    value,
+                    vars,
+                    warp_env,
+                )?);
+            }
+            Ok(res)
+        }
+        StringValue::Substring(substring) => {
+            let value = resolve_string_value(deps, env, *substring.value, vars, warp_env)?;
+            let len = value.chars().count();
+            let start = substring.start as usize;
+            let end = substring.end.map(|end| end as usize).unwrap_or(len);
+            if start > end || end > len {
+                return Err(ContractError::ConditionError {
+                    msg: format!(
+                        "Substring out of bounds: start {}, end {}, len {}.",
+                        start, end, len
+                    ),
+                });
+            }
+            Ok(value.chars().skip(start).take(end - start).collect())
+        }
+        StringValue::Replace(replace) => {
+            let value = resolve_string_value(deps, env.clone(), *replace.value, vars, warp_env)?;
+            let from = resolve_string_value(deps, env.clone(), *replace.from, vars, warp_env)?;
+            let to = resolve_string_value(deps, env, *replace.to, vars, warp_env)?;
+            if from.is_empty() {
+                return Err(ContractError::ConditionError {
+                    msg: "Replace pattern cannot be empty.".to_string(),
+                });
+            }
+            Ok(value.replace(&from, &to))
+        }
+        StringValue::Fn(StringFnValue { op, right }) => {
+            let right = resolve_string_value(deps, env, *right, vars, warp_env)?;
+            Ok(match op {
+                StringFnOp::ToLower => right.to_lowercase(),
+                StringFnOp::ToUpper => right.to_uppercase(),
+                StringFnOp::Len => right.chars().count().to_string(),
+                StringFnOp::Bech32Prefix => bech32_prefix(&right)?,
+            })
+        }
+        StringValue::Format(value) => resolve_string_format_value(deps, env, value, vars, warp_env),
+    }
+}
+
+pub fn resolve_string_format_value(
+    deps: Deps,
+    env: Env,
+    value: StringFormatValue,
+    vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
+) -> Result<String, ContractError> {
+    match value {
+        StringFormatValue::Uint(value) => {
+            Ok(resolve_num_value_uint(deps, env, value, vars, warp_env)?.to_string())
+        }
+        StringFormatValue::Int(value) => {
+            Ok(resolve_num_value_int(deps, env, value, vars, warp_env)?.to_string())
+        }
+        StringFormatValue::Decimal { value, precision } => {
+            let value = resolve_num_value_decimal(deps, env, value, vars, warp_env)?.to_string();
+            match precision {
+                None => Ok(value),
+                Some(precision) => {
+                    if precision > SignedDecimal256::DECIMAL_PLACES {
+                        return Err(ContractError::ConditionError {
+                            msg: format!(
+                                "Decimal precision {} exceeds max of {}.",
+                                precision,
+                                SignedDecimal256::DECIMAL_PLACES
+                            ),
+                        });
+                    }
+                    let (int_part, frac_part) = value.split_once('.').unwrap_or((&value, ""));
+                    if precision == 0 {
+                        return Ok(int_part.to_string());
+                    }
+                    let frac_part: String = frac_part
+                        .chars()
+                        .chain(std::iter::repeat('0'))
+                        .take(precision as usize)
+                        .collect();
+                    Ok(format!("{}.{}", int_part, frac_part))
+                }
+            }
+        }
     }
 }
 
 pub fn resolve_string_value_env(
     deps: Deps,
+    env: Env,
     value: StringEnvValue,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<String, ContractError> {
     match value {
-        StringEnvValue::WarpAccountAddr => match warp_account_addr {
-            Some(addr) => {
-                deps.api.addr_validate(&addr)?;
-                Ok(addr)
-            }
+        StringEnvValue::WarpAccountAddr => {
+            let addr = warp_env.warp_account_addr()?;
+            deps.api.addr_validate(&addr)?;
+            Ok(addr)
+        }
+        StringEnvValue::ChainId => Ok(env.block.chain_id),
+        StringEnvValue::JobId => Ok(warp_env.job()?.job_id.to_string()),
+        StringEnvValue::JobOwner => Ok(warp_env.job()?.job_owner.to_string()),
+        StringEnvValue::FundingAccountAddr => match warp_env.job()?.funding_account_addr {
+            Some(addr) => Ok(addr.to_string()),
             None => Err(ContractError::HydrationError {
-                msg: "Warp account addr not found.".to_string(),
+                msg: "Funding account addr not found.".to_string(),
             }),
         },
     }
@@ -561,23 +823,56 @@ fn resolve_ref_string(
         Variable::External(e) => e.value.clone().ok_or(ContractError::ConditionError {
             msg: format!("String External value not found: {}", e.name),
         })?,
+        Variable::Balance(b) => b.value.clone().ok_or(ContractError::ConditionError {
+            msg: format!("String Balance value not found: {}", b.name),
+        })?,
+        Variable::Price(p) => p.value.clone().ok_or(ContractError::ConditionError {
+            msg: format!("String Price value not found: {}", p.name),
+        })?,
     };
 
     Ok(res)
 }
 
-pub fn resolve_str_op(_deps: Deps, _env: Env, left: String, right: String, op: StringOp) -> bool {
-    match op {
+pub fn resolve_str_op(
+    deps: Deps,
+    _env: Env,
+    left: String,
+    right: String,
+    op: StringOp,
+) -> Result<bool, ContractError> {
+    let res = match op {
         StringOp::StartsWith => left.starts_with(&right),
         StringOp::EndsWith => left.ends_with(&right),
         StringOp::Contains => left.contains(&right),
         StringOp::Eq => left.eq(&right),
         StringOp::Neq => left.ne(&right),
+        StringOp::Matches => Regex::new(&right)
+            .map_err(|e| ContractError::ConditionError {
+                msg: format!("Invalid regex pattern: {}", e),
+            })?
+            .is_match(&left),
+        StringOp::IsValidAddr => {
+            deps.api.addr_validate(&left).is_ok()
+                && (right.is_empty() || matches!(bech32_prefix(&left), Ok(p) if p == right))
+        }
+    };
+
+    Ok(res)
+}
+
+// human readable part of a bech32 string - everything before the last separator
+pub fn bech32_prefix(addr: &str) -> Result<String, ContractError> {
+    match addr.rfind('1') {
+        Some(idx) if idx > 0 => Ok(addr[..idx].to_string()),
+        _ => Err(ContractError::ConditionError {
+            msg: format!("Invalid bech32 address: {}", addr),
+        }),
     }
 }
 
 pub fn resolve_query_expr(deps: Deps, _env: Env, expr: QueryExpr) -> Result<String, ContractError> {
-    let raw = to_vec(&expr.query).map_err(|serialize_err| {
+    let raw = to_json_vec(&expr.query).map_err(|serialize_err| {
         StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
     })?;
 
@@ -598,6 +893,46 @@ pub fn resolve_query_expr(deps: Deps, _env: Env, expr: QueryExpr) -> Result<Stri
     Ok(query_result_str)
 }
 
+pub fn resolve_balance_expr(
+    deps: Deps,
+    env: Env,
+    expr: BalanceExpr,
+    vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
+) -> Result<Uint256, ContractError> {
+    let address = resolve_string_value(deps, env, expr.address, vars, warp_env)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let balance = match expr.asset {
+        AssetInfo::Native(denom) => deps.querier.query_balance(address, denom)?.amount,
+        AssetInfo::Cw20(contract_addr) => {
+            let res: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &cw20::Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )?;
+            res.balance
+        }
+        AssetInfo::Cw721(contract_addr, token_id) => {
+            let res: cw721::OwnerOfResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &cw721::Cw721QueryMsg::OwnerOf {
+                    token_id,
+                    include_expired: None,
+                },
+            )?;
+            if res.owner == address {
+                Uint128::one()
+            } else {
+                Uint128::zero()
+            }
+        }
+    };
+
+    Ok(balance.into())
+}
+
 pub fn resolve_query_expr_bool(
     deps: Deps,
     env: Env,
@@ -637,6 +972,18 @@ pub fn resolve_ref_bool(
             })?;
             str::parse::<bool>(&val)?
         }
+        Variable::Balance(b) => {
+            let val = b.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Bool Balance value not found: {}", b.name),
+            })?;
+            str::parse::<bool>(&val)?
+        }
+        Variable::Price(p) => {
+            let val = p.clone().value.ok_or(ContractError::ConditionError {
+                msg: format!("Bool Price value not found: {}", p.name),
+            })?;
+            str::parse::<bool>(&val)?
+        }
     };
     Ok(res)
 }
@@ -665,26 +1012,32 @@ pub fn resolve_query_expr_int(
     deps: Deps,
     env: Env,
     expr: QueryExpr,
-) -> Result<i128, ContractError> {
+) -> Result<Int256, ContractError> {
     let query_result_str = resolve_query_expr(deps, env, expr.clone())?;
     let value = Decoder::default(query_result_str.chars()).decode()?;
     let r = Ref::new(&value);
     let resolved = resolve_path(r, expr.selector)?;
 
-    resolved.i128().ok_or(ContractError::DecodeError {})
+    // values beyond i128 are returned as strings
+    match resolved.string() {
+        Some(val) => Ok(Int256::from_str(val)?),
+        None => Ok(Int256::from(
+            resolved.i128().ok_or(ContractError::DecodeError {})?,
+        )),
+    }
 }
 
 pub fn resolve_query_expr_decimal(
     deps: Deps,
     env: Env,
     expr: QueryExpr,
-) -> Result<Decimal256, ContractError> {
+) -> Result<SignedDecimal256, ContractError> {
     let query_result_str = resolve_query_expr(deps, env, expr.clone())?;
     let value = Decoder::default(query_result_str.chars()).decode()?;
     let r = Ref::new(&value);
     let resolved = resolve_path(r, expr.selector)?;
 
-    Ok(Decimal256::from_str(
+    Ok(SignedDecimal256::from_str(
         resolved.string().ok_or(ContractError::DecodeError {})?,
     )?)
 }
@@ -701,60 +1054,5 @@ pub fn resolve_query_expr_string(
 
     let v = resolved.value().ok_or(ContractError::DecodeError {})?;
 
-    let json = Json { value: v.clone() };
-
-    Ok(json.to_string())
-}
-
-pub struct Json {
-    value: json_codec_wasm::Json,
-}
-
-impl Json {
-    fn to_json_string(&self, quote_strings: bool) -> String {
-        match &self.value {
-            json_codec_wasm::Json::Bool(b) => b.to_string(),
-            json_codec_wasm::Json::I128(i) => i.to_string(),
-            json_codec_wasm::Json::U128(u) => u.to_string(),
-            json_codec_wasm::Json::String(s) => {
-                if quote_strings {
-                    format!("\"{}\"", s)
-                } else {
-                    s.to_string()
-                }
-            }
-            json_codec_wasm::Json::Array(arr) => {
-                let items: Vec<String> = arr
-                    .iter()
-                    .map(|item| {
-                        Json {
-                            value: item.clone(),
-                        }
-                        .to_json_string(true)
-                    })
-                    .collect();
-                format!("[{}]", items.join(", "))
-            }
-            json_codec_wasm::Json::Object(obj) => {
-                let items: Vec<String> = obj
-                    .iter()
-                    .map(|(k, v)| {
-                        format!(
-                            "\"{}\":{}",
-                            k,
-                            Json { value: v.clone() }.to_json_string(true)
-                        )
-                    })
-                    .collect();
-                format!("{{{}}}", items.join(","))
-            }
-            json_codec_wasm::Json::Null => "null".to_string(),
-        }
-    }
-}
-
-impl ToString for Json {
-    fn to_string(&self) -> String {
-        self.to_json_string(false)
-    }
+    Ok(to_value_string(v))
 }
@@ -0,0 +1,209 @@
+use crate::util::condition::{
+    resolve_num_value_decimal, resolve_num_value_int, resolve_num_value_uint, resolve_ref_bool,
+    resolve_string_value, WarpEnv,
+};
+use crate::util::variable::get_var;
+use crate::ContractError;
+use cosmwasm_std::{Deps, Env, Int128, Int256};
+use json_codec_wasm::{Decoder, Json};
+use resolver::variable::{FnValue, JsonValue, Variable, VariableKind};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub fn resolve_json_value(
+    deps: Deps,
+    env: Env,
+    value: JsonValue,
+    vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
+) -> Result<Json, ContractError> {
+    match value {
+        JsonValue::Simple(value) => parse_json(&value),
+        JsonValue::Ref(r) => resolve_ref_json(r.clone(), vars).map_err(|e| e.for_variable(&r)),
+        JsonValue::Object(entries) => {
+            let mut obj = HashMap::new();
+            for (key, value) in entries {
+                let value = resolve_fn_value_json(deps, env.clone(), value, vars, warp_env)?;
+                obj.insert(key, value);
+            }
+            Ok(Json::Object(obj))
+        }
+        JsonValue::Array(items) => {
+            let mut arr = vec![];
+            for item in items {
+                arr.push(resolve_fn_value_json(
+                    deps,
+                    env.clone(),
+                    item,
+                    vars,
+                    warp_env,
+                )?);
+            }
+            Ok(Json::Array(arr))
+        }
+        JsonValue::Merge(values) => {
+            let mut merged = Json::Null;
+            for (idx, value) in values.into_iter().enumerate() {
+                let patch = resolve_json_value(deps, env.clone(), value, vars, warp_env)?;
+                merged = if idx == 0 {
+                    patch
+                } else {
+                    merge_patch(merged, patch)
+                };
+            }
+            Ok(merged)
+        }
+    }
+}
+
+pub fn resolve_fn_value_json(
+    deps: Deps,
+    env: Env,
+    value: FnValue,
+    vars: &Vec<Variable>,
+    warp_env: &WarpEnv,
+) -> Result<Json, ContractError> {
+    let res = match value {
+        FnValue::Uint(nv) => {
+            Json::String(resolve_num_value_uint(deps, env, nv, vars, warp_env)?.to_string())
+        }
+        FnValue::Int(nv) => int_to_json(resolve_num_value_int(deps, env, nv, vars, warp_env)?),
+        FnValue::Decimal(nv) => {
+            Json::String(resolve_num_value_decimal(deps, env, nv, vars, warp_env)?.to_string())
+        }
+        FnValue::Timestamp(nv) => {
+            int_to_json(resolve_num_value_int(deps, env, nv, vars, warp_env)?)
+        }
+        FnValue::BlockHeight(nv) => {
+            int_to_json(resolve_num_value_int(deps, env, nv, vars, warp_env)?)
+        }
+        FnValue::Bool(val) => Json::Bool(resolve_ref_bool(deps, env, val, vars)?),
+        FnValue::String(val) => Json::String(resolve_string_value(deps, env, val, vars, warp_env)?),
+        FnValue::Json(val) => resolve_json_value(deps, env, val, vars, warp_env)?,
+    };
+
+    Ok(res)
+}
+
+// referenced values are converted according to the kind of the referenced variable,
+// Json variables are embedded as documents rather than as strings
+fn resolve_ref_json(r: String, vars: &Vec<Variable>) -> Result<Json, ContractError> {
+    let var = get_var(r, vars)?;
+    let (name, kind, value) = match var {
+        Variable::Static(v) => (&v.name, &v.kind, &v.value),
+        Variable::External(v) => (&v.name, &v.kind, &v.value),
+        Variable::Query(v) => (&v.name, &v.kind, &v.value),
+        Variable::Balance(v) => (&v.name, &v.kind, &v.value),
+        Variable::Price(v) => (&v.name, &v.kind, &v.value),
+    };
+    let val = value.clone().ok_or(ContractError::HydrationError {
+        msg: format!("Json Ref value not found: {}", name),
+    })?;
+
+    let res = match kind {
+        VariableKind::String
+        | VariableKind::Uint
+        | VariableKind::Decimal
+        | VariableKind::Amount
+        | VariableKind::Asset => Json::String(val),
+        VariableKind::Int | VariableKind::Timestamp | VariableKind::BlockHeight => {
+            int_to_json(Int256::from_str(&val)?)
+        }
+        VariableKind::Bool => Json::Bool(str::parse::<bool>(&val)?),
+        // query variables store top level strings unquoted
+        VariableKind::Json => parse_json(&val).unwrap_or(Json::String(val)),
+        VariableKind::Series { .. } => parse_json(&val)?,
+    };
+
+    Ok(res)
+}
+
+// ints that don't fit a JSON number are written as strings
+pub fn int_to_json(value: Int256) -> Json {
+    match Int128::try_from(value) {
+        Ok(value) => Json::I128(value.i128()),
+        Err(_) => Json::String(value.to_string()),
+    }
+}
+
+pub fn parse_json(value: &str) -> Result<Json, ContractError> {
+    Ok(Decoder::default(value.chars()).decode()?)
+}
+
+// JSON merge patch (RFC 7396)
+// - objects are merged recursively, null members remove the key from the target
+// - any other patch value replaces the target
+pub fn merge_patch(target: Json, patch: Json) -> Json {
+    match patch {
+        Json::Object(patch) => {
+            let mut target = match target {
+                Json::Object(target) => target,
+                _ => HashMap::new(),
+            };
+            for (key, value) in patch {
+                if value == Json::Null {
+                    target.remove(&key);
+                } else {
+                    let current = target.remove(&key).unwrap_or(Json::Null);
+                    target.insert(key, merge_patch(current, value));
+                }
+            }
+            Json::Object(target)
+        }
+        patch => patch,
+    }
+}
+
+// Compact JSON encoding (RFC 8259)
+// - objects are hash maps without insertion order, keys are sorted to keep the output deterministic
+// - composed objects are therefore written in key order rather than in the order they were declared
+pub fn to_json_string(value: &Json) -> String {
+    match value {
+        Json::Bool(b) => b.to_string(),
+        Json::I128(i) => i.to_string(),
+        Json::U128(u) => u.to_string(),
+        Json::String(s) => escape_json_string(s),
+        Json::Array(arr) => {
+            let items: Vec<String> = arr.iter().map(to_json_string).collect();
+            format!("[{}]", items.join(","))
+        }
+        Json::Object(obj) => {
+            let mut keys: Vec<&String> = obj.keys().collect();
+            keys.sort();
+            let items: Vec<String> = keys
+                .into_iter()
+                .map(|k| format!("{}:{}", escape_json_string(k), to_json_string(&obj[k])))
+                .collect();
+            format!("{{{}}}", items.join(","))
+        }
+        Json::Null => "null".to_string(),
+    }
+}
+
+// Variable value representation - top level strings are stored unquoted
+pub fn to_value_string(value: &Json) -> String {
+    match value {
+        Json::String(s) => s.to_string(),
+        value => to_json_string(value),
+    }
+}
+
+pub fn escape_json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{08}' => escaped.push_str("\\b"),
+            '\u{0c}' => escaped.push_str("\\f"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,491 @@
+use crate::util::condition::{resolve_cond, WarpEnv};
+use crate::util::variable::{
+    get_var_dependencies, get_var_kind, get_var_name, referenced_vars, update_fn_kind_valid,
+};
+use controller::job::Execution;
+use cosmwasm_std::{Deps, Env};
+use resolver::condition::{
+    BlockExpr, Condition, Expr, NumOp, NumValue, StringFormatValue, StringValue, TimeExpr,
+};
+use resolver::variable::{FnValue, JsonValue, Variable, VariableKind};
+use resolver::{LintIssue, LintKind, LintSeverity, QueryLintJobMsg};
+
+const UINT_KINDS: &[VariableKind] = &[
+    VariableKind::Uint,
+    VariableKind::Amount,
+    VariableKind::Timestamp,
+    VariableKind::BlockHeight,
+];
+const INT_KINDS: &[VariableKind] = &[
+    VariableKind::Int,
+    VariableKind::Timestamp,
+    VariableKind::BlockHeight,
+];
+const DECIMAL_KINDS: &[VariableKind] = &[VariableKind::Decimal];
+const BOOL_KINDS: &[VariableKind] = &[VariableKind::Bool];
+
+struct Linter<'a> {
+    deps: Deps<'a>,
+    env: Env,
+    vars: Vec<Variable>,
+    issues: Vec<LintIssue>,
+    // set when the condition being walked depends on vars or env values
+    dynamic: bool,
+}
+
+pub fn lint_job(deps: Deps, env: Env, data: QueryLintJobMsg) -> Vec<LintIssue> {
+    let mut linter = Linter {
+        deps,
+        env,
+        vars: vec![],
+        issues: vec![],
+        dynamic: false,
+    };
+
+    match serde_json_wasm::from_str::<Vec<Variable>>(&data.vars) {
+        Ok(vars) => linter.vars = vars,
+        Err(e) => {
+            linter.error(
+                LintKind::Invalid,
+                "vars",
+                None,
+                format!("Vars input invalid: {}", e),
+            );
+            return linter.issues;
+        }
+    }
+
+    for (idx, var) in linter.vars.clone().iter().enumerate() {
+        linter.lint_var(idx, var);
+    }
+
+    let mut always_true: Option<usize> = None;
+    for (idx, execution) in data.executions.iter().enumerate() {
+        let path = format!("executions[{}].condition", idx);
+        if let Some(matched) = always_true {
+            linter.warning(
+                LintKind::UnreachableExecution,
+                &path,
+                None,
+                format!(
+                    "Execution {} is always true, this execution is never reached.",
+                    matched
+                ),
+            );
+        }
+
+        match linter.lint_condition(&path, &execution.condition) {
+            Some(true) => {
+                always_true.get_or_insert(idx);
+            }
+            Some(false) => linter.warning(
+                LintKind::NeverTrueCondition,
+                &path,
+                None,
+                "Condition can never become true.".to_string(),
+            ),
+            None => {}
+        }
+    }
+
+    if let Some(terminate_condition) = data.terminate_condition.clone() {
+        linter.lint_condition("terminate_condition", &terminate_condition);
+    }
+
+    linter.lint_unused_vars(&data.executions, &data.terminate_condition);
+
+    linter.issues
+}
+
+impl<'a> Linter<'a> {
+    fn issue(
+        &mut self,
+        severity: LintSeverity,
+        kind: LintKind,
+        path: &str,
+        variable: Option<String>,
+        message: String,
+    ) {
+        self.issues.push(LintIssue {
+            severity,
+            kind,
+            path: path.to_string(),
+            variable,
+            message,
+        })
+    }
+
+    fn error(&mut self, kind: LintKind, path: &str, variable: Option<String>, message: String) {
+        self.issue(LintSeverity::Error, kind, path, variable, message)
+    }
+
+    fn warning(&mut self, kind: LintKind, path: &str, variable: Option<String>, message: String) {
+        self.issue(LintSeverity::Warning, kind, path, variable, message)
+    }
+
+    fn lint_var(&mut self, idx: usize, var: &Variable) {
+        let name = get_var_name(var);
+        let (kind, update_fn) = match var {
+            Variable::Static(v) => {
+                let path = format!("vars[{}].init_fn", idx);
+                if !init_fn_kind_valid(&v.init_fn, &v.kind) {
+                    self.error(
+                        LintKind::InitFnKindMismatch,
+                        &path,
+                        Some(name.clone()),
+                        format!("init_fn does not match variable kind {:?}.", v.kind),
+                    );
+                }
+                self.lint_fn_value(&path, &v.init_fn);
+                (&v.kind, &v.update_fn)
+            }
+            Variable::External(v) => (&v.kind, &v.update_fn),
+            Variable::Query(v) => (&v.kind, &v.update_fn),
+            Variable::Balance(v) => (&v.kind, &v.update_fn),
+            Variable::Price(v) => (&v.kind, &v.update_fn),
+        };
+
+        if let Some(update_fn) = update_fn {
+            for (field, value) in [
+                ("on_success", &update_fn.on_success),
+                ("on_error", &update_fn.on_error),
+            ] {
+                if let Some(value) = value {
+                    let path = format!("vars[{}].update_fn.{}", idx, field);
+                    if !update_fn_kind_valid(value, kind) {
+                        self.error(
+                            LintKind::UpdateFnKindMismatch,
+                            &path,
+                            Some(name.clone()),
+                            format!("update_fn does not match variable kind {:?}.", kind),
+                        );
+                    }
+                    self.lint_fn_value(&path, value);
+                }
+            }
+        }
+    }
+
+    // vars referenced by neither conditions, msgs nor other vars
+    fn lint_unused_vars(&mut self, executions: &[Execution], terminate_condition: &Option<String>) {
+        let mut used = vec![false; self.vars.len()];
+        let mut texts: Vec<&str> = vec![];
+        for execution in executions {
+            texts.push(&execution.condition);
+            texts.push(&execution.msgs);
+        }
+        if let Some(terminate_condition) = terminate_condition {
+            texts.push(terminate_condition);
+        }
+        for text in texts {
+            for idx in referenced_vars(text, &self.vars) {
+                used[idx] = true;
+            }
+        }
+
+        for (idx, var) in self.vars.iter().enumerate() {
+            let mut deps = get_var_dependencies(var, &self.vars).unwrap_or_default();
+            if let Some(update_fn) = var_update_fn(var) {
+                deps.extend(referenced_vars(
+                    &serde_json_wasm::to_string(update_fn).unwrap_or_default(),
+                    &self.vars,
+                ));
+            }
+            for dep in deps {
+                if dep != idx {
+                    used[dep] = true;
+                }
+            }
+        }
+
+        for (idx, var) in self.vars.clone().iter().enumerate() {
+            if !used[idx] {
+                self.warning(
+                    LintKind::UnusedVariable,
+                    &format!("vars[{}]", idx),
+                    Some(get_var_name(var)),
+                    "Variable is never referenced.".to_string(),
+                );
+            }
+        }
+    }
+
+    // returns the value of the condition if it is known ahead of execution
+    fn lint_condition(&mut self, path: &str, condition: &str) -> Option<bool> {
+        match serde_json_wasm::from_str::<Condition>(condition) {
+            Ok(condition) => self.lint_cond(path, &condition),
+            Err(e) => {
+                self.error(
+                    LintKind::Invalid,
+                    path,
+                    None,
+                    format!("Condition input invalid: {}", e),
+                );
+                None
+            }
+        }
+    }
+
+    fn lint_cond(&mut self, path: &str, condition: &Condition) -> Option<bool> {
+        match condition {
+            Condition::And(conds) => {
+                let values: Vec<Option<bool>> =
+                    conds.iter().map(|c| self.lint_cond(path, c)).collect();
+                if values.contains(&Some(false)) {
+                    Some(false)
+                } else if values.iter().all(|v| *v == Some(true)) {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            Condition::Or(conds) => {
+                let values: Vec<Option<bool>> =
+                    conds.iter().map(|c| self.lint_cond(path, c)).collect();
+                if values.contains(&Some(true)) {
+                    Some(true)
+                } else if values.iter().all(|v| *v == Some(false)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Condition::Not(cond) => self.lint_cond(path, cond).map(|v| !v),
+            Condition::Expr(expr) => self.lint_expr(path, expr),
+        }
+    }
+
+    fn lint_expr(&mut self, path: &str, expr: &Expr) -> Option<bool> {
+        self.dynamic = false;
+        match expr {
+            Expr::String(e) => {
+                self.lint_string_value(path, &e.left);
+                self.lint_string_value(path, &e.right);
+            }
+            Expr::Uint(e) => {
+                self.lint_num_value(path, &e.left, UINT_KINDS);
+                self.lint_num_value(path, &e.right, UINT_KINDS);
+            }
+            Expr::Int(e) => {
+                self.lint_num_value(path, &e.left, INT_KINDS);
+                self.lint_num_value(path, &e.right, INT_KINDS);
+            }
+            Expr::Decimal(e) => {
+                self.lint_num_value(path, &e.left, DECIMAL_KINDS);
+                self.lint_num_value(path, &e.right, DECIMAL_KINDS);
+            }
+            Expr::Timestamp(e) => return self.time_expr_value(e),
+            Expr::BlockHeight(e) => return self.block_expr_value(e),
+            Expr::Bool(r) => self.lint_ref(path, r, Some(BOOL_KINDS)),
+        }
+
+        if self.dynamic {
+            return None;
+        }
+
+        // constant expressions are evaluated right away
+        let condition = Condition::Expr(Box::new(expr.clone()));
+        match resolve_cond(
+            self.deps,
+            self.env.clone(),
+            condition,
+            &vec![],
+            &WarpEnv::default(),
+        ) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error(LintKind::Invalid, path, None, e.to_string());
+                None
+            }
+        }
+    }
+
+    // block time and height only increase, comparisons with past values are fixed from now on
+    fn time_expr_value(&self, expr: &TimeExpr) -> Option<bool> {
+        past_comparison_value(
+            &expr.op,
+            expr.comparator.u64(),
+            self.env.block.time.seconds(),
+        )
+    }
+
+    fn block_expr_value(&self, expr: &BlockExpr) -> Option<bool> {
+        past_comparison_value(&expr.op, expr.comparator.u64(), self.env.block.height)
+    }
+
+    // expected is None when any kind can be referenced
+    fn lint_ref(&mut self, path: &str, r: &str, expected: Option<&[VariableKind]>) {
+        self.dynamic = true;
+        let name = r.trim_start_matches("$warp.variable.");
+        let var = self.vars.iter().find(|v| get_var_name(v) == name).cloned();
+        match var {
+            None => self.error(
+                LintKind::Invalid,
+                path,
+                Some(name.to_string()),
+                format!("Variable not found: {}", r),
+            ),
+            Some(var) => {
+                let kind = get_var_kind(&var);
+                if let Some(expected) = expected {
+                    if !expected.contains(kind) {
+                        self.error(
+                            LintKind::RefKindMismatch,
+                            path,
+                            Some(name.to_string()),
+                            format!(
+                                "Variable of kind {:?} referenced where {:?} is expected.",
+                                kind, expected
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn lint_num_value<T, ExprOp, FnOp>(
+        &mut self,
+        path: &str,
+        value: &NumValue<T, ExprOp, FnOp>,
+        expected: &[VariableKind],
+    ) {
+        match value {
+            NumValue::Simple(_) => {}
+            NumValue::Expr(e) => {
+                self.lint_num_value(path, &e.left, expected);
+                self.lint_num_value(path, &e.right, expected);
+            }
+            NumValue::Ref(r) => self.lint_ref(path, r, Some(expected)),
+            NumValue::Fn(f) => self.lint_num_value(path, &f.right, expected),
+            NumValue::Env(_) => self.dynamic = true,
+            NumValue::Duration(_) => {}
+            NumValue::Series(s) => self.lint_series_ref(path, &s.series),
+        }
+    }
+
+    fn lint_series_ref(&mut self, path: &str, r: &str) {
+        self.lint_ref(path, r, None);
+        let name = r.trim_start_matches("$warp.variable.");
+        if let Some(var) = self.vars.iter().find(|v| get_var_name(v) == name) {
+            let kind = get_var_kind(var);
+            if !matches!(kind, VariableKind::Series { .. }) {
+                self.error(
+                    LintKind::RefKindMismatch,
+                    path,
+                    Some(name.to_string()),
+                    format!(
+                        "Variable of kind {:?} referenced where Series is expected.",
+                        kind
+                    ),
+                );
+            }
+        }
+    }
+
+    fn lint_string_value<T>(&mut self, path: &str, value: &StringValue<T>) {
+        match value {
+            StringValue::Simple(_) => {}
+            StringValue::Ref(r) => self.lint_ref(path, r, None),
+            StringValue::Env(_) => self.dynamic = true,
+            StringValue::Concat(values) => {
+                for value in values {
+                    self.lint_string_value(path, value);
+                }
+            }
+            StringValue::Substring(v) => self.lint_string_value(path, &v.value),
+            StringValue::Replace(v) => {
+                self.lint_string_value(path, &v.value);
+                self.lint_string_value(path, &v.from);
+                self.lint_string_value(path, &v.to);
+            }
+            StringValue::Fn(v) => self.lint_string_value(path, &v.right),
+            StringValue::Format(StringFormatValue::Uint(v)) => {
+                self.lint_num_value(path, v, UINT_KINDS)
+            }
+            StringValue::Format(StringFormatValue::Int(v)) => {
+                self.lint_num_value(path, v, INT_KINDS)
+            }
+            StringValue::Format(StringFormatValue::Decimal { value, .. }) => {
+                self.lint_num_value(path, value, DECIMAL_KINDS)
+            }
+        }
+    }
+
+    fn lint_fn_value(&mut self, path: &str, value: &FnValue) {
+        match value {
+            FnValue::Uint(v) => self.lint_num_value(path, v, UINT_KINDS),
+            FnValue::Int(v) | FnValue::Timestamp(v) | FnValue::BlockHeight(v) => {
+                self.lint_num_value(path, v, INT_KINDS)
+            }
+            FnValue::Decimal(v) => self.lint_num_value(path, v, DECIMAL_KINDS),
+            FnValue::Bool(r) => self.lint_ref(path, r, Some(BOOL_KINDS)),
+            FnValue::String(v) => self.lint_string_value(path, v),
+            FnValue::Json(v) => self.lint_json_value(path, v),
+        }
+    }
+
+    fn lint_json_value(&mut self, path: &str, value: &JsonValue) {
+        match value {
+            JsonValue::Simple(_) => {}
+            JsonValue::Ref(r) => self.lint_ref(path, r, None),
+            JsonValue::Object(entries) => {
+                for (_, value) in entries {
+                    self.lint_fn_value(path, value);
+                }
+            }
+            JsonValue::Array(values) => {
+                for value in values {
+                    self.lint_fn_value(path, value);
+                }
+            }
+            JsonValue::Merge(values) => {
+                for value in values {
+                    self.lint_json_value(path, value);
+                }
+            }
+        }
+    }
+}
+
+// value of `current op comparator` for all current values from now on, if it cannot change
+fn past_comparison_value(op: &NumOp, comparator: u64, current: u64) -> Option<bool> {
+    match op {
+        NumOp::Lt if comparator <= current => Some(false),
+        NumOp::Lte | NumOp::Eq if comparator < current => Some(false),
+        NumOp::Gt | NumOp::Neq if comparator < current => Some(true),
+        NumOp::Gte if comparator <= current => Some(true),
+        _ => None,
+    }
+}
+
+// init_fn values accepted for each kind by hydrate_vars
+fn init_fn_kind_valid(value: &FnValue, kind: &VariableKind) -> bool {
+    matches!(
+        (value, kind),
+        (FnValue::Uint(_), VariableKind::Uint | VariableKind::Amount)
+            | (FnValue::Int(_), VariableKind::Int)
+            | (
+                FnValue::Decimal(_),
+                VariableKind::Decimal | VariableKind::Series { .. }
+            )
+            | (FnValue::Timestamp(_), VariableKind::Timestamp)
+            | (FnValue::BlockHeight(_), VariableKind::BlockHeight)
+            | (FnValue::Bool(_), VariableKind::Bool)
+            | (
+                FnValue::String(_),
+                VariableKind::String | VariableKind::Asset | VariableKind::Json
+            )
+            | (FnValue::Json(_), VariableKind::Json)
+    )
+}
+
+fn var_update_fn(var: &Variable) -> Option<&resolver::variable::UpdateFn> {
+    match var {
+        Variable::Static(v) => v.update_fn.as_ref(),
+        Variable::External(v) => v.update_fn.as_ref(),
+        Variable::Query(v) => v.update_fn.as_ref(),
+        Variable::Balance(v) => v.update_fn.as_ref(),
+        Variable::Price(v) => v.update_fn.as_ref(),
+    }
+}
@@ -1,3 +1,7 @@
 pub mod condition;
+pub mod json;
+pub mod lint;
 pub mod path;
+pub mod price;
+pub mod series;
 pub mod variable;
@@ -0,0 +1,201 @@
+use crate::ContractError;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::Deserialize;
+use cosmwasm_std::{Decimal256, Deps, Env, Uint256};
+use resolver::variable::{
+    CumulativePricesResponse, PoolOracleQueryMsg, PriceExpr, PriceQueryMsg, PriceResponse,
+    PriceSource,
+};
+use std::str::FromStr;
+
+// Pyth (pyth-sdk-cw) price feed query
+#[cw_serde]
+enum PythQueryMsg {
+    PriceFeed { id: String },
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+struct PythPriceFeedResponse {
+    price_feed: PythPriceFeed,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+struct PythPriceFeed {
+    price: PythPrice,
+}
+
+// price and conf are string encoded, the actual value is price * 10^expo
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+struct PythPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    publish_time: i64,
+}
+
+// Band standard reference query
+#[cw_serde]
+enum BandQueryMsg {
+    GetReferenceData {
+        base_symbol: String,
+        quote_symbol: String,
+    },
+}
+
+// rate is scaled by 1e18
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+struct BandReferenceData {
+    rate: Uint256,
+    last_updated_base: u64,
+    last_updated_quote: u64,
+}
+
+pub fn resolve_price_expr(
+    deps: Deps,
+    env: Env,
+    expr: PriceExpr,
+    skip_staleness: bool,
+) -> Result<Decimal256, ContractError> {
+    let (price, confidence, publish_time) = match expr.source {
+        PriceSource::Pyth { contract_addr } => {
+            let res: PythPriceFeedResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &PythQueryMsg::PriceFeed {
+                    id: expr.base.clone(),
+                },
+            )?;
+            let price = i64::from_str(&res.price_feed.price.price)?;
+            if price <= 0 {
+                return Err(ContractError::HydrationError {
+                    msg: format!("Price is not positive: {}", price),
+                });
+            }
+            let conf = u64::from_str(&res.price_feed.price.conf)?;
+            let expo = res.price_feed.price.expo;
+            (
+                scale_pyth_value(price as u128, expo)?,
+                Some(scale_pyth_value(conf as u128, expo)?),
+                res.price_feed.price.publish_time.max(0) as u64,
+            )
+        }
+        PriceSource::Band { contract_addr } => {
+            let res: BandReferenceData = deps.querier.query_wasm_smart(
+                contract_addr,
+                &BandQueryMsg::GetReferenceData {
+                    base_symbol: expr.base.clone(),
+                    quote_symbol: expr.quote.clone(),
+                },
+            )?;
+            let price = Decimal256::from_atomics(res.rate, 18).map_err(|_| {
+                ContractError::HydrationError {
+                    msg: "Band rate out of range.".to_string(),
+                }
+            })?;
+            (
+                price,
+                None,
+                res.last_updated_base.min(res.last_updated_quote),
+            )
+        }
+        PriceSource::PoolTwap {
+            contract_addr,
+            window_seconds,
+        } => {
+            if window_seconds == 0 {
+                return Err(ContractError::HydrationError {
+                    msg: "TWAP window is zero.".to_string(),
+                });
+            }
+            let res: CumulativePricesResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &PoolOracleQueryMsg::CumulativePrices {
+                    base: expr.base.clone(),
+                    quote: expr.quote.clone(),
+                    seconds_ago: vec![window_seconds, 0],
+                },
+            )?;
+            let (start, end) = match res.cumulative_prices[..] {
+                [start, end] if end >= start => (start, end),
+                _ => {
+                    return Err(ContractError::HydrationError {
+                        msg: "Invalid pool cumulative prices.".to_string(),
+                    })
+                }
+            };
+            let twap = (end - start)
+                .checked_div(Decimal256::from_ratio(window_seconds, 1u64))
+                .map_err(|_| ContractError::HydrationError {
+                    msg: "TWAP out of range.".to_string(),
+                })?;
+            // the window ends at the observation, a pool without recent trades isn't stale
+            (twap, None, res.observed_at)
+        }
+        PriceSource::Custom { contract_addr } => {
+            let res: PriceResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &PriceQueryMsg::Price {
+                    base: expr.base.clone(),
+                    quote: expr.quote.clone(),
+                },
+            )?;
+            (res.price, res.confidence, res.publish_time)
+        }
+    };
+
+    let age = env.block.time.seconds().saturating_sub(publish_time);
+    if !skip_staleness && age > expr.max_age_seconds {
+        return Err(ContractError::HydrationError {
+            msg: format!(
+                "Price {}/{} is stale: {}s old, max age {}s.",
+                expr.base, expr.quote, age, expr.max_age_seconds
+            ),
+        });
+    }
+
+    if let (Some(max_confidence), Some(confidence)) = (expr.max_confidence, confidence) {
+        if price.is_zero() {
+            return Err(ContractError::HydrationError {
+                msg: format!("Price {}/{} is zero.", expr.base, expr.quote),
+            });
+        }
+        let relative_confidence =
+            confidence
+                .checked_div(price)
+                .map_err(|_| ContractError::HydrationError {
+                    msg: format!(
+                        "Price {}/{} confidence interval out of range.",
+                        expr.base, expr.quote
+                    ),
+                })?;
+        if relative_confidence > max_confidence {
+            return Err(ContractError::HydrationError {
+                msg: format!(
+                    "Price {}/{} confidence interval {} exceeds max {}.",
+                    expr.base, expr.quote, relative_confidence, max_confidence
+                ),
+            });
+        }
+    }
+
+    Ok(price)
+}
+
+fn scale_pyth_value(value: u128, expo: i32) -> Result<Decimal256, ContractError> {
+    let res = if expo <= 0 {
+        Decimal256::from_atomics(value, expo.unsigned_abs()).ok()
+    } else {
+        Uint256::from(10u8)
+            .checked_pow(expo as u32)
+            .ok()
+            .and_then(|scale| Uint256::from(value).checked_mul(scale).ok())
+            .and_then(|value| Decimal256::checked_from_ratio(value, Uint256::one()).ok())
+    };
+
+    res.ok_or(ContractError::HydrationError {
+        msg: "Pyth price out of range.".to_string(),
+    })
+}
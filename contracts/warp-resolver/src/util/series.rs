@@ -0,0 +1,100 @@
+use crate::util::variable::get_var;
+use crate::ContractError;
+use cosmwasm_std::{Int256, SignedDecimal256};
+use resolver::condition::{SeriesFnOp, SeriesValue};
+use resolver::variable::{Variable, VariableKind};
+
+pub fn parse_series(value: &str) -> Result<Vec<SignedDecimal256>, ContractError> {
+    serde_json_wasm::from_str::<Vec<SignedDecimal256>>(value).map_err(|_| {
+        ContractError::HydrationError {
+            msg: format!("Invalid Series value: {}", value),
+        }
+    })
+}
+
+// value of a var after a Decimal fn: replaced for Decimal vars, appended as a sample to Series vars
+pub fn apply_decimal_value(
+    kind: &VariableKind,
+    current: Option<String>,
+    value: SignedDecimal256,
+) -> Result<String, ContractError> {
+    match kind {
+        VariableKind::Series { capacity } => {
+            let mut samples = match current {
+                Some(current) => parse_series(&current)?,
+                None => vec![],
+            };
+            samples.push(value);
+            let excess = samples.len().saturating_sub(*capacity as usize);
+            samples.drain(..excess);
+            Ok(serde_json_wasm::to_string(&samples)?)
+        }
+        _ => Ok(value.to_string()),
+    }
+}
+
+pub fn resolve_series_value(
+    value: SeriesValue,
+    vars: &Vec<Variable>,
+) -> Result<SignedDecimal256, ContractError> {
+    let series = value.series.clone();
+    resolve_series_fn(value, vars).map_err(|e| e.for_variable(&series))
+}
+
+fn resolve_series_fn(
+    value: SeriesValue,
+    vars: &Vec<Variable>,
+) -> Result<SignedDecimal256, ContractError> {
+    let var = get_var(value.series.clone(), vars)?;
+    let (kind, current) = match var {
+        Variable::Static(v) => (&v.kind, &v.value),
+        Variable::External(v) => (&v.kind, &v.value),
+        Variable::Query(v) => (&v.kind, &v.value),
+        Variable::Balance(v) => (&v.kind, &v.value),
+        Variable::Price(v) => (&v.kind, &v.value),
+    };
+    if !matches!(kind, VariableKind::Series { .. }) {
+        return Err(ContractError::ConditionError {
+            msg: "Variable is not of kind Series.".to_string(),
+        });
+    }
+    let samples = parse_series(current.as_ref().ok_or(ContractError::ConditionError {
+        msg: "Series value not found.".to_string(),
+    })?)?;
+
+    let empty = || ContractError::ConditionError {
+        msg: "Series is empty.".to_string(),
+    };
+    match value.op {
+        SeriesFnOp::Avg => {
+            if samples.is_empty() {
+                return Err(empty());
+            }
+            let sum = samples
+                .iter()
+                .try_fold(SignedDecimal256::zero(), |sum, s| sum.checked_add(*s))?;
+            sum.checked_div(SignedDecimal256::from_ratio(
+                Int256::from(samples.len() as u64),
+                Int256::one(),
+            ))
+            .map_err(|_| ContractError::ConditionError {
+                msg: "Series avg error.".to_string(),
+            })
+        }
+        SeriesFnOp::Min => samples.into_iter().min().ok_or_else(empty),
+        SeriesFnOp::Max => samples.into_iter().max().ok_or_else(empty),
+        SeriesFnOp::Last(n) => {
+            let idx = samples.len().checked_sub(n as usize);
+            match idx {
+                Some(idx) if n > 0 => Ok(samples[idx]),
+                _ => Err(ContractError::ConditionError {
+                    msg: format!(
+                        "Series has {} samples, last({}) requested.",
+                        samples.len(),
+                        n
+                    ),
+                }),
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::util::condition::{
-    resolve_num_value_decimal, resolve_num_value_int, resolve_num_value_uint,
+    resolve_balance_expr, resolve_num_value_decimal, resolve_num_value_int, resolve_num_value_uint,
     resolve_query_expr_bool, resolve_query_expr_decimal, resolve_query_expr_int,
     resolve_query_expr_string, resolve_query_expr_uint, resolve_ref_bool,
 };
@@ -8,353 +8,490 @@ use controller::account::WarpMsg;
 use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_schema::serde::Serialize;
 use cosmwasm_std::{
-    Binary, CosmosMsg, Decimal256, Deps, Env, QueryRequest, Uint128, Uint256, WasmQuery,
+    Binary, CosmosMsg, Deps, Env, HexBinary, Int256, QueryRequest, SignedDecimal256, Uint128,
+    Uint256, WasmQuery,
 };
 use std::str::FromStr;
 
 use controller::job::{ExternalInput, JobStatus};
-use resolver::variable::{FnValue, QueryExpr, Variable, VariableKind};
+use resolver::variable::{
+    Encoding, FnValue, QueryExpr, Variable, VariableKind, SERIES_MAX_CAPACITY,
+};
 
-use super::condition::resolve_string_value;
+use super::condition::{resolve_string_value, WarpEnv};
+use super::json::{int_to_json, parse_json, resolve_json_value, to_json_string};
+use super::price::resolve_price_expr;
+use super::series::{apply_decimal_value, parse_series};
+use json_codec_wasm::Json;
+use std::collections::HashMap;
 
 pub fn hydrate_vars(
     deps: Deps,
     env: Env,
     vars: Vec<Variable>,
     external_inputs: Option<Vec<ExternalInput>>,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<Vec<Variable>, ContractError> {
+    let order = dependency_order(&vars)?;
     let mut hydrated_vars = vec![];
 
-    for var in vars {
-        let hydrated_var = match var {
-            Variable::Static(mut v) => {
-                if v.reinitialize || v.value.is_none() {
-                    match v.kind {
-                        VariableKind::Uint => match v.init_fn.clone() {
-                            FnValue::Uint(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_num_value_uint(deps, env.clone(), val, &hydrated_vars)?
-                                        .to_string(),
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::Uint."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Int => match v.init_fn.clone() {
-                            FnValue::Int(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_num_value_int(deps, env.clone(), val, &hydrated_vars)?
-                                        .to_string(),
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::Int."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Decimal => match v.init_fn.clone() {
-                            FnValue::Decimal(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_num_value_decimal(
-                                        deps,
-                                        env.clone(),
-                                        val,
-                                        &hydrated_vars,
-                                    )?
-                                    .to_string(),
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::Decimal."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Timestamp => match v.init_fn.clone() {
-                            FnValue::Timestamp(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_num_value_int(deps, env.clone(), val, &hydrated_vars)?
-                                        .to_string(),
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::Timestamp."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Bool => match v.init_fn.clone() {
-                            FnValue::Bool(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_ref_bool(deps, env.clone(), val, &hydrated_vars)?
-                                        .to_string(),
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::Bool."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Amount => match v.init_fn.clone() {
-                            FnValue::Uint(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_num_value_uint(deps, env.clone(), val, &hydrated_vars)?
-                                        .to_string(),
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::Uint."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::String => match v.init_fn.clone() {
-                            FnValue::String(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_string_value(
-                                        deps,
-                                        env.clone(),
-                                        val,
-                                        &hydrated_vars,
-                                        warp_account_addr.clone(),
-                                    )?,
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "1Variable init_fn is not of type FnValue::String."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Asset => match v.init_fn.clone() {
-                            FnValue::String(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_string_value(
-                                        deps,
-                                        env.clone(),
-                                        val,
-                                        &hydrated_vars,
-                                        warp_account_addr.clone(),
-                                    )?,
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::String."
-                                        .to_string(),
-                                })
-                            }
-                        },
-                        VariableKind::Json => match v.init_fn.clone() {
-                            FnValue::String(val) => {
-                                v.value = Some(replace_in_string(
-                                    resolve_string_value(
-                                        deps,
-                                        env.clone(),
-                                        val,
-                                        &hydrated_vars,
-                                        warp_account_addr.clone(),
-                                    )?,
-                                    &hydrated_vars,
-                                )?)
-                            }
-                            _ => {
-                                return Err(ContractError::HydrationError {
-                                    msg: "Variable init_fn is not of type FnValue::String."
-                                        .to_string(),
-                                });
-                            }
-                        },
-                    }
-                }
-                if v.value.is_none() {
-                    return Err(ContractError::Unauthorized {});
-                }
-                Variable::Static(v)
-            }
-            Variable::External(mut v) => {
-                if v.reinitialize || v.value.is_none() {
-                    match external_inputs {
-                        None => {
-                            if v.value.is_none() {
-                                return Err(ContractError::HydrationError {
-                                    msg: "External input value is none.".to_string(),
-                                });
-                            }
-                            Variable::External(v)
-                        }
-                        Some(ref input) => {
-                            let idx = input.iter().position(|i| i.name == v.name);
-                            v.value = match idx {
-                                None => {
-                                    return Err(ContractError::HydrationError {
-                                        msg: "External input variable not found.".to_string(),
-                                    })
-                                }
-                                Some(i) => Some(input[i].input.clone()),
-                            };
-                            Variable::External(v)
-                        }
-                    }
-                } else {
-                    if v.value.is_none() {
-                        return Err(ContractError::HydrationError {
-                            msg: "External value is none.".to_string(),
-                        });
-                    }
-                    Variable::External(v)
-                }
-            }
-            Variable::Query(mut v) => {
-                if v.reinitialize || v.value.is_none() {
-                    let replaced_init_fn = replace_references(v.init_fn.clone(), &hydrated_vars)?;
+    // hydrate in dependency order so referenced vars are resolved first
+    for var in order.iter().map(|i| vars[*i].clone()) {
+        let name = get_var_name(&var);
+        let hydrated_var = hydrate_var(
+            deps,
+            env.clone(),
+            var,
+            &hydrated_vars,
+            &external_inputs,
+            warp_env,
+        )
+        .map_err(|e| e.for_variable(&name))?;
+        hydrated_vars.push(hydrated_var);
+    }
 
-                    match v.kind {
-                        VariableKind::String => {
-                            v.value = Some(
-                                // \"$warp.variable\" => \"VALUE"\
-                                resolve_query_expr_string(
+    // return vars in declaration order
+    let mut res: Vec<Option<Variable>> = vec![None; hydrated_vars.len()];
+    for (i, var) in order.into_iter().zip(hydrated_vars) {
+        res[i] = Some(var);
+    }
+    Ok(res.into_iter().flatten().collect())
+}
+
+// hydrated_vars are the already hydrated vars the init_fn may reference
+pub fn hydrate_var(
+    deps: Deps,
+    env: Env,
+    var: Variable,
+    hydrated_vars: &Vec<Variable>,
+    external_inputs: &Option<Vec<ExternalInput>>,
+    warp_env: &WarpEnv,
+) -> Result<Variable, ContractError> {
+    let hydrated_var = match var {
+        Variable::Static(mut v) => {
+            if v.reinitialize || v.value.is_none() {
+                match v.kind {
+                    VariableKind::Uint => match v.init_fn.clone() {
+                        FnValue::Uint(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_num_value_uint(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
                                 )?
                                 .to_string(),
-                            )
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Uint => {
-                            v.value = Some(
-                                resolve_query_expr_uint(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Uint.".to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Int => match v.init_fn.clone() {
+                        FnValue::Int(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_num_value_int(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
                                 )?
                                 .to_string(),
-                            )
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Int => {
-                            v.value = Some(
-                                resolve_query_expr_int(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Int.".to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Decimal => match v.init_fn.clone() {
+                        FnValue::Decimal(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_num_value_decimal(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
                                 )?
                                 .to_string(),
-                            )
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Decimal => {
-                            v.value = Some(
-                                resolve_query_expr_decimal(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Decimal."
+                                    .to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Timestamp => match v.init_fn.clone() {
+                        FnValue::Timestamp(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_num_value_int(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
                                 )?
                                 .to_string(),
-                            )
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Timestamp => {
-                            v.value = Some(
-                                resolve_query_expr_int(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Timestamp."
+                                    .to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::BlockHeight => match v.init_fn.clone() {
+                        FnValue::BlockHeight(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_num_value_int(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
                                 )?
                                 .to_string(),
-                            )
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Bool => {
-                            v.value = Some(
-                                resolve_query_expr_bool(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::BlockHeight."
+                                    .to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Bool => match v.init_fn.clone() {
+                        FnValue::Bool(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_ref_bool(deps, env.clone(), val, hydrated_vars)?
+                                    .to_string(),
+                                hydrated_vars,
+                            )?)
+                        }
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Bool.".to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Amount => match v.init_fn.clone() {
+                        FnValue::Uint(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_num_value_uint(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
                                 )?
                                 .to_string(),
-                            )
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Amount => {
-                            v.value = Some(
-                                resolve_query_expr_uint(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Uint.".to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::String => match v.init_fn.clone() {
+                        FnValue::String(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_string_value(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
-                                )?
-                                .to_string(),
-                            )
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
+                                )?,
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Asset => {
-                            v.value = Some(
-                                resolve_query_expr_string(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "1Variable init_fn is not of type FnValue::String."
+                                    .to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Asset => match v.init_fn.clone() {
+                        FnValue::String(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_string_value(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
-                                )?
-                                .to_string(),
-                            )
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
+                                )?,
+                                hydrated_vars,
+                            )?)
                         }
-                        VariableKind::Json => {
-                            v.value = Some(
-                                resolve_query_expr_string(
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::String.".to_string(),
+                            })
+                        }
+                    },
+                    VariableKind::Json => match v.init_fn.clone() {
+                        FnValue::String(val) => {
+                            v.value = Some(replace_in_string(
+                                resolve_string_value(
                                     deps,
                                     env.clone(),
-                                    replaced_init_fn.clone(),
-                                )?
-                                .to_string(),
-                            )
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
+                                )?,
+                                hydrated_vars,
+                            )?)
+                        }
+                        FnValue::Json(val) => {
+                            v.value = Some(to_json_string(&resolve_json_value(
+                                deps,
+                                env.clone(),
+                                val,
+                                hydrated_vars,
+                                warp_env,
+                            )?))
                         }
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::String or FnValue::Json."
+                                    .to_string(),
+                            });
+                        }
+                    },
+                    VariableKind::Series { .. } => match v.init_fn.clone() {
+                        FnValue::Decimal(val) => {
+                            v.value = Some(apply_decimal_value(
+                                &v.kind,
+                                None,
+                                resolve_num_value_decimal(
+                                    deps,
+                                    env.clone(),
+                                    val,
+                                    hydrated_vars,
+                                    warp_env,
+                                )?,
+                            )?)
+                        }
+                        _ => {
+                            return Err(ContractError::HydrationError {
+                                msg: "Variable init_fn is not of type FnValue::Decimal."
+                                    .to_string(),
+                            })
+                        }
+                    },
+                }
+            }
+            if v.value.is_none() {
+                return Err(ContractError::Unauthorized {});
+            }
+            Variable::Static(v)
+        }
+        Variable::External(mut v) => {
+            if v.reinitialize || v.value.is_none() {
+                match external_inputs {
+                    None => {
+                        if v.value.is_none() {
+                            return Err(ContractError::HydrationError {
+                                msg: "External input value is none.".to_string(),
+                            });
+                        }
+                        Variable::External(v)
+                    }
+                    Some(ref input) => {
+                        let idx = input.iter().position(|i| i.name == v.name);
+                        v.value = match idx {
+                            None => {
+                                return Err(ContractError::HydrationError {
+                                    msg: "External input variable not found.".to_string(),
+                                })
+                            }
+                            Some(i) => Some(input[i].input.clone()),
+                        };
+                        Variable::External(v)
                     }
                 }
+            } else {
                 if v.value.is_none() {
-                    return Err(ContractError::Unauthorized {});
+                    return Err(ContractError::HydrationError {
+                        msg: "External value is none.".to_string(),
+                    });
                 }
-                Variable::Query(v)
+                Variable::External(v)
             }
-        };
-        hydrated_vars.push(hydrated_var);
-    }
-    Ok(hydrated_vars)
+        }
+        Variable::Query(mut v) => {
+            if v.reinitialize || v.value.is_none() {
+                let replaced_init_fn = replace_references(v.init_fn.clone(), hydrated_vars)?;
+
+                match v.kind {
+                    VariableKind::String => {
+                        v.value = Some(
+                            // \"$warp.variable\" => \"VALUE"\
+                            resolve_query_expr_string(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Uint => {
+                        v.value = Some(
+                            resolve_query_expr_uint(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Int => {
+                        v.value = Some(
+                            resolve_query_expr_int(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Decimal => {
+                        v.value = Some(
+                            resolve_query_expr_decimal(
+                                deps,
+                                env.clone(),
+                                replaced_init_fn.clone(),
+                            )?
+                            .to_string(),
+                        )
+                    }
+                    VariableKind::Timestamp => {
+                        v.value = Some(
+                            resolve_query_expr_int(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::BlockHeight => {
+                        v.value = Some(
+                            resolve_query_expr_int(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Bool => {
+                        v.value = Some(
+                            resolve_query_expr_bool(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Amount => {
+                        v.value = Some(
+                            resolve_query_expr_uint(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Asset => {
+                        v.value = Some(
+                            resolve_query_expr_string(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Json => {
+                        v.value = Some(
+                            resolve_query_expr_string(deps, env.clone(), replaced_init_fn.clone())?
+                                .to_string(),
+                        )
+                    }
+                    VariableKind::Series { .. } => {
+                        v.value = Some(apply_decimal_value(
+                            &v.kind,
+                            None,
+                            resolve_query_expr_decimal(
+                                deps,
+                                env.clone(),
+                                replaced_init_fn.clone(),
+                            )?,
+                        )?)
+                    }
+                }
+            }
+            if v.value.is_none() {
+                return Err(ContractError::Unauthorized {});
+            }
+            Variable::Query(v)
+        }
+        // balances are re-queried on every hydration, a value carried over from a previous
+        // execution is never reused
+        Variable::Balance(mut v) => {
+            match v.kind {
+                VariableKind::Uint | VariableKind::Amount => {
+                    v.value = Some(
+                        resolve_balance_expr(
+                            deps,
+                            env.clone(),
+                            v.init_fn.clone(),
+                            hydrated_vars,
+                            warp_env,
+                        )?
+                        .to_string(),
+                    )
+                }
+                _ => {
+                    return Err(ContractError::HydrationError {
+                        msg: "Balance variable is not of kind Uint or Amount.".to_string(),
+                    })
+                }
+            }
+            Variable::Balance(v)
+        }
+        // prices are re-queried on every hydration, so staleness and confidence are always checked
+        Variable::Price(mut v) => {
+            match v.kind {
+                VariableKind::Decimal => {
+                    v.value = Some(
+                        resolve_price_expr(
+                            deps,
+                            env.clone(),
+                            v.init_fn.clone(),
+                            warp_env.skip_price_staleness,
+                        )?
+                        .to_string(),
+                    )
+                }
+                _ => {
+                    return Err(ContractError::HydrationError {
+                        msg: "Price variable is not of kind Decimal.".to_string(),
+                    })
+                }
+            }
+            Variable::Price(v)
+        }
+    };
+
+    Ok(hydrated_var)
 }
 
+// msgs are templated as a JSON tree
+// - only string nodes exactly matching "$warp.variable.{name}" are replaced
+// - values are type checked against the var kind, Json and Series values must be JSON strings
+//   (or encoded into one) as a value can't change the structure of the msg
+// - objects and arrays are only inserted by explicit Json placeholders,
+//   {"$warp.json": "$warp.variable.{name}"} is replaced by the value of any type
+// - inserted values are not templated again
 pub fn hydrate_msgs(msgs: String, vars: Vec<Variable>) -> Result<Vec<WarpMsg>, ContractError> {
-    let mut replaced_msgs = msgs;
-    for var in &vars {
-        let (name, replacement) = get_replacement_in_struct(var)?;
-        replaced_msgs =
-            replaced_msgs.replace(&format!("\"$warp.variable.{}\"", name), &replacement);
-        if replacement.contains("$warp.variable") {
-            return Err(ContractError::HydrationError {
-                msg: "Attempt to inject warp variable.".to_string(),
-            });
-        }
-    }
+    let replaced_msgs = to_json_string(&replace_in_json(parse_json(&msgs)?, &vars)?);
 
     match serde_json_wasm::from_str::<Vec<WarpMsg>>(&replaced_msgs) {
         Ok(msgs) => Ok(msgs),
 
         // fallback to legacy flow
         Err(_) => {
-            let msgs = serde_json_wasm::from_str::<Vec<CosmosMsg>>(&replaced_msgs)
-                .unwrap()
+            let msgs = serde_json_wasm::from_str::<Vec<CosmosMsg>>(&replaced_msgs)?
                 .into_iter()
                 .map(WarpMsg::Generic)
                 .collect();
@@ -364,298 +501,170 @@ pub fn hydrate_msgs(msgs: String, vars: Vec<Variable>) -> Result<Vec<WarpMsg>, C
     }
 }
 
-fn get_replacement_in_struct(var: &Variable) -> Result<(String, String), ContractError> {
-    let (name, replacement) = match var {
-        Variable::Static(v) => match v.value.clone() {
-            None => {
-                return Err(ContractError::HydrationError {
-                    msg: "Static msg value is none.".to_string(),
-                });
-            }
-            Some(val) => (v.name.clone(), {
-                match v.kind {
-                    VariableKind::Uint => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Int => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Decimal => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Timestamp => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Bool => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Amount => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::String => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Asset => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Json => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
+const JSON_PLACEHOLDER_KEY: &str = "$warp.json";
+
+fn replace_in_json(value: Json, vars: &[Variable]) -> Result<Json, ContractError> {
+    match value {
+        Json::String(s) if s.starts_with("$warp.variable.") => {
+            let var = find_placeholder_var(&s, vars)?;
+            let replacement = get_replacement_json(var)?;
+            match (get_var_kind(var), &replacement) {
+                (VariableKind::Json | VariableKind::Series { .. }, Json::String(_)) => {
+                    Ok(replacement)
                 }
-            }),
-        },
-        Variable::External(v) => match v.value.clone() {
-            None => {
-                return Err(ContractError::HydrationError {
-                    msg: "External msg value is none.".to_string(),
-                });
-            }
-            Some(val) => (v.name.clone(), {
-                match v.kind {
-                    VariableKind::String => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Uint => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Int => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Decimal => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Timestamp => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Bool => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Amount => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Asset => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Json => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
+                (VariableKind::Json | VariableKind::Series { .. }, _) => {
+                    Err(ContractError::VariableError {
+                        name: get_var_name(var),
+                        error: Box::new(ContractError::HydrationError {
+                            msg: format!(
+                                "Value of a string placeholder is not a string, use {{\"{}\": \"{}\"}} to insert JSON.",
+                                JSON_PLACEHOLDER_KEY, s
+                            ),
+                        }),
+                    })
                 }
-            }),
-        },
-        Variable::Query(v) => match v.value.clone() {
-            None => {
-                return Err(ContractError::HydrationError {
-                    msg: "Query msg value is none.".to_string(),
-                });
+                _ => Ok(replacement),
             }
-            Some(val) => (v.name.clone(), {
-                match v.kind {
-                    VariableKind::Uint => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Int => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Decimal => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Timestamp => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Bool => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                    VariableKind::Amount => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::String => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Asset => format!(
-                        "\"{}\"",
-                        match v.encode {
-                            true => {
-                                base64::encode(val)
-                            }
-                            false => val,
-                        }
-                    ),
-                    VariableKind::Json => match v.encode {
-                        true => {
-                            format!("\"{}\"", base64::encode(val))
-                        }
-                        false => val,
-                    },
-                }
-            }),
-        },
+        }
+        Json::Object(values) if json_placeholder(&values).is_some() => {
+            let placeholder = json_placeholder(&values).unwrap_or_default();
+            get_replacement_json(find_placeholder_var(placeholder, vars)?)
+        }
+        Json::Array(values) => Ok(Json::Array(
+            values
+                .into_iter()
+                .map(|v| replace_in_json(v, vars))
+                .collect::<Result<Vec<Json>, ContractError>>()?,
+        )),
+        Json::Object(values) => Ok(Json::Object(
+            values
+                .into_iter()
+                .map(|(k, v)| Ok((k, replace_in_json(v, vars)?)))
+                .collect::<Result<HashMap<String, Json>, ContractError>>()?,
+        )),
+        value => Ok(value),
+    }
+}
+
+// explicit Json placeholder, an object with the single entry {"$warp.json": "$warp.variable.{name}"}
+fn json_placeholder(values: &HashMap<String, Json>) -> Option<&str> {
+    match values.get(JSON_PLACEHOLDER_KEY) {
+        Some(Json::String(s)) if values.len() == 1 && s.starts_with("$warp.variable.") => Some(s),
+        _ => None,
+    }
+}
+
+fn find_placeholder_var<'a>(
+    placeholder: &str,
+    vars: &'a [Variable],
+) -> Result<&'a Variable, ContractError> {
+    vars.iter()
+        .find(|v| format!("$warp.variable.{}", get_var_name(v)) == placeholder)
+        .ok_or(ContractError::VariableNotFound {
+            name: placeholder.to_string(),
+        })
+}
+
+// value of a var as a JSON value of its kind
+fn typed_value(value: &str, kind: &VariableKind) -> Result<Json, ContractError> {
+    let valid = match kind {
+        VariableKind::String | VariableKind::Asset | VariableKind::Json => true,
+        VariableKind::Uint => Uint256::from_str(value).is_ok(),
+        VariableKind::Int | VariableKind::Timestamp | VariableKind::BlockHeight => {
+            Int256::from_str(value).is_ok()
+        }
+        VariableKind::Decimal => SignedDecimal256::from_str(value).is_ok(),
+        VariableKind::Bool => bool::from_str(value).is_ok(),
+        VariableKind::Amount => Uint128::from_str(value).is_ok(),
+        VariableKind::Series { .. } => parse_series(value).is_ok(),
     };
+    if !valid {
+        return Err(ContractError::HydrationError {
+            msg: format!("Value {:?} is not of kind {:?}.", value, kind),
+        });
+    }
 
-    Ok((name, replacement))
+    Ok(match kind {
+        VariableKind::String
+        | VariableKind::Uint
+        | VariableKind::Decimal
+        | VariableKind::Amount
+        | VariableKind::Asset => Json::String(value.to_string()),
+        VariableKind::Int | VariableKind::Timestamp | VariableKind::BlockHeight => {
+            int_to_json(Int256::from_str(value)?)
+        }
+        VariableKind::Bool => Json::Bool(bool::from_str(value)?),
+        VariableKind::Series { .. } => parse_json(value)?,
+        // query variables store top level strings unquoted
+        VariableKind::Json => parse_json(value).unwrap_or(Json::String(value.to_string())),
+    })
 }
 
-fn get_replacement_in_string(var: &Variable) -> Result<(String, String), ContractError> {
-    let (name, replacement) = match var {
-        Variable::Static(v) => match v.value.clone() {
-            None => {
-                return Err(ContractError::HydrationError {
-                    msg: "Static msg value is none.".to_string(),
-                });
-            }
-            Some(val) => (
-                v.name.clone(),
-                match v.encode {
-                    true => base64::encode(val),
-                    false => val,
-                },
-            ),
-        },
-        Variable::External(v) => match v.value.clone() {
-            None => {
-                return Err(ContractError::HydrationError {
-                    msg: "External msg value is none.".to_string(),
-                });
-            }
-            Some(val) => (
-                v.name.clone(),
-                match v.encode {
-                    true => base64::encode(val),
-                    false => val,
-                },
-            ),
-        },
-        Variable::Query(v) => match v.value.clone() {
-            None => {
-                return Err(ContractError::HydrationError {
-                    msg: "Query msg value is none.".to_string(),
-                });
-            }
-            Some(val) => (
-                v.name.clone(),
-                match v.encode {
-                    true => base64::encode(val),
-                    false => val,
-                },
-            ),
-        },
+fn encoded_value(
+    value: &str,
+    kind: &VariableKind,
+    encoding: &Encoding,
+) -> Result<Json, ContractError> {
+    let typed = typed_value(value, kind)?;
+
+    Ok(match encoding {
+        Encoding::None => typed,
+        Encoding::Base64 => Json::String(base64::encode(value)),
+        Encoding::Hex => Json::String(HexBinary::from(value.as_bytes()).to_hex()),
+        Encoding::JsonString => Json::String(to_json_string(&typed)),
+        Encoding::Base64Json => Json::String(base64::encode(to_json_string(&typed))),
+    })
+}
+
+fn get_replacement_json(var: &Variable) -> Result<Json, ContractError> {
+    let (name, kind, encoding, value) = get_hydrated_value(var)?;
+
+    encoded_value(&value, kind, encoding).map_err(|e| e.for_variable(&name))
+}
+
+// name, kind, encoding and value of a hydrated var
+fn get_hydrated_value(
+    var: &Variable,
+) -> Result<(String, &VariableKind, &Encoding, String), ContractError> {
+    let (prefix, name, kind, encode, value) = match var {
+        Variable::Static(v) => ("Static", &v.name, &v.kind, &v.encode, &v.value),
+        Variable::External(v) => ("External", &v.name, &v.kind, &v.encode, &v.value),
+        Variable::Query(v) => ("Query", &v.name, &v.kind, &v.encode, &v.value),
+        Variable::Balance(v) => ("Balance", &v.name, &v.kind, &v.encode, &v.value),
+        Variable::Price(v) => ("Price", &v.name, &v.kind, &v.encode, &v.value),
     };
+    let value = value.clone().ok_or(ContractError::HydrationError {
+        msg: format!("{} msg value is none.", prefix),
+    })?;
+
+    Ok((name.clone(), kind, encode, value))
+}
+
+// replacement of $warp.variable.{name} within a string
+fn encode_in_string(
+    value: String,
+    kind: &VariableKind,
+    encoding: &Encoding,
+) -> Result<String, ContractError> {
+    Ok(match encoding {
+        Encoding::None => value,
+        encoding => match encoded_value(&value, kind, encoding)? {
+            Json::String(encoded) => encoded,
+            encoded => to_json_string(&encoded),
+        },
+    })
+}
+
+fn get_replacement_in_struct(var: &Variable) -> Result<(String, String), ContractError> {
+    Ok((
+        get_var_name(var),
+        to_json_string(&get_replacement_json(var)?),
+    ))
+}
+
+fn get_replacement_in_string(var: &Variable) -> Result<(String, String), ContractError> {
+    let (name, kind, encoding, value) = get_hydrated_value(var)?;
+    let replacement = encode_in_string(value, kind, encoding).map_err(|e| e.for_variable(&name))?;
 
     Ok((name, replacement))
 }
@@ -732,89 +741,72 @@ fn replace_in_string(value: String, vars: &[Variable]) -> Result<String, Contrac
     Ok(replaced_value)
 }
 
-pub fn msgs_valid(msgs: &str, vars: &Vec<Variable>) -> Result<bool, ContractError> {
-    let mut replaced_msgs = msgs.to_owned();
-    for var in vars {
-        let (name, replacement) = match var {
-            Variable::Static(v) => (
-                v.name.clone(),
-                match v.kind {
-                    VariableKind::String => "\"test\"",
-                    VariableKind::Uint => "\"0\"",
-                    VariableKind::Int => "0",
-                    VariableKind::Decimal => "\"0.0\"",
-                    VariableKind::Timestamp => "0",
-                    VariableKind::Bool => "true",
-                    VariableKind::Amount => "\"0\"",
-                    VariableKind::Asset => "\"test\"",
-                    VariableKind::Json => "true",
-                },
-            ),
-            Variable::External(v) => (
-                v.name.clone(),
-                match v.kind {
-                    VariableKind::String => "\"test\"",
-                    VariableKind::Uint => "\"0\"",
-                    VariableKind::Int => "0",
-                    VariableKind::Decimal => "\"0.0\"",
-                    VariableKind::Timestamp => "0",
-                    VariableKind::Bool => "true",
-                    VariableKind::Amount => "\"0\"",
-                    VariableKind::Asset => "\"test\"",
-                    VariableKind::Json => "true",
-                },
-            ),
-            Variable::Query(v) => (
-                v.name.clone(),
-                match v.kind {
-                    VariableKind::String => "\"test\"",
-                    VariableKind::Uint => "\"0\"",
-                    VariableKind::Int => "0",
-                    VariableKind::Decimal => "\"0.0\"",
-                    VariableKind::Timestamp => "0",
-                    VariableKind::Bool => "true",
-                    VariableKind::Amount => "\"0\"",
-                    VariableKind::Asset => "\"test\"",
-                    VariableKind::Json => "true",
-                },
-            ),
-        };
-        if replacement.contains("$warp.variable") {
-            return Err(ContractError::HydrationError {
-                msg: "Attempt to inject warp variable.".to_string(),
-            });
-        }
-        let replacement_with_encode = match var {
-            Variable::Static(v) => match v.encode {
-                true => format!("\"{}\"", base64::encode(replacement)),
-                false => replacement.to_string(),
-            },
-            Variable::External(v) => match v.encode {
-                true => format!("\"{}\"", base64::encode(replacement)),
-                false => replacement.to_string(),
-            },
-            Variable::Query(v) => match v.encode {
-                true => format!("\"{}\"", base64::encode(replacement)),
-                false => replacement.to_string(),
-            },
-        };
-        replaced_msgs = replaced_msgs.replace(
-            &format!("\"$warp.variable.{}\"", name),
-            &replacement_with_encode,
-        );
-    }
+// msgs are templated with placeholder values of each var kind
+pub fn msgs_valid(msgs: &str, vars: &[Variable]) -> Result<bool, ContractError> {
+    let placeholder_vars: Vec<Variable> = vars
+        .iter()
+        .cloned()
+        .map(|mut var| {
+            let placeholder = match get_var_kind(&var) {
+                VariableKind::String => "test",
+                VariableKind::Uint => "0",
+                VariableKind::Int => "0",
+                VariableKind::Decimal => "0.0",
+                VariableKind::Timestamp => "0",
+                VariableKind::BlockHeight => "0",
+                VariableKind::Bool => "true",
+                VariableKind::Amount => "0",
+                VariableKind::Asset => "test",
+                VariableKind::Json => "test",
+                VariableKind::Series { .. } => "[]",
+            }
+            .to_string();
+            match &mut var {
+                Variable::Static(v) => v.value = Some(placeholder),
+                Variable::External(v) => v.value = Some(placeholder),
+                Variable::Query(v) => v.value = Some(placeholder),
+                Variable::Balance(v) => v.value = Some(placeholder),
+                Variable::Price(v) => v.value = Some(placeholder),
+            }
+            var
+        })
+        .collect();
 
+    let replaced_msgs = to_json_string(&replace_in_json(parse_json(msgs)?, &placeholder_vars)?);
     let _msgs = serde_json_wasm::from_str::<Vec<WarpMsg>>(&replaced_msgs)?;
 
     Ok(true)
 }
 
+// kinds of vars an update_fn value can be applied to, as enforced by apply_var_fn
+// - Timestamp functions are also accepted on Int vars, jobs created before the Timestamp kind
+//   stored their time in Int vars and must keep updating them
+pub fn update_fn_kind_valid(value: &FnValue, kind: &VariableKind) -> bool {
+    matches!(
+        (value, kind),
+        (FnValue::Uint(_), VariableKind::Uint)
+            | (FnValue::Int(_), VariableKind::Int)
+            | (
+                FnValue::Decimal(_),
+                VariableKind::Decimal | VariableKind::Series { .. }
+            )
+            | (
+                FnValue::Timestamp(_),
+                VariableKind::Timestamp | VariableKind::Int
+            )
+            | (FnValue::BlockHeight(_), VariableKind::BlockHeight)
+            | (FnValue::Bool(_), VariableKind::Bool)
+            | (FnValue::String(_), VariableKind::String)
+            | (FnValue::Json(_), VariableKind::Json)
+    )
+}
+
 pub fn apply_var_fn(
     deps: Deps,
     env: Env,
     vars: Vec<Variable>,
     status: JobStatus,
-    warp_account_addr: Option<String>,
+    warp_env: &WarpEnv,
 ) -> Result<String, ContractError> {
     let mut res = vec![];
     for var in vars.clone() {
@@ -838,8 +830,14 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_uint(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_uint(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::Int(nv) => {
@@ -849,42 +847,73 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::Decimal(nv) => {
-                                    if v.kind != VariableKind::Decimal {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Decimal | VariableKind::Series { .. }
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Static Decimal function mismatch.".to_string(),
                                         });
                                     }
-                                    v.value = Some(
-                                        resolve_num_value_decimal(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
-                                    );
+                                    v.value = Some(apply_decimal_value(
+                                        &v.kind,
+                                        v.value.clone(),
+                                        resolve_num_value_decimal(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?,
+                                    )?);
                                 }
                                 FnValue::Timestamp(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Timestamp | VariableKind::Int
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Static Timestamp function mismatch.".to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::BlockHeight(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if v.kind != VariableKind::BlockHeight {
                                         return Err(ContractError::FunctionError {
                                             msg: "Static BlockHeight function mismatch."
                                                 .to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::Bool(val) => {
@@ -910,11 +939,25 @@ pub fn apply_var_fn(
                                             env.clone(),
                                             val,
                                             &vars,
-                                            warp_account_addr.clone(),
+                                            warp_env,
                                         )?
                                         .to_string(),
                                     );
                                 }
+                                FnValue::Json(val) => {
+                                    if v.kind != VariableKind::Json {
+                                        return Err(ContractError::FunctionError {
+                                            msg: "Static Json function mismatch.".to_string(),
+                                        });
+                                    }
+                                    v.value = Some(to_json_string(&resolve_json_value(
+                                        deps,
+                                        env.clone(),
+                                        val,
+                                        &vars,
+                                        warp_env,
+                                    )?));
+                                }
                             },
                         },
                         JobStatus::Failed => match update_fn.on_error {
@@ -927,8 +970,14 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_uint(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_uint(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::Int(nv) => {
@@ -938,42 +987,73 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::Decimal(nv) => {
-                                    if v.kind != VariableKind::Decimal {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Decimal | VariableKind::Series { .. }
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Static Uint function mismatch.".to_string(),
                                         });
                                     }
-                                    v.value = Some(
-                                        resolve_num_value_decimal(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
-                                    );
+                                    v.value = Some(apply_decimal_value(
+                                        &v.kind,
+                                        v.value.clone(),
+                                        resolve_num_value_decimal(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?,
+                                    )?);
                                 }
                                 FnValue::Timestamp(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Timestamp | VariableKind::Int
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Static Timestamp function mismatch.".to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::BlockHeight(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if v.kind != VariableKind::BlockHeight {
                                         return Err(ContractError::FunctionError {
                                             msg: "Static BlockHeight function mismatch."
                                                 .to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     );
                                 }
                                 FnValue::Bool(val) => {
@@ -999,11 +1079,25 @@ pub fn apply_var_fn(
                                             env.clone(),
                                             val,
                                             &vars,
-                                            warp_account_addr.clone(),
+                                            warp_env,
                                         )?
                                         .to_string(),
                                     );
                                 }
+                                FnValue::Json(val) => {
+                                    if v.kind != VariableKind::Json {
+                                        return Err(ContractError::FunctionError {
+                                            msg: "Static Json function mismatch.".to_string(),
+                                        });
+                                    }
+                                    v.value = Some(to_json_string(&resolve_json_value(
+                                        deps,
+                                        env.clone(),
+                                        val,
+                                        &vars,
+                                        warp_env,
+                                    )?));
+                                }
                             },
                         },
                         _ => {
@@ -1034,8 +1128,14 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_uint(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_uint(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Int(nv) => {
@@ -1045,43 +1145,74 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Decimal(nv) => {
-                                    if v.kind != VariableKind::Decimal {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Decimal | VariableKind::Series { .. }
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "External Decimal function mismatch.".to_string(),
                                         });
                                     }
-                                    v.value = Some(
-                                        resolve_num_value_decimal(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
-                                    )
+                                    v.value = Some(apply_decimal_value(
+                                        &v.kind,
+                                        v.value.clone(),
+                                        resolve_num_value_decimal(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?,
+                                    )?);
                                 }
                                 FnValue::Timestamp(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Timestamp | VariableKind::Int
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "External Timestamp function mismatch."
                                                 .to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::BlockHeight(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if v.kind != VariableKind::BlockHeight {
                                         return Err(ContractError::FunctionError {
                                             msg: "External BlockHeight function mismatch."
                                                 .to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Bool(val) => {
@@ -1107,11 +1238,25 @@ pub fn apply_var_fn(
                                             env.clone(),
                                             val,
                                             &vars,
-                                            warp_account_addr.clone(),
+                                            warp_env,
                                         )?
                                         .to_string(),
                                     )
                                 }
+                                FnValue::Json(val) => {
+                                    if v.kind != VariableKind::Json {
+                                        return Err(ContractError::FunctionError {
+                                            msg: "External Json function mismatch.".to_string(),
+                                        });
+                                    }
+                                    v.value = Some(to_json_string(&resolve_json_value(
+                                        deps,
+                                        env.clone(),
+                                        val,
+                                        &vars,
+                                        warp_env,
+                                    )?))
+                                }
                             },
                         },
                         JobStatus::Failed => match update_fn.on_error {
@@ -1124,8 +1269,14 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_uint(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_uint(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Int(nv) => {
@@ -1135,43 +1286,74 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Decimal(nv) => {
-                                    if v.kind != VariableKind::Decimal {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Decimal | VariableKind::Series { .. }
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "External Decimal function mismatch.".to_string(),
                                         });
                                     }
-                                    v.value = Some(
-                                        resolve_num_value_decimal(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
-                                    )
+                                    v.value = Some(apply_decimal_value(
+                                        &v.kind,
+                                        v.value.clone(),
+                                        resolve_num_value_decimal(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?,
+                                    )?);
                                 }
                                 FnValue::Timestamp(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Timestamp | VariableKind::Int
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "External Timestamp function mismatch."
                                                 .to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::BlockHeight(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if v.kind != VariableKind::BlockHeight {
                                         return Err(ContractError::FunctionError {
                                             msg: "External BlockHeight function mismatch."
                                                 .to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Bool(val) => {
@@ -1197,11 +1379,25 @@ pub fn apply_var_fn(
                                             env.clone(),
                                             val,
                                             &vars,
-                                            warp_account_addr.clone(),
+                                            warp_env,
                                         )?
                                         .to_string(),
                                     )
                                 }
+                                FnValue::Json(val) => {
+                                    if v.kind != VariableKind::Json {
+                                        return Err(ContractError::FunctionError {
+                                            msg: "External Json function mismatch.".to_string(),
+                                        });
+                                    }
+                                    v.value = Some(to_json_string(&resolve_json_value(
+                                        deps,
+                                        env.clone(),
+                                        val,
+                                        &vars,
+                                        warp_env,
+                                    )?))
+                                }
                             },
                         },
                         _ => {
@@ -1232,8 +1428,14 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_uint(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_uint(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Int(nv) => {
@@ -1243,42 +1445,72 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Decimal(nv) => {
-                                    if v.kind != VariableKind::Decimal {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Decimal | VariableKind::Series { .. }
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Query Decimal function mismatch.".to_string(),
                                         });
                                     }
-                                    v.value = Some(
-                                        resolve_num_value_decimal(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
-                                    )
+                                    v.value = Some(apply_decimal_value(
+                                        &v.kind,
+                                        v.value.clone(),
+                                        resolve_num_value_decimal(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?,
+                                    )?);
                                 }
                                 FnValue::Timestamp(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Timestamp | VariableKind::Int
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Query Timestamp function mismatch.".to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::BlockHeight(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if v.kind != VariableKind::BlockHeight {
                                         return Err(ContractError::FunctionError {
-                                            msg: "Query Blockheighht function mismatch."
-                                                .to_string(),
+                                            msg: "Query BlockHeight function mismatch.".to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Bool(val) => {
@@ -1304,11 +1536,25 @@ pub fn apply_var_fn(
                                             env.clone(),
                                             val,
                                             &vars,
-                                            warp_account_addr.clone(),
+                                            warp_env,
                                         )?
                                         .to_string(),
                                     )
                                 }
+                                FnValue::Json(val) => {
+                                    if v.kind != VariableKind::Json {
+                                        return Err(ContractError::FunctionError {
+                                            msg: "Query Json function mismatch.".to_string(),
+                                        });
+                                    }
+                                    v.value = Some(to_json_string(&resolve_json_value(
+                                        deps,
+                                        env.clone(),
+                                        val,
+                                        &vars,
+                                        warp_env,
+                                    )?))
+                                }
                             },
                         },
                         JobStatus::Failed => match update_fn.on_error {
@@ -1321,8 +1567,14 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_uint(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_uint(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Int(nv) => {
@@ -1332,41 +1584,72 @@ pub fn apply_var_fn(
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Decimal(nv) => {
-                                    if v.kind != VariableKind::Decimal {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Decimal | VariableKind::Series { .. }
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Query Decimal function mismatch.".to_string(),
                                         });
                                     }
-                                    v.value = Some(
-                                        resolve_num_value_decimal(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
-                                    )
+                                    v.value = Some(apply_decimal_value(
+                                        &v.kind,
+                                        v.value.clone(),
+                                        resolve_num_value_decimal(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?,
+                                    )?);
                                 }
                                 FnValue::Timestamp(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if !matches!(
+                                        v.kind,
+                                        VariableKind::Timestamp | VariableKind::Int
+                                    ) {
                                         return Err(ContractError::FunctionError {
                                             msg: "Query Timestamp function mismatch.".to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::BlockHeight(nv) => {
-                                    if v.kind != VariableKind::Int {
+                                    if v.kind != VariableKind::BlockHeight {
                                         return Err(ContractError::FunctionError {
                                             msg: "Query BlockHeight function mismatch.".to_string(),
                                         });
                                     }
                                     v.value = Some(
-                                        resolve_num_value_int(deps, env.clone(), nv, &vars)?
-                                            .to_string(),
+                                        resolve_num_value_int(
+                                            deps,
+                                            env.clone(),
+                                            nv,
+                                            &vars,
+                                            warp_env,
+                                        )?
+                                        .to_string(),
                                     )
                                 }
                                 FnValue::Bool(val) => {
@@ -1392,11 +1675,25 @@ pub fn apply_var_fn(
                                             env.clone(),
                                             val,
                                             &vars,
-                                            warp_account_addr.clone(),
+                                            warp_env,
                                         )?
                                         .to_string(),
                                     )
                                 }
+                                FnValue::Json(val) => {
+                                    if v.kind != VariableKind::Json {
+                                        return Err(ContractError::FunctionError {
+                                            msg: "Query Json function mismatch.".to_string(),
+                                        });
+                                    }
+                                    v.value = Some(to_json_string(&resolve_json_value(
+                                        deps,
+                                        env.clone(),
+                                        val,
+                                        &vars,
+                                        warp_env,
+                                    )?))
+                                }
                             },
                         },
                         _ => {
@@ -1408,6 +1705,10 @@ pub fn apply_var_fn(
                 }
                 res.push(Variable::Query(v));
             }
+            // balances are re-queried on every hydration, update_fn is rejected by vars_valid
+            Variable::Balance(v) => res.push(Variable::Balance(v)),
+            // prices are re-queried on every hydration as well
+            Variable::Price(v) => res.push(Variable::Price(v)),
         }
     }
     Ok(serde_json_wasm::to_string(&res)?)
@@ -1419,6 +1720,8 @@ pub fn get_var(name: String, vars: &Vec<Variable>) -> Result<&Variable, Contract
             Variable::Static(v) => v.name.clone(),
             Variable::External(v) => v.name.clone(),
             Variable::Query(v) => v.name.clone(),
+            Variable::Balance(v) => v.name.clone(),
+            Variable::Price(v) => v.name.clone(),
         };
         if format!("$warp.variable.{}", n) == name {
             return Ok(var);
@@ -1427,65 +1730,157 @@ pub fn get_var(name: String, vars: &Vec<Variable>) -> Result<&Variable, Contract
     Err(ContractError::VariableNotFound { name })
 }
 
-pub fn has_duplicates(vars: &Vec<Variable>) -> bool {
-    for i in 0..vars.len() {
-        for j in i..vars.len() {
-            if i != j {
-                match vars[i].clone() {
-                    Variable::Static(vari) => match vars[j].clone() {
-                        Variable::Static(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                        Variable::External(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                        Variable::Query(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                    },
-                    Variable::External(vari) => match vars[j].clone() {
-                        Variable::Static(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                        Variable::External(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                        Variable::Query(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                    },
-                    Variable::Query(vari) => match vars[j].clone() {
-                        Variable::Static(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                        Variable::External(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                        Variable::Query(varj) => {
-                            if vari.name == varj.name {
-                                return true;
-                            }
-                        }
-                    },
+// indices of the vars referenced in text, a reference to "x" does not match "$warp.variable.xy"
+pub fn referenced_vars(text: &str, vars: &[Variable]) -> Vec<usize> {
+    let mut res = vec![];
+    for (idx, var) in vars.iter().enumerate() {
+        let pattern = format!("$warp.variable.{}", get_var_name(var));
+        let referenced = text.match_indices(&pattern).any(|(i, _)| {
+            !matches!(
+                text[i + pattern.len()..].chars().next(),
+                Some(c) if c.is_alphanumeric() || c == '_'
+            )
+        });
+        if referenced {
+            res.push(idx);
+        }
+    }
+    res
+}
+
+// indices of the vars referenced by init_fn, either as Ref nodes or as placeholders in queries
+pub fn get_var_dependencies(
+    var: &Variable,
+    vars: &[Variable],
+) -> Result<Vec<usize>, ContractError> {
+    let mut init_fn = match var {
+        Variable::Static(v) => serde_json_wasm::to_string(&v.init_fn)?,
+        Variable::External(v) => serde_json_wasm::to_string(&v.init_fn)?,
+        Variable::Query(v) => serde_json_wasm::to_string(&v.init_fn)?,
+        Variable::Balance(v) => serde_json_wasm::to_string(&v.init_fn)?,
+        Variable::Price(v) => serde_json_wasm::to_string(&v.init_fn)?,
+    };
+    // query msgs and keys are base64 encoded
+    if let Variable::Query(v) = var {
+        match &v.init_fn.query {
+            QueryRequest::Wasm(WasmQuery::Smart { msg: bin, .. })
+            | QueryRequest::Wasm(WasmQuery::Raw { key: bin, .. }) => {
+                init_fn.push_str(&String::from_utf8_lossy(bin.as_slice()))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(referenced_vars(&init_fn, vars))
+}
+
+// indices of vars sorted so that every var comes after the vars it references,
+// independent vars keep their declaration order
+pub fn dependency_order(vars: &[Variable]) -> Result<Vec<usize>, ContractError> {
+    let mut deps = vec![];
+    for var in vars {
+        deps.push(get_var_dependencies(var, vars)?);
+    }
+
+    let mut order = vec![];
+    let mut done = vec![false; vars.len()];
+    while order.len() < vars.len() {
+        let next = (0..vars.len()).find(|i| !done[*i] && deps[*i].iter().all(|d| done[*d]));
+        match next {
+            Some(i) => {
+                done[i] = true;
+                order.push(i);
+            }
+            None => {
+                return Err(ContractError::VariableDependencyCycle {
+                    names: (0..vars.len())
+                        .filter(|i| !done[*i])
+                        .map(|i| get_var_name(&vars[i]))
+                        .collect(),
+                })
+            }
+        }
+    }
+    Ok(order)
+}
+
+// Lazily hydrated vars, each var is hydrated at most once
+pub struct VarCache<'a> {
+    vars: Vec<Variable>,
+    order: Vec<usize>,
+    hydrated: Vec<Option<Variable>>,
+    external_inputs: Option<Vec<ExternalInput>>,
+    warp_env: &'a WarpEnv,
+}
+
+impl<'a> VarCache<'a> {
+    pub fn new(
+        vars: Vec<Variable>,
+        external_inputs: Option<Vec<ExternalInput>>,
+        warp_env: &'a WarpEnv,
+    ) -> Result<Self, ContractError> {
+        Ok(VarCache {
+            order: dependency_order(&vars)?,
+            hydrated: vec![None; vars.len()],
+            vars,
+            external_inputs,
+            warp_env,
+        })
+    }
+
+    // hydrates the vars referenced in text and the vars they depend on,
+    // returns all vars hydrated so far
+    pub fn hydrate_referenced(
+        &mut self,
+        deps: Deps,
+        env: Env,
+        text: &str,
+    ) -> Result<Vec<Variable>, ContractError> {
+        let mut required = vec![false; self.vars.len()];
+        for idx in referenced_vars(text, &self.vars) {
+            required[idx] = true;
+        }
+        // dependents come after their dependencies in order
+        for idx in self.order.iter().rev() {
+            if required[*idx] {
+                for dep in get_var_dependencies(&self.vars[*idx], &self.vars)? {
+                    required[dep] = true;
                 }
             }
         }
+
+        for idx in self.order.clone() {
+            if required[idx] && self.hydrated[idx].is_none() {
+                let hydrated_vars = self.hydrated_vars();
+                self.hydrated[idx] = Some(
+                    hydrate_var(
+                        deps,
+                        env.clone(),
+                        self.vars[idx].clone(),
+                        &hydrated_vars,
+                        &self.external_inputs,
+                        self.warp_env,
+                    )
+                    .map_err(|e| e.for_variable(&get_var_name(&self.vars[idx])))?,
+                );
+            }
+        }
+
+        Ok(self.hydrated_vars())
+    }
+
+    fn hydrated_vars(&self) -> Vec<Variable> {
+        self.hydrated.iter().flatten().cloned().collect()
+    }
+}
+
+pub fn has_duplicates(vars: &[Variable]) -> bool {
+    for i in 0..vars.len() {
+        for j in i + 1..vars.len() {
+            if get_var_name(&vars[i]) == get_var_name(&vars[j]) {
+                return true;
+            }
+        }
     }
     false
 }
@@ -1502,16 +1897,40 @@ pub fn string_vars_in_vector(vars: &Vec<Variable>, s: &String) -> bool {
     true
 }
 
-fn get_var_name(var: &Variable) -> String {
+pub fn get_var_name(var: &Variable) -> String {
     match var.clone() {
         Variable::Static(v) => v.name,
         Variable::External(v) => v.name,
         Variable::Query(v) => v.name,
+        Variable::Balance(v) => v.name,
+        Variable::Price(v) => v.name,
+    }
+}
+
+pub fn get_var_kind(var: &Variable) -> &VariableKind {
+    match var {
+        Variable::Static(v) => &v.kind,
+        Variable::External(v) => &v.kind,
+        Variable::Query(v) => &v.kind,
+        Variable::Balance(v) => &v.kind,
+        Variable::Price(v) => &v.kind,
     }
 }
 
 pub fn vars_valid(vars: &Vec<Variable>) -> bool {
     for var in vars {
+        if let VariableKind::Series { capacity } = get_var_kind(var) {
+            let reinitialize = match var {
+                Variable::Static(v) => v.reinitialize,
+                Variable::External(v) => v.reinitialize,
+                Variable::Query(v) => v.reinitialize,
+                Variable::Balance(v) => v.reinitialize,
+                Variable::Price(v) => v.reinitialize,
+            };
+            if *capacity == 0 || *capacity > SERIES_MAX_CAPACITY || reinitialize {
+                return false;
+            }
+        }
         match var {
             Variable::Static(v) => {
                 if v.reinitialize && v.update_fn.is_some() {
@@ -1526,17 +1945,22 @@ pub fn vars_valid(vars: &Vec<Variable>) -> bool {
                             }
                         }
                         VariableKind::Int => {
-                            if i128::from_str(&val).is_err() {
+                            if Int256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
                         VariableKind::Decimal => {
-                            if Decimal256::from_str(&val).is_err() {
+                            if SignedDecimal256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
                         VariableKind::Timestamp => {
-                            if i128::from_str(&val).is_err() {
+                            if Int256::from_str(&val).is_err() {
+                                return false;
+                            }
+                        }
+                        VariableKind::BlockHeight => {
+                            if Int256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
@@ -1556,6 +1980,10 @@ pub fn vars_valid(vars: &Vec<Variable>) -> bool {
                             }
                         }
                         VariableKind::Json => {}
+                        VariableKind::Series { capacity } => match parse_series(&val) {
+                            Ok(samples) if samples.len() <= capacity as usize => {}
+                            _ => return false,
+                        },
                     }
                 }
             }
@@ -1573,17 +2001,22 @@ pub fn vars_valid(vars: &Vec<Variable>) -> bool {
                             }
                         }
                         VariableKind::Int => {
-                            if i128::from_str(&val).is_err() {
+                            if Int256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
                         VariableKind::Decimal => {
-                            if Decimal256::from_str(&val).is_err() {
+                            if SignedDecimal256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
                         VariableKind::Timestamp => {
-                            if i128::from_str(&val).is_err() {
+                            if Int256::from_str(&val).is_err() {
+                                return false;
+                            }
+                        }
+                        VariableKind::BlockHeight => {
+                            if Int256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
@@ -1603,6 +2036,10 @@ pub fn vars_valid(vars: &Vec<Variable>) -> bool {
                             }
                         }
                         VariableKind::Json => {}
+                        VariableKind::Series { capacity } => match parse_series(&val) {
+                            Ok(samples) if samples.len() <= capacity as usize => {}
+                            _ => return false,
+                        },
                     }
                 }
             }
@@ -1619,17 +2056,22 @@ pub fn vars_valid(vars: &Vec<Variable>) -> bool {
                             }
                         }
                         VariableKind::Int => {
-                            if i128::from_str(&val).is_err() {
+                            if Int256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
                         VariableKind::Decimal => {
-                            if Decimal256::from_str(&val).is_err() {
+                            if SignedDecimal256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
                         VariableKind::Timestamp => {
-                            if i128::from_str(&val).is_err() {
+                            if Int256::from_str(&val).is_err() {
+                                return false;
+                            }
+                        }
+                        VariableKind::BlockHeight => {
+                            if Int256::from_str(&val).is_err() {
                                 return false;
                             }
                         }
@@ -1649,9 +2091,33 @@ pub fn vars_valid(vars: &Vec<Variable>) -> bool {
                             }
                         }
                         VariableKind::Json => {}
+                        VariableKind::Series { capacity } => match parse_series(&val) {
+                            Ok(samples) if samples.len() <= capacity as usize => {}
+                            _ => return false,
+                        },
+                    }
+                }
+            }
+            Variable::Balance(v) => {
+                if v.update_fn.is_some() {
+                    return false;
+                }
+                match v.kind {
+                    VariableKind::Uint | VariableKind::Amount => {}
+                    _ => return false,
+                }
+                if let Some(val) = v.value.clone() {
+                    if Uint256::from_str(&val).is_err() {
+                        return false;
                     }
                 }
             }
+            // a preset price would skip the oracle guards on creation
+            Variable::Price(v) => {
+                if v.update_fn.is_some() || v.kind != VariableKind::Decimal || v.value.is_some() {
+                    return false;
+                }
+            }
         }
     }
     true
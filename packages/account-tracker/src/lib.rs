@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint64};
+use cosmwasm_std::{Addr, Coin, Uint128, Uint64};
 
 #[cw_serde]
 pub enum AccountType {
@@ -34,6 +34,7 @@ pub enum ExecuteMsg {
     FreeJobAccount(FreeJobAccountMsg),
     TakeFundingAccount(TakeFundingAccountMsg),
     FreeFundingAccount(FreeFundingAccountMsg),
+    CloseFundingAccount(CloseFundingAccountMsg),
     UpdateConfig(UpdateConfigMsg),
 }
 
@@ -70,6 +71,12 @@ pub struct FreeFundingAccountMsg {
     pub job_id: Uint64,
 }
 
+#[cw_serde]
+pub struct CloseFundingAccountMsg {
+    pub account_owner_addr: String,
+    pub account_addr: String,
+}
+
 #[cw_serde]
 pub struct AddFundingAccountMsg {
     pub account_owner_addr: String,
@@ -89,6 +96,8 @@ pub enum QueryMsg {
     QueryJobAccount(QueryJobAccountMsg),
     #[returns(JobAccountResponse)]
     QueryFirstFreeJobAccount(QueryFirstFreeJobAccountMsg),
+    #[returns(JobAccountBalancesResponse)]
+    QueryJobAccountBalances(QueryJobAccountBalancesMsg),
     #[returns(FundingAccountsResponse)]
     QueryFundingAccounts(QueryFundingAccountsMsg),
     #[returns(FundingAccountResponse)]
@@ -160,6 +169,40 @@ pub struct JobAccountResponse {
     pub job_account: Option<JobAccount>,
 }
 
+#[cw_serde]
+pub struct QueryJobAccountBalancesMsg {
+    pub account_owner_addr: String,
+    // all job accounts when not provided
+    pub account_status: Option<AccountStatus>,
+    // CW20 tokens to query balances of
+    pub cw20_addrs: Vec<String>,
+    pub start_after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[cw_serde]
+pub struct Cw20Balance {
+    pub contract_addr: Addr,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct JobAccountBalances {
+    pub account_addr: Addr,
+    pub account_status: AccountStatus,
+    // ID of the job using the account if taken, of the last job that used it otherwise
+    pub job_id: Uint64,
+    pub native_balances: Vec<Coin>,
+    // only non zero balances of the requested tokens
+    pub cw20_balances: Vec<Cw20Balance>,
+}
+
+#[cw_serde]
+pub struct JobAccountBalancesResponse {
+    pub job_accounts: Vec<JobAccountBalances>,
+    pub total_count: u32,
+}
+
 #[cw_serde]
 pub struct QueryFundingAccountMsg {
     pub account_owner_addr: String,
@@ -1,6 +1,8 @@
-use controller::account::{CwFund, WarpMsg, WarpMsgs};
+use controller::account::{AccountPolicy, CwFund, WarpMsg, WarpMsgs};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin as NativeCoin, Uint64};
+use cosmwasm_std::{Addr, Coin as NativeCoin, Timestamp, Uint64};
+use cw20::Cw20ReceiveMsg;
+use cw721::Cw721ReceiveMsg;
 
 #[cw_serde]
 pub struct Config {
@@ -27,6 +29,60 @@ pub struct InstantiateMsg {
 #[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     WarpMsgs(WarpMsgs),
+    Grant(GrantMsg),
+    Revoke(RevokeMsg),
+    UpdatePolicy(UpdatePolicyMsg),
+    Receive(Cw20ReceiveMsg),
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+// Payload of cw20 Send and cw721 SendNft to an account, an empty payload is a plain deposit
+#[cw_serde]
+pub enum ReceiveHookMsg {
+    Deposit {},
+    // Triggers an execution of the job through the controller, the deposit goes through even if it fails
+    ExecuteJob { job_id: Uint64 },
+}
+
+#[cw_serde]
+pub enum WarpMsgKind {
+    Generic,
+    IbcTransfer,
+    WithdrawAssets,
+}
+
+#[cw_serde]
+pub struct GrantScope {
+    // WarpMsg variants the grantee can execute, WithdrawAssets always withdraws to the owner
+    pub msg_kinds: Vec<WarpMsgKind>,
+    // When provided, Generic msgs must be wasm execute msgs targeting one of these contracts
+    pub contracts: Option<Vec<String>>,
+}
+
+#[cw_serde]
+pub struct Grant {
+    pub grantee: Addr,
+    pub scope: GrantScope,
+    // Never expires when not provided
+    pub expiration: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct GrantMsg {
+    pub grantee: String,
+    pub scope: GrantScope,
+    pub expiration: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct RevokeMsg {
+    pub grantee: String,
+}
+
+#[cw_serde]
+pub struct UpdatePolicyMsg {
+    // Removes the policy when not provided
+    pub policy: Option<AccountPolicy>,
 }
 
 #[derive(QueryResponses)]
@@ -34,6 +90,10 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     QueryConfig(QueryConfigMsg),
+    #[returns(GrantsResponse)]
+    QueryGrants(QueryGrantsMsg),
+    #[returns(PolicyResponse)]
+    QueryPolicy(QueryPolicyMsg),
 }
 
 #[cw_serde]
@@ -44,5 +104,24 @@ pub struct ConfigResponse {
     pub config: Config,
 }
 
+#[cw_serde]
+pub struct QueryGrantsMsg {
+    pub start_after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[cw_serde]
+pub struct GrantsResponse {
+    pub grants: Vec<Grant>,
+}
+
+#[cw_serde]
+pub struct QueryPolicyMsg {}
+
+#[cw_serde]
+pub struct PolicyResponse {
+    pub policy: Option<AccountPolicy>,
+}
+
 #[cw_serde]
 pub struct MigrateMsg {}
@@ -1,9 +1,11 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::CosmosMsg::Stargate;
-use cosmwasm_std::{to_binary, BankMsg, DepsMut, Uint64, WasmMsg};
+use cosmwasm_std::{
+    to_json_binary, BankMsg, DepsMut, DistributionMsg, IbcMsg, StakingMsg, Uint64, WasmMsg,
+};
 use cosmwasm_std::{Addr, CosmosMsg, Deps, Env, Response, StdError, StdResult, Uint128};
 use cw20::{BalanceResponse, Cw20ExecuteMsg};
-use cw721::{Cw721QueryMsg, OwnerOfResponse};
+use cw721::{Cw721ExecuteMsg as Cw721BaseExecuteMsg, Cw721QueryMsg, OwnerOfResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -58,6 +60,25 @@ pub enum AssetInfo {
     Cw721(Addr, String),
 }
 
+// Owner configured firewall of a warp account, checked on job executions and grantee msgs
+// - controller msgs outside of job executions (fee and reward payments, top ups, withdrawals)
+//   are not checked, so a policy on a funding account never blocks its fee payments
+#[cw_serde]
+pub struct AccountPolicy {
+    // Contracts wasm msgs can target, any when not provided
+    pub allowed_contracts: Option<Vec<String>>,
+    // Recipients of assets, approvals and delegations besides the owner, any when not provided
+    pub allowed_recipients: Option<Vec<String>>,
+    // Max amount of an asset spent in a single execution, others are not limited
+    pub max_spend: Vec<SpendLimit>,
+}
+
+#[cw_serde]
+pub struct SpendLimit {
+    pub asset: AssetInfo,
+    pub amount: Uint128,
+}
+
 #[cw_serde]
 pub struct WarpMsgs {
     pub msgs: Vec<WarpMsg>,
@@ -130,8 +151,9 @@ pub fn execute_warp_msgs(
     env: Env,
     data: WarpMsgs,
     owner: &Addr,
+    policy: Option<&AccountPolicy>,
 ) -> Result<Response, StdError> {
-    let msgs = warp_msgs_to_cosmos_msgs(deps.as_ref(), env, data.msgs, owner).unwrap();
+    let msgs = warp_msgs_to_cosmos_msgs(deps.as_ref(), env, data.msgs, owner, policy)?;
 
     let mut resp = Response::new()
         .add_messages(msgs)
@@ -149,7 +171,12 @@ pub fn warp_msgs_to_cosmos_msgs(
     env: Env,
     msgs: Vec<WarpMsg>,
     owner: &Addr,
+    policy: Option<&AccountPolicy>,
 ) -> Result<Vec<CosmosMsg>, StdError> {
+    if let Some(policy) = policy {
+        validate_policy(&msgs, policy, owner)?;
+    }
+
     let result = msgs
         .into_iter()
         .flat_map(|msg| -> Vec<CosmosMsg> {
@@ -168,6 +195,216 @@ pub fn warp_msgs_to_cosmos_msgs(
     Ok(result)
 }
 
+fn policy_violation(msg: &str) -> StdError {
+    StdError::generic_err(format!("Account policy violation: {}", msg))
+}
+
+// - WithdrawAssets msgs only pay out to the owner and are always allowed
+// - recipients are checked on every msg moving assets or rights out of the account: bank, cw20
+//   and cw721 transfers and approvals, ibc transfers, delegations and the reward withdraw address
+// - msgs that aren't handled below (e.g. Stargate, Gov, Custom) can't be inspected and are rejected
+pub fn validate_policy(msgs: &[WarpMsg], policy: &AccountPolicy, owner: &Addr) -> StdResult<()> {
+    let check_contract = |contract_addr: &String| match &policy.allowed_contracts {
+        Some(contracts) if !contracts.contains(contract_addr) => Err(policy_violation(&format!(
+            "contract {} not allowed",
+            contract_addr
+        ))),
+        _ => Ok(()),
+    };
+    let check_recipient = |recipient: &String| match &policy.allowed_recipients {
+        Some(recipients) if recipient != owner.as_str() && !recipients.contains(recipient) => Err(
+            policy_violation(&format!("recipient {} not allowed", recipient)),
+        ),
+        _ => Ok(()),
+    };
+
+    let mut spent: Vec<SpendLimit> = vec![];
+    let mut spend = |asset: AssetInfo, amount: Uint128| -> StdResult<()> {
+        match spent.iter_mut().find(|s| s.asset == asset) {
+            Some(s) => s.amount = s.amount.checked_add(amount)?,
+            None => spent.push(SpendLimit { asset, amount }),
+        }
+        Ok(())
+    };
+
+    for msg in msgs {
+        match msg {
+            WarpMsg::WithdrawAssets(_) => {}
+            WarpMsg::IbcTransfer(msg) => {
+                check_recipient(&msg.transfer_msg.receiver)?;
+                if let Some(token) = &msg.transfer_msg.token {
+                    spend(
+                        AssetInfo::Native(token.denom.clone()),
+                        token.amount.parse::<Uint128>()?,
+                    )?;
+                }
+            }
+            WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Send { to_address, amount })) => {
+                check_recipient(to_address)?;
+                // sends to the owner are not spent
+                if to_address != owner.as_str() {
+                    for coin in amount {
+                        spend(AssetInfo::Native(coin.denom.clone()), coin.amount)?;
+                    }
+                }
+            }
+            WarpMsg::Generic(CosmosMsg::Bank(BankMsg::Burn { amount })) => {
+                for coin in amount {
+                    spend(AssetInfo::Native(coin.denom.clone()), coin.amount)?;
+                }
+            }
+            WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            })) => {
+                check_contract(contract_addr)?;
+                for coin in funds {
+                    spend(AssetInfo::Native(coin.denom.clone()), coin.amount)?;
+                }
+                if let Ok(cw20_msg) = cosmwasm_std::from_json::<Cw20ExecuteMsg>(msg) {
+                    validate_cw20_policy(
+                        cw20_msg,
+                        contract_addr,
+                        owner,
+                        &check_recipient,
+                        &mut spend,
+                    )?;
+                } else if let Ok(cw721_msg) = cosmwasm_std::from_json::<Cw721BaseExecuteMsg>(msg) {
+                    validate_cw721_policy(cw721_msg, contract_addr, &check_recipient, &mut spend)?;
+                }
+            }
+            WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }))
+            | WarpMsg::Generic(CosmosMsg::Wasm(WasmMsg::Instantiate2 { funds, .. })) => {
+                if policy.allowed_contracts.is_some() {
+                    return Err(policy_violation("contract instantiation not allowed"));
+                }
+                for coin in funds {
+                    spend(AssetInfo::Native(coin.denom.clone()), coin.amount)?;
+                }
+            }
+            WarpMsg::Generic(CosmosMsg::Wasm(
+                WasmMsg::Migrate { contract_addr, .. }
+                | WasmMsg::UpdateAdmin { contract_addr, .. }
+                | WasmMsg::ClearAdmin { contract_addr },
+            )) => check_contract(contract_addr)?,
+            WarpMsg::Generic(CosmosMsg::Ibc(IbcMsg::Transfer {
+                to_address, amount, ..
+            })) => {
+                check_recipient(to_address)?;
+                spend(AssetInfo::Native(amount.denom.clone()), amount.amount)?;
+            }
+            WarpMsg::Generic(CosmosMsg::Staking(StakingMsg::Delegate { validator, amount })) => {
+                check_recipient(validator)?;
+                spend(AssetInfo::Native(amount.denom.clone()), amount.amount)?;
+            }
+            WarpMsg::Generic(CosmosMsg::Staking(StakingMsg::Redelegate {
+                dst_validator, ..
+            })) => check_recipient(dst_validator)?,
+            // undelegated funds and rewards return to the account
+            WarpMsg::Generic(CosmosMsg::Staking(StakingMsg::Undelegate { .. }))
+            | WarpMsg::Generic(CosmosMsg::Distribution(
+                DistributionMsg::WithdrawDelegatorReward { .. },
+            )) => {}
+            WarpMsg::Generic(CosmosMsg::Distribution(DistributionMsg::SetWithdrawAddress {
+                address,
+            })) => check_recipient(address)?,
+            WarpMsg::Generic(msg) => {
+                return Err(policy_violation(&format!(
+                    "msg {} not allowed",
+                    serde_json_wasm::to_string(msg)
+                        .map_err(|e| StdError::generic_err(e.to_string()))?
+                )));
+            }
+        }
+    }
+
+    for limit in &policy.max_spend {
+        if let Some(s) = spent.iter().find(|s| s.asset == limit.asset) {
+            if s.amount > limit.amount {
+                return Err(policy_violation(&format!(
+                    "spending {} of {:?} exceeds limit of {}",
+                    s.amount, limit.asset, limit.amount
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// cw20 token amounts leaving the account, allowances count as spent
+fn validate_cw20_policy(
+    msg: Cw20ExecuteMsg,
+    contract_addr: &str,
+    owner: &Addr,
+    check_recipient: &dyn Fn(&String) -> StdResult<()>,
+    spend: &mut dyn FnMut(AssetInfo, Uint128) -> StdResult<()>,
+) -> StdResult<()> {
+    let asset = AssetInfo::Cw20(Addr::unchecked(contract_addr));
+    match msg {
+        Cw20ExecuteMsg::Transfer { recipient, amount }
+        | Cw20ExecuteMsg::Send {
+            contract: recipient,
+            amount,
+            ..
+        } => {
+            check_recipient(&recipient)?;
+            if recipient != owner.as_str() {
+                spend(asset, amount)?;
+            }
+        }
+        Cw20ExecuteMsg::IncreaseAllowance {
+            spender, amount, ..
+        } => {
+            check_recipient(&spender)?;
+            spend(asset, amount)?;
+        }
+        Cw20ExecuteMsg::Burn { amount } => spend(asset, amount)?,
+        Cw20ExecuteMsg::TransferFrom { recipient, .. }
+        | Cw20ExecuteMsg::SendFrom {
+            contract: recipient,
+            ..
+        }
+        | Cw20ExecuteMsg::Mint { recipient, .. } => check_recipient(&recipient)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+// cw721 tokens leaving the account, approvals count as spent
+fn validate_cw721_policy(
+    msg: Cw721BaseExecuteMsg,
+    contract_addr: &str,
+    check_recipient: &dyn Fn(&String) -> StdResult<()>,
+    spend: &mut dyn FnMut(AssetInfo, Uint128) -> StdResult<()>,
+) -> StdResult<()> {
+    let asset = |token_id: String| AssetInfo::Cw721(Addr::unchecked(contract_addr), token_id);
+    match msg {
+        Cw721BaseExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        }
+        | Cw721BaseExecuteMsg::SendNft {
+            contract: recipient,
+            token_id,
+            ..
+        }
+        | Cw721BaseExecuteMsg::Approve {
+            spender: recipient,
+            token_id,
+            ..
+        } => {
+            check_recipient(&recipient)?;
+            spend(asset(token_id), Uint128::one())?;
+        }
+        Cw721BaseExecuteMsg::ApproveAll { operator, .. } => check_recipient(&operator)?,
+        Cw721BaseExecuteMsg::Burn { token_id } => spend(asset(token_id), Uint128::one())?,
+        Cw721BaseExecuteMsg::Revoke { .. } | Cw721BaseExecuteMsg::RevokeAll { .. } => {}
+    }
+    Ok(())
+}
+
 fn extract_messages(resp: Response) -> Vec<CosmosMsg> {
     resp.messages
         .into_iter()
@@ -256,7 +493,7 @@ fn withdraw_asset_cw20(
     let res = if amount.balance > Uint128::zero() {
         Some(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: owner.to_string(),
                 amount: amount.balance,
             })?,
@@ -286,7 +523,7 @@ fn withdraw_asset_cw721(
     let res = if owner_query.owner == *owner {
         Some(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: token.to_string(),
-            msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+            msg: to_json_binary(&Cw721BaseExecuteMsg::TransferNft {
                 recipient: owner.to_string(),
                 token_id: token_id.to_string(),
             })?,
@@ -302,21 +539,20 @@ fn withdraw_asset_cw721(
 pub fn ibc_transfer(env: Env, data: IbcTransferMsg) -> Result<Response, StdError> {
     let mut transfer_msg = data.transfer_msg.clone();
 
-    if data.timeout_block_delta.is_some() && data.transfer_msg.timeout_block.is_some() {
-        let block = transfer_msg.timeout_block.unwrap();
+    if let (Some(timeout_block_delta), Some(block)) =
+        (data.timeout_block_delta, transfer_msg.timeout_block.clone())
+    {
         transfer_msg.timeout_block = Some(TimeoutBlock {
             revision_number: Some(block.revision_number()),
-            revision_height: Some(env.block.height + data.timeout_block_delta.unwrap()),
+            revision_height: Some(env.block.height + timeout_block_delta),
         })
     }
 
-    if data.timeout_timestamp_seconds_delta.is_some() {
+    if let Some(timeout_timestamp_seconds_delta) = data.timeout_timestamp_seconds_delta {
         transfer_msg.timeout_timestamp = Some(
             env.block
                 .time
-                .plus_seconds(
-                    env.block.time.seconds() + data.timeout_timestamp_seconds_delta.unwrap(),
-                )
+                .plus_seconds(env.block.time.seconds() + timeout_timestamp_seconds_delta)
                 .nanos(),
         );
     }
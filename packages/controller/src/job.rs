@@ -35,6 +35,41 @@ pub struct Job {
     // For withdrawing assets on each job execution (recurring jobs),
     // use WithdrawAssets warp msg
     pub assets_to_withdraw: Vec<AssetInfo>,
+    // Number of executions so far, including previous iterations of a recurring job.
+    // Defaults to 0 for jobs stored before it was tracked
+    #[serde(default)]
+    pub execution_count: Uint64,
+    // Max amount of reward + fees the job draws from its funding account, across all iterations
+    #[serde(default)]
+    pub funding_budget: Option<Uint128>,
+    // Reward + fees drawn from the funding account so far, across all iterations
+    #[serde(default)]
+    pub funding_spent: Uint128,
+    // Account the funding account is topped up from when it can't pay for the next iteration
+    #[serde(default)]
+    pub top_up_source: Option<TopUpSource>,
+    // Recurring job is paused instead of terminated when the funding account can't pay for the next iteration
+    #[serde(default)]
+    pub grace_mode: bool,
+    // Set while status is Paused
+    #[serde(default)]
+    pub pause_reason: Option<PauseReason>,
+}
+
+#[cw_serde]
+pub struct TopUpSource {
+    // Another funding account of the job owner
+    pub account: Addr,
+    // Max amount of fee denom pulled from it, decreased on every top up
+    pub allowance: Uint128,
+}
+
+#[cw_serde]
+pub enum PauseReason {
+    // Funding account couldn't pay reward + fees of the iteration, resumed once refunded
+    InsufficientFunds,
+    // Paused by the job owner, paused time doesn't count toward eviction
+    Owner { paused_at: Uint64 },
 }
 
 #[cw_serde]
@@ -50,6 +85,7 @@ pub enum JobStatus {
     Failed,
     Cancelled,
     Evicted,
+    Paused,
 }
 
 #[cw_serde]
@@ -84,6 +120,17 @@ pub struct CreateJobMsg {
     // - required for recurring jobs
     // - optionally provided for one time jobs
     pub funding_account: Option<Addr>,
+    // Max amount of reward + fees the job draws from its funding account, across all iterations.
+    // Recurrence stops once the next iteration would exceed it
+    pub funding_budget: Option<Uint128>,
+    // Another funding account of the job owner, pulled from when the funding account can't pay for the next iteration
+    pub top_up_source: Option<TopUpSource>,
+    // Pause the recurring job instead of terminating it when the funding account can't pay for the next iteration
+    pub grace_mode: Option<bool>,
+    // Free job account of the job owner to run the job from, defaults to the first free one
+    pub job_account: Option<Addr>,
+    // Always instantiate a new job account, can't be combined with job_account
+    pub fresh_job_account: Option<bool>,
 }
 
 #[cw_serde]
@@ -105,6 +152,16 @@ pub struct ExecuteJobMsg {
     pub external_inputs: Option<Vec<ExternalInput>>,
 }
 
+#[cw_serde]
+pub struct PauseJobMsg {
+    pub id: Uint64,
+}
+
+#[cw_serde]
+pub struct ResumeJobMsg {
+    pub id: Uint64,
+}
+
 #[cw_serde]
 pub struct EvictJobMsg {
     pub id: Uint64,
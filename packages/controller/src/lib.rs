@@ -1,9 +1,10 @@
+use crate::account::AssetInfo;
 use crate::job::{
-    CreateJobMsg, DeleteJobMsg, EvictJobMsg, ExecuteJobMsg, JobResponse, JobsResponse, QueryJobMsg,
-    QueryJobsMsg, UpdateJobMsg,
+    CreateJobMsg, DeleteJobMsg, EvictJobMsg, ExecuteJobMsg, JobResponse, JobsResponse, PauseJobMsg,
+    QueryJobMsg, QueryJobsMsg, ResumeJobMsg, UpdateJobMsg,
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128, Uint64};
+use cosmwasm_std::{Addr, Coin, Uint128, Uint64};
 
 pub mod account;
 pub mod job;
@@ -79,6 +80,8 @@ pub enum ExecuteMsg {
     UpdateJob(UpdateJobMsg),
     ExecuteJob(ExecuteJobMsg),
     EvictJob(EvictJobMsg),
+    PauseJob(PauseJobMsg),
+    ResumeJob(ResumeJobMsg),
 
     UpdateConfig(UpdateConfigMsg),
 
@@ -88,6 +91,10 @@ pub enum ExecuteMsg {
     MigrateFinishedJobs(MigrateJobsMsg),
 
     CreateFundingAccount(CreateFundingAccountMsg),
+    WithdrawFromFundingAccount(WithdrawFromFundingAccountMsg),
+    CloseFundingAccount(CloseFundingAccountMsg),
+
+    SweepAccounts(SweepAccountsMsg),
 }
 
 #[cw_serde]
@@ -128,6 +135,27 @@ pub struct MigrateJobsMsg {
 #[cw_serde]
 pub struct CreateFundingAccountMsg {}
 
+#[cw_serde]
+pub struct WithdrawFromFundingAccountMsg {
+    pub account: String,
+    // assets are withdrawn to the funding account owner
+    pub assets: Vec<AssetInfo>,
+}
+
+#[cw_serde]
+pub struct CloseFundingAccountMsg {
+    // native balances are withdrawn to the owner, withdraw cw20 / cw721 assets beforehand
+    pub account: String,
+}
+
+#[cw_serde]
+pub struct SweepAccountsMsg {
+    // free job accounts of the sender
+    pub accounts: Vec<String>,
+    // assets withdrawn from each account to the sender
+    pub assets: Vec<AssetInfo>,
+}
+
 //query
 #[derive(QueryResponses)]
 #[cw_serde]
@@ -142,6 +170,12 @@ pub enum QueryMsg {
 
     #[returns(StateResponse)]
     QueryState(QueryStateMsg),
+
+    #[returns(FundingAccountSummaryResponse)]
+    QueryFundingAccountSummary(QueryFundingAccountSummaryMsg),
+
+    #[returns(AccountAddressResponse)]
+    QueryAccountAddress(QueryAccountAddressMsg),
 }
 
 #[cw_serde]
@@ -160,5 +194,37 @@ pub struct StateResponse {
     pub state: State,
 }
 
+#[cw_serde]
+pub struct QueryAccountAddressMsg {
+    pub owner: String,
+    // Index of the next account created for the owner when not provided
+    pub index: Option<Uint64>,
+}
+
+#[cw_serde]
+pub struct AccountAddressResponse {
+    pub account: Addr,
+    pub index: Uint64,
+}
+
+#[cw_serde]
+pub struct QueryFundingAccountSummaryMsg {
+    pub account: String,
+}
+
+#[cw_serde]
+pub struct FundingAccountSummaryResponse {
+    pub account: Addr,
+    pub owner: Addr,
+    // native balances only
+    pub balances: Vec<Coin>,
+    // pending jobs currently funded by the account
+    pub taken_by_job_ids: Vec<Uint64>,
+    // reward + fees of the next execution of all attached jobs, at current rates
+    pub cost_per_execution: Uint128,
+    // executions the fee denom balance covers, none when no job is attached
+    pub runway: Option<Uint64>,
+}
+
 #[cw_serde]
 pub struct MigrateMsg {}
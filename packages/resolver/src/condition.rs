@@ -1,7 +1,7 @@
 use std::str;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal256, Uint256, Uint64};
+use cosmwasm_std::{Int256, SignedDecimal256, Uint256, Uint64};
 
 #[cw_serde]
 pub enum Condition {
@@ -21,7 +21,7 @@ pub struct GenExpr<Type, Op> {
 #[cw_serde]
 pub struct TimeExpr {
     pub comparator: Uint64,
-    pub op: TimeOp, //tmp: fix this post-comp
+    pub op: NumOp,
 }
 
 #[cw_serde]
@@ -35,11 +35,62 @@ pub enum StringValue<T> {
     Simple(T),
     Ref(String),
     Env(StringEnvValue),
+    Concat(Vec<StringValue<T>>),
+    Substring(StringSubstringValue<T>),
+    Replace(StringReplaceValue<T>),
+    Fn(StringFnValue<T>),
+    Format(StringFormatValue),
+}
+
+// start and end are char indices, end is exclusive and defaults to the end of the string
+#[cw_serde]
+pub struct StringSubstringValue<T> {
+    pub value: Box<StringValue<T>>,
+    pub start: u32,
+    pub end: Option<u32>,
+}
+
+// replaces all occurrences of `from` with `to`
+#[cw_serde]
+pub struct StringReplaceValue<T> {
+    pub value: Box<StringValue<T>>,
+    pub from: Box<StringValue<T>>,
+    pub to: Box<StringValue<T>>,
+}
+
+#[cw_serde]
+pub struct StringFnValue<T> {
+    pub op: StringFnOp,
+    pub right: Box<StringValue<T>>,
+}
+
+#[cw_serde]
+pub enum StringFnOp {
+    ToLower,
+    ToUpper,
+    Len,          // char count, as a string
+    Bech32Prefix, // human readable part of a bech32 address
+}
+
+// numeric values formatted as strings
+// - decimal precision truncates (or pads) to the given number of fractional digits, up to 18
+#[cw_serde]
+pub enum StringFormatValue {
+    Uint(NumValue<Uint256, NumExprOp, IntFnOp>),
+    Int(NumValue<Int256, NumExprOp, IntFnOp>),
+    Decimal {
+        value: NumValue<SignedDecimal256, NumExprOp, DecimalFnOp>,
+        precision: Option<u32>,
+    },
 }
 
 #[cw_serde]
 pub enum StringEnvValue {
     WarpAccountAddr,
+    ChainId,
+    JobId,
+    JobOwner,
+    FundingAccountAddr,
 }
 
 #[cw_serde]
@@ -49,12 +100,53 @@ pub enum NumValue<T, ExprOp, FnOp> {
     Ref(String),
     Fn(NumFnValue<T, ExprOp, FnOp>),
     Env(NumEnvValue),
+    Duration(Duration), // in seconds, e.g. time + 1 day
+    Series(SeriesValue),
+}
+
+// aggregate over the samples of a Series variable
+#[cw_serde]
+pub struct SeriesValue {
+    pub op: SeriesFnOp,
+    pub series: String, // ref
+}
+
+#[cw_serde]
+pub enum SeriesFnOp {
+    Avg,
+    Min,
+    Max,
+    Last(u32), // n-th most recent sample, last(1) is the latest
+}
+
+#[cw_serde]
+pub enum Duration {
+    Seconds(u64),
+    Minutes(u64),
+    Hours(u64),
+    Days(u64),
+    Weeks(u64),
+}
+
+impl Duration {
+    pub fn seconds(&self) -> u64 {
+        match self {
+            Duration::Seconds(n) => *n,
+            Duration::Minutes(n) => n.saturating_mul(60),
+            Duration::Hours(n) => n.saturating_mul(3_600),
+            Duration::Days(n) => n.saturating_mul(86_400),
+            Duration::Weeks(n) => n.saturating_mul(604_800),
+        }
+    }
 }
 
 #[cw_serde]
 pub enum NumEnvValue {
     Time,
     BlockHeight,
+    JobId,
+    ExecutionCount,
+    AccountBalance(String), // warp account balance of the given native denom
 }
 
 #[cw_serde]
@@ -98,8 +190,8 @@ pub enum IntFnOp {
 pub enum Expr {
     String(GenExpr<StringValue<String>, StringOp>),
     Uint(GenExpr<NumValue<Uint256, NumExprOp, IntFnOp>, NumOp>),
-    Int(GenExpr<NumValue<i128, NumExprOp, IntFnOp>, NumOp>),
-    Decimal(GenExpr<NumValue<Decimal256, NumExprOp, DecimalFnOp>, NumOp>),
+    Int(GenExpr<NumValue<Int256, NumExprOp, IntFnOp>, NumOp>),
+    Decimal(GenExpr<NumValue<SignedDecimal256, NumExprOp, DecimalFnOp>, NumOp>),
     Timestamp(TimeExpr),
     BlockHeight(BlockExpr),
     Bool(String), //ref
@@ -120,12 +212,6 @@ pub enum NumOp {
     Lte,
 }
 
-#[cw_serde]
-pub enum TimeOp {
-    Lt,
-    Gt,
-}
-
 #[cw_serde]
 pub enum StringOp {
     StartsWith,
@@ -133,4 +219,6 @@ pub enum StringOp {
     Contains,
     Eq,
     Neq,
+    Matches,     // right is a regex pattern, matched anywhere in left unless anchored
+    IsValidAddr, // left is a valid address, right is the expected bech32 prefix (empty for any)
 }
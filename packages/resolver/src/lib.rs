@@ -6,7 +6,7 @@ use controller::{
     job::{Execution, ExternalInput, JobStatus},
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, QueryRequest};
+use cosmwasm_std::{Addr, QueryRequest, Uint64};
 #[cw_serde]
 pub struct InstantiateMsg {}
 
@@ -32,10 +32,16 @@ pub enum QueryMsg {
     QueryHydrateVars(QueryHydrateVarsMsg),
     #[returns(bool)]
     QueryResolveCondition(QueryResolveConditionMsg),
+    #[returns(ResolveConditionResponse)]
+    QueryResolveConditionV2(QueryResolveConditionMsg),
     #[returns(String)]
     QueryApplyVarFn(QueryApplyVarFnMsg),
     #[returns(Vec<WarpMsg>)]
     QueryHydrateMsgs(QueryHydrateMsgsMsg),
+    #[returns(ResolveExecutionResponse)]
+    QueryResolveExecution(QueryResolveExecutionMsg),
+    #[returns(LintJobResponse)]
+    QueryLintJob(QueryLintJobMsg),
 }
 
 #[cw_serde]
@@ -63,6 +69,7 @@ pub struct ExecuteHydrateVarsMsg {
     pub vars: String,
     pub external_inputs: Option<Vec<ExternalInput>>,
     pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
 }
 
 #[cw_serde]
@@ -70,6 +77,7 @@ pub struct ExecuteResolveConditionMsg {
     pub condition: String,
     pub vars: String,
     pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
 }
 
 #[cw_serde]
@@ -77,6 +85,7 @@ pub struct ExecuteApplyVarFnMsg {
     pub vars: String,
     pub status: JobStatus,
     pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
 }
 
 #[cw_serde]
@@ -104,6 +113,10 @@ pub struct QueryHydrateVarsMsg {
     pub vars: String,
     pub external_inputs: Option<Vec<ExternalInput>>,
     pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
+    // skips max_age_seconds of price variables, used when hydrating for a job's next iteration
+    // whose prices are queried and checked again once it executes
+    pub skip_price_staleness: Option<bool>,
 }
 
 #[cw_serde]
@@ -111,6 +124,7 @@ pub struct QueryResolveConditionMsg {
     pub condition: String,
     pub vars: String,
     pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
 }
 
 #[cw_serde]
@@ -118,6 +132,114 @@ pub struct QueryApplyVarFnMsg {
     pub vars: String,
     pub status: JobStatus,
     pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
+}
+
+// Resolves executions top-down, vars are only hydrated when referenced by the evaluated
+// condition or by the msgs of the matched execution
+// - var hydration errors fail the query, they may be temporary (e.g. a failing query, a stale price)
+#[cw_serde]
+pub struct QueryResolveExecutionMsg {
+    pub vars: String,
+    pub executions: Vec<Execution>,
+    pub external_inputs: Option<Vec<ExternalInput>>,
+    pub warp_account_addr: Option<String>,
+    pub job_env: Option<JobEnv>,
+}
+
+#[cw_serde]
+pub struct ResolveExecutionResponse {
+    // index of the matched execution, or of the execution that failed to resolve
+    pub index: Option<u64>,
+    // hydrated msgs of the matched execution
+    pub msgs: Option<Vec<WarpMsg>>,
+    // invalid vars, condition or msgs error, index is None for invalid vars
+    pub error: Option<ResolverError>,
+}
+
+// Condition resolution result, errors are returned instead of failing the query
+#[cw_serde]
+pub enum ResolveConditionResponse {
+    Ok(bool),
+    Err(ResolverError),
+}
+
+#[cw_serde]
+pub enum ResolverErrorKind {
+    // malformed condition, vars or msgs
+    Parse,
+    VariableNotFound,
+    InvalidVariables,
+    Hydration,
+    Condition,
+    Function,
+    Msg,
+    // query or value conversion error
+    Std,
+    Other,
+}
+
+#[cw_serde]
+pub struct ResolverError {
+    pub kind: ResolverErrorKind,
+    // name of the variable the error originates from
+    pub variable: Option<String>,
+    // location of the failing input, e.g. executions[1].condition
+    pub path: Option<String>,
+    pub message: String,
+}
+
+#[cw_serde]
+pub struct QueryLintJobMsg {
+    pub terminate_condition: Option<String>,
+    pub vars: String,
+    pub executions: Vec<Execution>,
+}
+
+#[cw_serde]
+pub struct LintJobResponse {
+    pub issues: Vec<LintIssue>,
+}
+
+// Errors fail at runtime, warnings point at jobs that likely do not behave as intended
+#[cw_serde]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[cw_serde]
+pub enum LintKind {
+    // unparsable input or reference to a missing variable
+    Invalid,
+    // Ref to a variable of a kind the expression cannot use
+    RefKindMismatch,
+    InitFnKindMismatch,
+    UpdateFnKindMismatch,
+    UnusedVariable,
+    // an earlier execution is always true
+    UnreachableExecution,
+    NeverTrueCondition,
+}
+
+#[cw_serde]
+pub struct LintIssue {
+    pub severity: LintSeverity,
+    pub kind: LintKind,
+    // location in the job, e.g. executions[1].condition or vars[0].update_fn.on_success
+    pub path: String,
+    pub variable: Option<String>,
+    pub message: String,
+}
+
+// job metadata used to resolve Env values
+#[cw_serde]
+pub struct JobEnv {
+    pub job_id: Uint64,
+    pub job_owner: Addr,
+    pub funding_account_addr: Option<Addr>,
+    // number of executions of the job so far, including previous iterations of recurring jobs
+    pub execution_count: Uint64,
 }
 
 #[cw_serde]
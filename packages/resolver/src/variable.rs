@@ -1,7 +1,12 @@
 use std::collections::HashMap;
+use std::fmt;
 
+use controller::account::AssetInfo;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal256, QueryRequest, Uint256};
+use cosmwasm_schema::schemars::JsonSchema;
+use cosmwasm_schema::serde::de::{self, Visitor};
+use cosmwasm_schema::serde::{Deserialize, Deserializer, Serialize};
+use cosmwasm_std::{Decimal256, Int256, QueryRequest, SignedDecimal256, Uint256};
 
 use crate::condition::StringValue;
 
@@ -14,10 +19,69 @@ pub enum VariableKind {
     Int,
     Decimal,
     Timestamp,
+    BlockHeight,
     Bool,
     Amount,
     Asset,
     Json,
+    // ring buffer of the last `capacity` decimal samples, stored as a JSON array
+    // - init_fn sets the first sample, Decimal update_fns append one on every execution
+    // - capacity is between 1 and SERIES_MAX_CAPACITY, reinitialize is rejected as it would
+    //   reset the history
+    Series { capacity: u32 },
+}
+
+pub const SERIES_MAX_CAPACITY: u32 = 100;
+
+// how a variable value is injected into msgs and queries
+// - the JSON representation is the value as injected without encoding (e.g. quoted for Uint,
+//   unquoted for Int and Json)
+#[derive(Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", crate = "::cosmwasm_schema::serde")]
+#[schemars(crate = "::cosmwasm_schema::schemars")]
+pub enum Encoding {
+    None,
+    Base64,     // base64 of the raw value
+    Hex,        // hex of the raw value
+    JsonString, // JSON representation escaped into a JSON string
+    Base64Json, // base64 of the JSON representation
+}
+
+const ENCODINGS: &[&str] = &["none", "base64", "hex", "json_string", "base64_json"];
+
+// also accepts the legacy `encode` bool, true being Base64
+impl<'de> Deserialize<'de> for Encoding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(EncodingVisitor)
+    }
+}
+
+struct EncodingVisitor;
+
+impl<'de> Visitor<'de> for EncodingVisitor {
+    type Value = Encoding;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an encoding or a bool")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Encoding, E> {
+        match value {
+            true => Ok(Encoding::Base64),
+            false => Ok(Encoding::None),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Encoding, E> {
+        match value {
+            "none" => Ok(Encoding::None),
+            "base64" => Ok(Encoding::Base64),
+            "hex" => Ok(Encoding::Hex),
+            "json_string" => Ok(Encoding::JsonString),
+            "base64_json" => Ok(Encoding::Base64Json),
+            _ => Err(E::unknown_variant(value, ENCODINGS)),
+        }
+    }
 }
 
 #[cw_serde]
@@ -25,6 +89,17 @@ pub enum VariableValue {
     Static(String),
     Query(QueryExpr),
     External(ExternalExpr),
+    Balance(BalanceExpr),
+}
+
+// Balance of an address, resolved without a raw query
+// - Native: bank balance of the denom
+// - Cw20: token balance
+// - Cw721: 1 if the address owns the token, 0 otherwise
+#[cw_serde]
+pub struct BalanceExpr {
+    pub address: StringValue<String>,
+    pub asset: AssetInfo,
 }
 
 #[cw_serde]
@@ -72,12 +147,27 @@ pub enum FnOp {
 #[cw_serde]
 pub enum FnValue {
     Uint(NumValue<Uint256, NumExprOp, IntFnOp>),
-    Int(NumValue<i128, NumExprOp, IntFnOp>),
-    Decimal(NumValue<Decimal256, NumExprOp, DecimalFnOp>),
-    Timestamp(NumValue<i128, NumExprOp, IntFnOp>),
-    BlockHeight(NumValue<i128, NumExprOp, IntFnOp>),
+    Int(NumValue<Int256, NumExprOp, IntFnOp>),
+    Decimal(NumValue<SignedDecimal256, NumExprOp, DecimalFnOp>),
+    Timestamp(NumValue<Int256, NumExprOp, IntFnOp>),
+    BlockHeight(NumValue<Int256, NumExprOp, IntFnOp>),
     Bool(String), //ref
     String(StringValue<String>),
+    Json(JsonValue),
+}
+
+// Json variables composed from other values
+// - object / array entries can be any FnValue (Uint, Decimal and String resolve to JSON strings,
+//   Int, Timestamp and BlockHeight to JSON numbers (strings beyond i128), Bool to a JSON boolean)
+// - merge applies each entry as a JSON merge patch (RFC 7396) on top of the previous ones
+// - object keys are written in sorted order, the declaration order of entries isn't kept
+#[cw_serde]
+pub enum JsonValue {
+    Simple(String), // raw JSON document
+    Ref(String),
+    Object(Vec<(String, FnValue)>),
+    Array(Vec<FnValue>),
+    Merge(Vec<JsonValue>),
 }
 
 #[cw_serde]
@@ -88,18 +178,22 @@ pub struct UpdateFn {
 
 // Variable is specified as a reference value (string) in form of $warp.variable.{name}
 // - variables are supplied along with the input (msg, query, template)
+// - in msgs, Json and Series values must be strings (e.g. encoded) to replace a reference,
+//   {"$warp.json": "$warp.variable.{name}"} is replaced by the value as JSON of any type
 #[cw_serde]
 pub enum Variable {
     Static(StaticVariable),
     External(ExternalVariable),
     Query(QueryVariable),
+    Balance(BalanceVariable),
+    Price(PriceVariable),
 }
 
 #[cw_serde]
 pub struct StaticVariable {
     pub kind: VariableKind,
     pub name: String,
-    pub encode: bool,
+    pub encode: Encoding,
     pub init_fn: FnValue,
     pub reinitialize: bool,
     pub value: Option<String>, //none if uninitialized
@@ -110,7 +204,7 @@ pub struct StaticVariable {
 pub struct ExternalVariable {
     pub kind: VariableKind,
     pub name: String,
-    pub encode: bool,
+    pub encode: Encoding,
     pub init_fn: ExternalExpr,
     pub reinitialize: bool,
     pub value: Option<String>, //none if uninitialized
@@ -121,9 +215,104 @@ pub struct ExternalVariable {
 pub struct QueryVariable {
     pub kind: VariableKind,
     pub name: String,
-    pub encode: bool,
+    pub encode: Encoding,
     pub init_fn: QueryExpr,
     pub reinitialize: bool,
     pub value: Option<String>, //none if uninitialized
     pub update_fn: Option<UpdateFn>,
 }
+
+// kind is either Uint or Amount
+// - the balance is queried on every hydration, reinitialize has no effect
+#[cw_serde]
+pub struct BalanceVariable {
+    pub kind: VariableKind,
+    pub name: String,
+    pub encode: Encoding,
+    pub init_fn: BalanceExpr,
+    pub reinitialize: bool,
+    pub value: Option<String>, //none if uninitialized
+    pub update_fn: Option<UpdateFn>,
+}
+
+// Price of base denominated in quote, read from an oracle
+// - hydration fails when the price is older than max_age_seconds, so the job is not executed,
+//   the age isn't checked when hydrating for a job's next iteration
+// - the price is queried on every hydration, reinitialize has no effect and value can't be preset
+// - max_confidence is the maximum confidence interval relative to the price (e.g. 0.01 for 1%),
+//   only checked for sources that report one (Pyth, Custom)
+#[cw_serde]
+pub struct PriceExpr {
+    pub source: PriceSource,
+    pub base: String,
+    pub quote: String,
+    pub max_age_seconds: u64,
+    pub max_confidence: Option<Decimal256>,
+}
+
+#[cw_serde]
+pub enum PriceSource {
+    // base is the price feed id, quote is informational
+    Pyth {
+        contract_addr: String,
+    },
+    // base and quote are band symbols
+    Band {
+        contract_addr: String,
+    },
+    // pool implementing PoolOracleQueryMsg, price is time weighted over the last window_seconds
+    PoolTwap {
+        contract_addr: String,
+        window_seconds: u64,
+    },
+    // contract implementing PriceQueryMsg
+    Custom {
+        contract_addr: String,
+    },
+}
+
+// Interface for Custom price sources
+#[cw_serde]
+pub enum PriceQueryMsg {
+    Price { base: String, quote: String },
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub price: Decimal256,
+    pub confidence: Option<Decimal256>,
+    pub publish_time: u64,
+}
+
+// Interface for PoolTwap price sources
+// - cumulative prices of base in quote (sum of price * seconds it held) as of each seconds_ago,
+//   the TWAP over a window is (cumulative now - cumulative window ago) / window
+#[cw_serde]
+pub enum PoolOracleQueryMsg {
+    CumulativePrices {
+        base: String,
+        quote: String,
+        seconds_ago: Vec<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct CumulativePricesResponse {
+    // one per seconds_ago, in the same order
+    pub cumulative_prices: Vec<Decimal256>,
+    // time the cumulative prices were observed at, the end of the TWAP window
+    // - staleness is measured from it, not from the last pool price change
+    pub observed_at: u64,
+}
+
+// kind is Decimal
+#[cw_serde]
+pub struct PriceVariable {
+    pub kind: VariableKind,
+    pub name: String,
+    pub encode: Encoding,
+    pub init_fn: PriceExpr,
+    pub reinitialize: bool,
+    pub value: Option<String>, //none if uninitialized
+    pub update_fn: Option<UpdateFn>,
+}